@@ -0,0 +1,116 @@
+// Recounts an exported poll without the bot: reads a ballot file, tallies it with the same code
+// the bot uses, and prints every round.
+use serde_json::json;
+use std::env;
+use std::process;
+use trusty_vote_core::import::ImportFormat;
+use trusty_vote_core::models::VotingMethod;
+use trusty_vote_core::voting::{self, PollResults};
+
+const USAGE: &str = "Usage: trusty-vote-tally <file> [--format csv|json|blt|abif] \
+                     [--method star|plurality|ranked|approval] [--seed <number>] [--output text|json]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let mut file = None;
+    let (mut format, mut method, mut seed) = (None, None, None);
+    let mut json_output = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().ok_or_else(|| format!("{} needs a value\n{}", arg, USAGE));
+        match arg.as_str() {
+            "--format" => {
+                let name = value()?;
+                format = Some(ImportFormat::parse(&name).ok_or_else(|| format!("Unknown format `{}`", name))?);
+            }
+            "--method" => {
+                let name = value()?;
+                method = Some(VotingMethod::parse(&name).ok_or_else(|| format!("Unknown voting method `{}`", name))?);
+            }
+            "--seed" => {
+                let number = value()?;
+                seed = Some(number.parse::<u64>().map_err(|_| format!("`{}` is not a seed", number))?);
+            }
+            "--output" => match value()?.as_str() {
+                "text" => json_output = false,
+                "json" => json_output = true,
+                other => return Err(format!("Unknown output `{}`\n{}", other, USAGE)),
+            },
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return Ok(());
+            }
+            _ if arg.starts_with("--") || file.is_some() => return Err(format!("Unexpected argument `{}`\n{}", arg, USAGE)),
+            _ => file = Some(arg.clone()),
+        }
+    }
+
+    let file = file.ok_or_else(|| format!("Missing the ballot file\n{}", USAGE))?;
+    let format = format
+        .or_else(|| ImportFormat::from_file_name(&file))
+        .ok_or("Unrecognized file type; pass --format")?;
+    let text = std::fs::read_to_string(&file).map_err(|e| format!("Failed to read {}: {}", file, e))?;
+
+    // JSON, BLT and ABIF exports carry the seed the bot announced its results with
+    let election = format.read(&text)?;
+    let seed = match seed.or(election.seed) {
+        Some(seed) => seed,
+        None => {
+            eprintln!("The file doesn't name a tie-break seed; breaking ties with seed 0. Pass --seed to match announced results.");
+            0
+        }
+    };
+    let imported = election.into_poll(String::new(), String::new(), String::new(), None, method)?;
+    let results = voting::tally(&imported.poll, &imported.votes, seed);
+
+    if json_output {
+        let report = json!({
+            "question": imported.poll.question,
+            "voting_method": imported.poll.voting_method.as_str(),
+            "ballots": imported.ballots,
+            "blank_ballots": imported.blank_ballots,
+            "seed": seed,
+            "results": results,
+        });
+        println!("{}", serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?);
+    } else {
+        print!("{}", text_report(&imported.poll.question, &imported.poll.voting_method, imported.ballots, imported.blank_ballots, &results));
+    }
+    Ok(())
+}
+
+fn text_report(question: &str, method: &VotingMethod, ballots: usize, blank_ballots: usize, results: &PollResults) -> String {
+    let mut report = format!(
+        "{}\nMethod: {}\nBallots: {} ({} blank)\nTie-break seed: {}\n",
+        question, method, ballots, blank_ballots, results.seed
+    );
+    for round in &results.rounds {
+        report.push_str(&format!("\n{} ({})\n", round.label, round.unit));
+        let width = round.counts.iter().map(|count| count.option_text.chars().count()).max().unwrap_or(0);
+        for count in &round.counts {
+            report.push_str(&format!("  {:>2}. {:<width$}  {}\n", count.rank, count.option_text, count.score, width = width));
+        }
+        if !round.eliminated.is_empty() {
+            let names: Vec<&str> = round
+                .eliminated
+                .iter()
+                .filter_map(|id| round.counts.iter().find(|count| &count.option_id == id))
+                .map(|count| count.option_text.as_str())
+                .collect();
+            report.push_str(&format!("  Eliminated: {}\n", names.join(", ")));
+        }
+        for note in &round.notes {
+            report.push_str(&format!("  {}\n", note));
+        }
+    }
+    report.push_str(&format!("\nWinner: {}\n", results.winner));
+    report
+}
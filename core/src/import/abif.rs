@@ -0,0 +1,147 @@
+use super::{option_index, BallotKind, ImportedBallot, ImportedElection, ImportedOption, MAX_IMPORTED_BALLOTS};
+use regex::Regex;
+
+// Aggregated Ballot Information Format. Reads `{title: "..."}` and `{tie_break_seed: N}` metadata,
+// candidate definitions (`=token:[Full Name]`) and ballot lines (`count:A>B=C` for rankings,
+// `count:A/5,B/3` or `count:A/5>B/3` for ratings). Candidates that aren't defined are named by
+// their token.
+// A file whose ballots carry ratings is read as rated, otherwise as ranked.
+pub fn read(text: &str) -> Result<ImportedElection, String> {
+    let title_pattern = Regex::new(r#"title\s*:\s*"((?:[^"\\]|\\.)*)""#).expect("valid regex");
+    let seed_pattern = Regex::new(r"^\{\s*tie_break_seed\s*:\s*(\d+)\s*\}$").expect("valid regex");
+    let candidate_pattern = Regex::new(r"^=\s*([^:\s]+)\s*:\s*(?:\[(.*)\]|(.*))$").expect("valid regex");
+
+    let mut title = None;
+    let mut seed = None;
+    let mut tokens: Vec<(String, usize)> = Vec::new();
+    let mut options: Vec<ImportedOption> = Vec::new();
+    let mut ranked: Vec<ImportedBallot> = Vec::new();
+    let mut rated: Vec<ImportedBallot> = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('{') {
+            if let Some(captures) = title_pattern.captures(line) {
+                title = Some(captures[1].replace("\\\"", "\"").replace("\\\\", "\\"));
+            }
+            if let Some(captures) = seed_pattern.captures(line) {
+                seed = captures[1].parse().ok();
+            }
+            continue;
+        }
+        if let Some(captures) = candidate_pattern.captures(line) {
+            let name = captures.get(2).or(captures.get(3)).map(|m| m.as_str().trim()).unwrap_or_default();
+            let index = option_index(&mut options, name);
+            tokens.push((captures[1].to_string(), index));
+            continue;
+        }
+
+        let error = |what: &str| format!("Line {}: {}", number + 1, what);
+        let (count, preferences) = line.split_once(':').ok_or_else(|| error("expected `count:preferences`"))?;
+        let count: usize = count.trim().parse().map_err(|_| error("the ballot count isn't a whole number"))?;
+        if count > MAX_IMPORTED_BALLOTS {
+            return Err(error("the ballot count is more than can be imported"));
+        }
+
+        // Split into candidates, remembering whether each one follows a `>`
+        let mut marks = Vec::new();
+        let mut has_ratings = false;
+        let mut rank = 1;
+        let mut rest = preferences.trim();
+        let mut candidate_count = 0;
+        while !rest.is_empty() {
+            let end = rest.find(['>', '=', ',']).unwrap_or(rest.len());
+            let (candidate, rating) = match rest[..end].trim().rsplit_once('/') {
+                Some((candidate, rating)) => {
+                    let rating: i32 = rating.trim().parse().map_err(|_| error("ratings must be whole numbers"))?;
+                    has_ratings = true;
+                    (candidate.trim(), Some(rating))
+                }
+                None => (rest[..end].trim(), None),
+            };
+            let candidate = candidate.trim_start_matches('[').trim_end_matches(']');
+            if candidate.is_empty() {
+                return Err(error("empty candidate"));
+            }
+            let index = match tokens.iter().find(|(token, _)| token == candidate) {
+                Some((_, index)) => *index,
+                None => option_index(&mut options, candidate),
+            };
+            marks.push((index, rank, rating));
+            candidate_count += 1;
+
+            if end < rest.len() && &rest[end..end + 1] == ">" {
+                rank = candidate_count + 1;
+            }
+            rest = rest.get(end + 1..).unwrap_or_default().trim();
+        }
+
+        if has_ratings {
+            if marks.iter().any(|(_, _, rating)| rating.is_none()) {
+                return Err(error("every candidate on a rated ballot needs a rating"));
+            }
+            rated.push(ImportedBallot {
+                count,
+                marks: marks.into_iter().map(|(index, _, rating)| (index, rating.unwrap_or_default())).collect(),
+            });
+        } else {
+            ranked.push(ImportedBallot { count, marks: marks.into_iter().map(|(index, rank, _)| (index, rank)).collect() });
+        }
+    }
+
+    let (kind, ballots) = match (ranked.is_empty(), rated.is_empty()) {
+        (_, true) => (BallotKind::Ranked, ranked),
+        (true, false) => (BallotKind::Rated, rated),
+        (false, false) => return Err("The ABIF file mixes ranked and rated ballots".to_string()),
+    };
+    Ok(ImportedElection { title, kind, method: None, seed, options, ballots })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_metadata_candidates_and_rankings() {
+        let election = read(
+            "{version: \"0.1\"}\n{title: \"Say \\\"hi\\\"\"}\n{tie_break_seed: 42}\n=O1:[Tacos]\n=O2:[Pizza]\n3:O2>O1 # comment\n1:O1=O2\n",
+        )
+        .unwrap();
+        assert_eq!(election.title.as_deref(), Some("Say \"hi\""));
+        assert_eq!(election.seed, Some(42));
+        assert_eq!(election.kind, BallotKind::Ranked);
+        let options: Vec<&str> = election.options.iter().map(|option| option.text.as_str()).collect();
+        assert_eq!(options, ["Tacos", "Pizza"]);
+        assert_eq!(election.ballots[0].count, 3);
+        assert_eq!(election.ballots[0].marks, [(1, 1), (0, 2)]);
+        assert_eq!(election.ballots[1].marks, [(0, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn reads_ratings_and_names_undefined_candidates_by_token() {
+        let election = read("2:A/5>B/3=C/3\n1:A/0,B/1,C/2\n").unwrap();
+        assert_eq!(election.kind, BallotKind::Rated);
+        assert_eq!(election.seed, None);
+        let options: Vec<&str> = election.options.iter().map(|option| option.text.as_str()).collect();
+        assert_eq!(options, ["A", "B", "C"]);
+        assert_eq!(election.ballots[0].marks, [(0, 5), (1, 3), (2, 3)]);
+    }
+
+    #[test]
+    fn seed_in_the_title_is_not_read_as_metadata() {
+        let election = read("{title: \"tie_break_seed: 7\"}\n1:A>B\n").unwrap();
+        assert_eq!(election.seed, None);
+    }
+
+    #[test]
+    fn refuses_mixed_and_malformed_ballots() {
+        assert!(read("1:A>B\n1:A/5,B/3\n").is_err());
+        assert!(read("1:A/5,B\n").is_err());
+        assert!(read("many:A>B\n").is_err());
+        assert!(read(&format!("{}:A>B\n", MAX_IMPORTED_BALLOTS + 1)).is_err());
+        assert!(read("18446744073709551616:A>B\n").is_err());
+    }
+}
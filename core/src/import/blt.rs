@@ -0,0 +1,111 @@
+use super::{BallotKind, ImportedBallot, ImportedElection, ImportedOption, MAX_IMPORTED_BALLOTS};
+
+// Comment `/poll export` adds after the title, naming the tie-breaking seed of the announced results
+pub const SEED_COMMENT: &str = "# tie_break_seed:";
+
+// OpenSTV's BLT format: `candidates seats`, optional withdrawn candidates as negative numbers,
+// ballot lines `weight c1 c2 ... 0` ending with a lone `0`, then a quoted name per candidate and
+// the quoted title. Only single-winner files with whole-number weights and no tied rankings fit
+// a poll.
+pub fn read(text: &str) -> Result<ImportedElection, String> {
+    let seed = text
+        .lines()
+        .find_map(|line| line.trim().strip_prefix(SEED_COMMENT))
+        .and_then(|seed| seed.trim().parse().ok());
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+    let header: Vec<usize> = lines
+        .next()
+        .ok_or("The BLT file is empty")?
+        .split_whitespace()
+        .map(|n| n.parse().map_err(|_| "The BLT file doesn't start with the candidate and seat counts".to_string()))
+        .collect::<Result<_, _>>()?;
+    let (candidates, seats) = match header.as_slice() {
+        [candidates, seats] => (*candidates, *seats),
+        _ => return Err("The BLT file doesn't start with the candidate and seat counts".to_string()),
+    };
+    if seats != 1 {
+        return Err(format!("The BLT file elects {} seats; polls have a single winner.", seats));
+    }
+
+    let mut withdrawn = Vec::new();
+    let mut ballots = Vec::new();
+    for line in lines.by_ref() {
+        if line == "0" {
+            break;
+        }
+        if line.starts_with('-') {
+            for number in line.split_whitespace() {
+                let number: usize = number.trim_start_matches('-').parse().map_err(|_| format!("Bad withdrawn candidate line `{}`", line))?;
+                withdrawn.push(number);
+            }
+            continue;
+        }
+
+        // An optional ballot ID in parentheses comes first
+        let mut fields = line.split_whitespace().skip_while(|field| field.starts_with('(')).peekable();
+        let weight: usize = fields
+            .next()
+            .and_then(|weight| weight.parse().ok())
+            .ok_or_else(|| format!("Ballot line `{}` needs a whole-number weight", line))?;
+        if weight > MAX_IMPORTED_BALLOTS {
+            return Err(format!("Ballot line `{}` has a weight of more than {}", line, MAX_IMPORTED_BALLOTS));
+        }
+        let mut marks = Vec::new();
+        for field in fields {
+            if field == "0" {
+                break;
+            }
+            if field.contains('=') {
+                return Err(format!("Ballot line `{}` ranks candidates equally, which ranked polls don't allow", line));
+            }
+            let number: usize = field
+                .parse()
+                .ok()
+                .filter(|number| (1..=candidates).contains(number))
+                .ok_or_else(|| format!("Ballot line `{}` names an unknown candidate `{}`", line, field))?;
+            marks.push((number - 1, marks.len() as i32 + 1));
+        }
+        if weight > 0 {
+            ballots.push(ImportedBallot { count: weight, marks });
+        }
+    }
+
+    let mut strings = lines.map(|line| line.trim_matches('"').to_string());
+    let options = (1..=candidates)
+        .map(|number| {
+            let text = strings.next().ok_or(format!("The BLT file is missing the name of candidate {}", number))?;
+            Ok(ImportedOption { text, withdrawn: withdrawn.contains(&number) })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let title = strings.next().filter(|title| !title.is_empty());
+
+    Ok(ImportedElection { title, kind: BallotKind::Ranked, method: None, seed, options, ballots })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_ballots_withdrawn_candidates_title_and_seed() {
+        let election = read("3 1\n-3\n4 1 2 0\n(b2) 2 2 0\n0 0\n0\n\"Tacos\"\n\"Pizza\"\n\"Sushi\"\n\"Lunch\"\n# tie_break_seed: 99\n").unwrap();
+        assert_eq!(election.title.as_deref(), Some("Lunch"));
+        assert_eq!(election.seed, Some(99));
+        let withdrawn: Vec<bool> = election.options.iter().map(|option| option.withdrawn).collect();
+        assert_eq!(withdrawn, [false, false, true]);
+        // The zero-weight ballot is dropped
+        assert_eq!(election.ballots.len(), 2);
+        assert_eq!((election.ballots[0].count, election.ballots[0].marks.clone()), (4, vec![(0, 1), (1, 2)]));
+        assert_eq!((election.ballots[1].count, election.ballots[1].marks.clone()), (2, vec![(1, 1)]));
+    }
+
+    #[test]
+    fn refuses_what_a_poll_cant_hold() {
+        assert!(read("2 2\n1 1 0\n0\n\"A\"\n\"B\"\n").is_err());
+        assert!(read("2 1\n1 1=2 0\n0\n\"A\"\n\"B\"\n").is_err());
+        assert!(read("2 1\n1 3 0\n0\n\"A\"\n\"B\"\n").is_err());
+        assert!(read("2 1\n1 1 0\n0\n\"A\"\n").is_err());
+        assert!(read(&format!("2 1\n{} 1 0\n0\n\"A\"\n\"B\"\n", MAX_IMPORTED_BALLOTS + 1)).is_err());
+    }
+}
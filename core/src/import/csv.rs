@@ -0,0 +1,251 @@
+use super::{option_index, BallotKind, ImportedBallot, ImportedElection, ImportedOption, MAX_IMPORTED_BALLOTS};
+use std::collections::HashMap;
+
+// Reads either CSV layout `/poll export` writes:
+// - long form, recognized by its `Option Number` column: one row per vote, grouped by voter
+// - wide form: one row per ballot. `Rank 1`, `Rank 2`, ... columns hold rankings, a `Choice`
+//   column a plurality pick, and any other column is an option whose cells are ratings.
+//   `Voter` and `Submitted` are ignored; an optional `Count` column repeats the row.
+pub fn read(text: &str) -> Result<ImportedElection, String> {
+    let mut rows = records(text)?.into_iter().filter(|row| row.iter().any(|field| !field.trim().is_empty()));
+    let header: Vec<String> = rows.next().ok_or("The CSV file is empty")?.iter().map(|h| h.trim().to_string()).collect();
+    let rows: Vec<Vec<String>> = rows.collect();
+
+    if header.iter().any(|h| h == "Option Number") {
+        long_form(&header, &rows)
+    } else {
+        wide_form(&header, &rows)
+    }
+}
+
+fn long_form(header: &[String], rows: &[Vec<String>]) -> Result<ImportedElection, String> {
+    let column = |name: &str| header.iter().position(|h| h == name).ok_or(format!("The CSV file has no `{}` column", name));
+    let (voter, number, text) = (column("User ID")?, column("Option Number")?, column("Option Text")?);
+    // The rating column is named after the method it was exported from
+    let (rating, kind) = [("Stars", BallotKind::Rated), ("Approved", BallotKind::Rated), ("Rank", BallotKind::Ranked), ("Selected", BallotKind::Choice)]
+        .into_iter()
+        .find_map(|(name, kind)| header.iter().position(|h| h == name).map(|i| (i, kind)))
+        .ok_or("The CSV file has no `Stars`, `Approved`, `Rank` or `Selected` column")?;
+
+    let mut numbered: Vec<(usize, String)> = Vec::new();
+    let mut ballots: Vec<Vec<(usize, i32)>> = Vec::new();
+    let mut ballot_of_voter: HashMap<&str, usize> = HashMap::new();
+    for (line, row) in rows.iter().enumerate() {
+        let field = |i: usize| row.get(i).map(|f| f.trim()).unwrap_or_default();
+        let error = |what: &str| format!("Row {}: {}", line + 2, what);
+        let number: usize = field(number).parse().ok().filter(|n| *n > 0).ok_or_else(|| error("bad option number"))?;
+        let value: i32 = field(rating).parse().map_err(|_| error("bad rating"))?;
+        if !numbered.iter().any(|(n, _)| *n == number) {
+            numbered.push((number, unescape(field(text))));
+        }
+        let ballot = *ballot_of_voter.entry(field(voter)).or_insert_with(|| {
+            ballots.push(Vec::new());
+            ballots.len() - 1
+        });
+        ballots[ballot].push((number, value));
+    }
+
+    // Option numbers come from the poll embed; renumber them from 0 in that order
+    numbered.sort_by_key(|(number, _)| *number);
+    let index: HashMap<usize, usize> = numbered.iter().enumerate().map(|(i, (number, _))| (*number, i)).collect();
+    let options = numbered.into_iter().map(|(_, text)| exported_option(text)).collect();
+    let ballots = ballots
+        .into_iter()
+        .map(|marks| ImportedBallot {
+            count: 1,
+            marks: marks
+                .into_iter()
+                // Zero ranks and unselected options are the UI's way of saying "not marked"
+                .filter(|(_, value)| kind == BallotKind::Rated || *value != 0)
+                .map(|(number, value)| (index[&number], value))
+                .collect(),
+        })
+        .collect();
+
+    Ok(ImportedElection { title: None, kind, method: None, seed: None, options, ballots })
+}
+
+fn wide_form(header: &[String], rows: &[Vec<String>]) -> Result<ImportedElection, String> {
+    let count_column = header.iter().position(|h| h.eq_ignore_ascii_case("Count"));
+    let ignored = |h: &str| ["Voter", "Submitted", "Count"].iter().any(|name| h.eq_ignore_ascii_case(name));
+    let rank_columns: Vec<(usize, i32)> = header
+        .iter()
+        .enumerate()
+        .filter_map(|(i, h)| h.strip_prefix("Rank ").and_then(|rank| rank.trim().parse().ok()).map(|rank| (i, rank)))
+        .collect();
+    let choice_column = header.iter().position(|h| h == "Choice");
+
+    let mut options: Vec<ImportedOption> = Vec::new();
+    let mut ballots = Vec::new();
+    let kind = if !rank_columns.is_empty() {
+        BallotKind::Ranked
+    } else if choice_column.is_some() {
+        BallotKind::Choice
+    } else {
+        BallotKind::Rated
+    };
+
+    // Rated layouts have an option per column
+    let rated_columns: Vec<usize> = if kind == BallotKind::Rated {
+        header
+            .iter()
+            .enumerate()
+            .filter(|(_, h)| !ignored(h))
+            .map(|(i, h)| {
+                options.push(exported_option(unescape(h)));
+                i
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    for (line, row) in rows.iter().enumerate() {
+        let field = |i: usize| row.get(i).map(|f| f.trim()).unwrap_or_default();
+        let error = |what: String| format!("Row {}: {}", line + 2, what);
+        let count = match count_column {
+            Some(i) => field(i).parse().ok().filter(|count| (1..=MAX_IMPORTED_BALLOTS).contains(count)).ok_or_else(|| error("bad count".to_string()))?,
+            None => 1,
+        };
+
+        let marks = match kind {
+            BallotKind::Ranked => rank_columns
+                .iter()
+                .filter(|(i, _)| !field(*i).is_empty())
+                .map(|(i, rank)| (cell_option(&mut options, field(*i)), *rank))
+                .collect(),
+            BallotKind::Choice => {
+                let choice = field(choice_column.unwrap_or_default());
+                if choice.is_empty() { Vec::new() } else { vec![(cell_option(&mut options, choice), 1)] }
+            }
+            BallotKind::Rated => rated_columns
+                .iter()
+                .enumerate()
+                .filter(|(_, column)| !field(**column).is_empty())
+                .map(|(option, column)| {
+                    field(*column)
+                        .parse()
+                        .map(|rating| (option, rating))
+                        .map_err(|_| error(format!("`{}` is not a rating", field(*column))))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        ballots.push(ImportedBallot { count, marks });
+    }
+
+    Ok(ImportedElection { title: None, kind, method: None, seed: None, options, ballots })
+}
+
+// Exports mark withdrawn options by appending ` (withdrawn)` to their text
+fn exported_option(text: String) -> ImportedOption {
+    match text.strip_suffix(" (withdrawn)") {
+        Some(text) => ImportedOption { text: text.to_string(), withdrawn: true },
+        None => ImportedOption { text, withdrawn: false },
+    }
+}
+
+// Index of the option a rank or choice cell names, adding it if it's new
+fn cell_option(options: &mut Vec<ImportedOption>, cell: &str) -> usize {
+    let option = exported_option(unescape(cell));
+    let index = option_index(options, &option.text);
+    options[index].withdrawn |= option.withdrawn;
+    index
+}
+
+// Exports prefix formula-like text with `'` so spreadsheets don't evaluate it
+fn unescape(field: &str) -> String {
+    match field.strip_prefix('\'') {
+        Some(rest) if rest.starts_with(['=', '+', '-', '@']) => rest.to_string(),
+        _ => field.to_string(),
+    }
+}
+
+// RFC 4180 records: quoted fields may contain commas, doubled quotes and line breaks
+fn records(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\r', false) => {}
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("The CSV file ends inside a quoted field".to_string());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn option_texts(election: &ImportedElection) -> Vec<(&str, bool)> {
+        election.options.iter().map(|option| (option.text.as_str(), option.withdrawn)).collect()
+    }
+
+    #[test]
+    fn reads_the_long_form_grouped_by_voter() {
+        let election = read(
+            "User ID,Option Number,Option ID,Option Text,Rank,Timestamp\n\
+             u1,2,b,Pizza,1,t\n\
+             u1,1,a,Tacos (withdrawn),2,t\n\
+             u2,1,a,Tacos (withdrawn),0,t\n\
+             u2,2,b,Pizza,1,t\n",
+        )
+        .unwrap();
+        assert_eq!(election.kind, BallotKind::Ranked);
+        assert_eq!(option_texts(&election), [("Tacos", true), ("Pizza", false)]);
+        assert_eq!(election.ballots[0].marks, [(1, 1), (0, 2)]);
+        // Zero ranks are left off
+        assert_eq!(election.ballots[1].marks, [(1, 1)]);
+    }
+
+    #[test]
+    fn reads_wide_rated_rows_with_counts_and_quoted_headers() {
+        let election = read("Voter,Submitted,\"Fish, chips\",'=Soup,Count\nv1,t,5,,3\nv2,t,0,2,1\n").unwrap();
+        assert_eq!(election.kind, BallotKind::Rated);
+        assert_eq!(option_texts(&election), [("Fish, chips", false), ("=Soup", false)]);
+        assert_eq!((election.ballots[0].count, election.ballots[0].marks.clone()), (3, vec![(0, 5)]));
+        assert_eq!(election.ballots[1].marks, [(0, 0), (1, 2)]);
+    }
+
+    #[test]
+    fn reads_wide_rankings_and_choices() {
+        let ranked = read("Voter,Submitted,Rank 1,Rank 2\nv1,t,Pizza,Tacos (withdrawn)\nv2,t,,\n").unwrap();
+        assert_eq!(ranked.kind, BallotKind::Ranked);
+        assert_eq!(option_texts(&ranked), [("Pizza", false), ("Tacos", true)]);
+        assert_eq!(ranked.ballots[0].marks, [(0, 1), (1, 2)]);
+        assert!(ranked.ballots[1].marks.is_empty());
+
+        let choice = read("Voter,Submitted,Choice\nv1,t,Pizza\nv2,t,Tacos\nv3,t,Pizza\n").unwrap();
+        assert_eq!(choice.kind, BallotKind::Choice);
+        assert_eq!(choice.ballots[2].marks, [(0, 1)]);
+    }
+
+    #[test]
+    fn refuses_bad_counts_and_ratings() {
+        assert!(read("Voter,A,Count\nv1,5,0\n").is_err());
+        assert!(read(&format!("Voter,A,Count\nv1,5,{}\n", MAX_IMPORTED_BALLOTS + 1)).is_err());
+        assert!(read("Voter,A\nv1,lots\n").is_err());
+        assert!(read("").is_err());
+    }
+}
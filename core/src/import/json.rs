@@ -0,0 +1,99 @@
+use super::{BallotKind, ImportedBallot, ImportedElection, ImportedOption};
+use crate::models::VotingMethod;
+use serde_json::Value;
+
+// The JSON `/poll export` writes. Unlike the other formats it names the voting method and the
+// tie-breaking seed the results were announced with, so a recount can match them exactly.
+pub fn read(text: &str) -> Result<ImportedElection, String> {
+    let export: Value = serde_json::from_str(text).map_err(|e| format!("The JSON file can't be read: {}", e))?;
+    let poll = export.get("poll").ok_or("The JSON file has no `poll`")?;
+
+    let method = poll
+        .get("voting_method")
+        .and_then(Value::as_str)
+        .and_then(VotingMethod::parse)
+        .ok_or("The JSON file has no known `voting_method`")?;
+    let kind = match method {
+        VotingMethod::Ranked => BallotKind::Ranked,
+        VotingMethod::Plurality => BallotKind::Choice,
+        VotingMethod::Star | VotingMethod::Approval => BallotKind::Rated,
+    };
+
+    let mut option_ids = Vec::new();
+    let mut options = Vec::new();
+    for option in poll.get("options").and_then(Value::as_array).ok_or("The JSON file has no `options`")? {
+        let id = option.get("id").and_then(Value::as_str).ok_or("An option has no `id`")?;
+        let text = option.get("text").and_then(Value::as_str).ok_or("An option has no `text`")?;
+        let withdrawn = option.get("withdrawn").and_then(Value::as_bool).unwrap_or(false);
+        option_ids.push(id.to_string());
+        options.push(ImportedOption { text: text.to_string(), withdrawn });
+    }
+
+    let mut ballots = Vec::new();
+    for (i, ballot) in export.get("ballots").and_then(Value::as_array).ok_or("The JSON file has no `ballots`")?.iter().enumerate() {
+        let ratings = ballot
+            .get("ratings")
+            .and_then(Value::as_object)
+            .ok_or_else(|| format!("Ballot {} has no `ratings`", i + 1))?;
+        let mut marks = Vec::new();
+        for (option_id, rating) in ratings {
+            let option = option_ids
+                .iter()
+                .position(|id| id == option_id)
+                .ok_or_else(|| format!("Ballot {} rates an unknown option `{}`", i + 1, option_id))?;
+            let rating = rating
+                .as_i64()
+                .and_then(|rating| i32::try_from(rating).ok())
+                .ok_or_else(|| format!("Ballot {} has a rating that isn't a whole number", i + 1))?;
+            marks.push((option, rating));
+        }
+        marks.sort_unstable();
+        ballots.push(ImportedBallot { count: 1, marks });
+    }
+
+    Ok(ImportedElection {
+        title: poll.get("question").and_then(Value::as_str).map(str::to_string),
+        kind,
+        method: Some(method),
+        seed: export.get("results").and_then(|results| results.get("seed")).and_then(Value::as_u64),
+        options,
+        ballots,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_method_seed_options_and_ballots_by_option_id() {
+        let election = read(
+            r#"{
+                "poll": {
+                    "question": "Lunch?",
+                    "voting_method": "star",
+                    "options": [{"id": "a", "text": "Tacos"}, {"id": "b", "text": "Pizza", "withdrawn": true}]
+                },
+                "results": {"seed": 12345},
+                "ballots": [{"ratings": {"b": 3, "a": 5}}, {"ratings": {}}]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(election.title.as_deref(), Some("Lunch?"));
+        assert!(matches!(election.method, Some(VotingMethod::Star)));
+        assert_eq!(election.kind, BallotKind::Rated);
+        assert_eq!(election.seed, Some(12345));
+        assert!(election.options[1].withdrawn);
+        assert_eq!(election.ballots[0].marks, [(0, 5), (1, 3)]);
+        assert!(election.ballots[1].marks.is_empty());
+    }
+
+    #[test]
+    fn refuses_unknown_options_and_methods() {
+        let poll = r#""poll": {"voting_method": "star", "options": [{"id": "a", "text": "Tacos"}]}"#;
+        assert!(read(&format!(r#"{{{}, "ballots": [{{"ratings": {{"z": 1}}}}]}}"#, poll)).is_err());
+        assert!(read(&format!(r#"{{{}, "ballots": [{{"ratings": {{"a": 1.5}}}}]}}"#, poll)).is_err());
+        assert!(read(r#"{"poll": {"voting_method": "borda", "options": []}, "ballots": []}"#).is_err());
+        assert!(read("not json").is_err());
+    }
+}
@@ -0,0 +1,344 @@
+pub mod abif;
+pub mod blt;
+pub mod csv;
+pub mod json;
+
+use crate::models::{Poll, PollOrigin, PollStatus, Vote, VotingMethod, MAX_OPTIONS_LIMIT, MAX_OPTION_LENGTH, MAX_QUESTION_LENGTH};
+use crate::voting::validate_ballot;
+use chrono::Utc;
+
+// Keeps an import within what one transaction and one results tally handle comfortably
+pub const MAX_IMPORTED_BALLOTS: usize = 50_000;
+
+// Files `/poll import`, `trusty-vote import` and `trusty-vote-tally` can read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Csv,  // The CSV layouts `/poll export` writes, one row per vote or per ballot
+    Json, // The JSON `/poll export` writes
+    Blt,  // OpenSTV ballot files, single winner
+    Abif, // Aggregated Ballot Information Format
+}
+
+impl ImportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "csv" => Some(ImportFormat::Csv),
+            "json" => Some(ImportFormat::Json),
+            "blt" => Some(ImportFormat::Blt),
+            "abif" => Some(ImportFormat::Abif),
+            _ => None,
+        }
+    }
+
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        let (_, extension) = file_name.rsplit_once('.')?;
+        Self::parse(&extension.to_lowercase())
+    }
+
+    pub fn read(&self, text: &str) -> Result<ImportedElection, String> {
+        let text = text.trim_start_matches('\u{feff}');
+        match self {
+            ImportFormat::Csv => csv::read(text),
+            ImportFormat::Json => json::read(text),
+            ImportFormat::Blt => blt::read(text),
+            ImportFormat::Abif => abif::read(text),
+        }
+    }
+}
+
+// What kind of marks the file's ballots carry, which limits the methods they can be counted with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BallotKind {
+    Ranked, // Preference orders
+    Rated,  // Stars or approvals per option
+    Choice, // A single pick
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportedOption {
+    pub text: String,
+    pub withdrawn: bool,
+}
+
+// A group of identical ballots. `marks` are (option index, rating), ranks for ranked ballots.
+#[derive(Debug, Clone)]
+pub struct ImportedBallot {
+    pub count: usize,
+    pub marks: Vec<(usize, i32)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportedElection {
+    pub title: Option<String>,
+    pub kind: BallotKind,
+    pub method: Option<VotingMethod>, // Method the file says the ballots were cast for
+    pub seed: Option<u64>,            // Tie-breaking seed of the results the file was exported with
+    pub options: Vec<ImportedOption>,
+    pub ballots: Vec<ImportedBallot>,
+}
+
+// The closed poll built from an import, its votes, and how many ballots were blank
+pub struct ImportedPoll {
+    pub poll: Poll,
+    pub votes: Vec<Vote>,
+    pub ballots: usize,
+    pub blank_ballots: usize,
+}
+
+impl ImportedElection {
+    // The file's own method, otherwise the one the ballots fit: rated ballots with only 0 and 1
+    // are approvals
+    pub fn default_method(&self) -> VotingMethod {
+        if let Some(method) = &self.method {
+            return method.clone();
+        }
+        match self.kind {
+            BallotKind::Ranked => VotingMethod::Ranked,
+            BallotKind::Choice => VotingMethod::Plurality,
+            BallotKind::Rated => {
+                let approvals = self
+                    .ballots
+                    .iter()
+                    .all(|ballot| ballot.marks.iter().all(|(_, rating)| (0..=1).contains(rating)));
+                if approvals { VotingMethod::Approval } else { VotingMethod::Star }
+            }
+        }
+    }
+
+    // A closed poll holding the ballots, checked against the method's ballot rules. Each ballot
+    // gets a synthetic voter ID (`imported-1`, ...), since the file doesn't identify voters.
+    pub fn into_poll(
+        self,
+        guild_id: String,
+        channel_id: String,
+        creator_id: String,
+        question: Option<String>,
+        method: Option<VotingMethod>,
+    ) -> Result<ImportedPoll, String> {
+        let method = method.unwrap_or_else(|| self.default_method());
+        let compatible = match self.kind {
+            // Plurality exports to ranked-only formats write each pick as a one-option ranking
+            BallotKind::Ranked => {
+                matches!(method, VotingMethod::Ranked)
+                    || (matches!(method, VotingMethod::Plurality) && self.ballots.iter().all(|ballot| ballot.marks.len() <= 1))
+            }
+            BallotKind::Rated => matches!(method, VotingMethod::Star | VotingMethod::Approval),
+            BallotKind::Choice => matches!(method, VotingMethod::Plurality),
+        };
+        if !compatible {
+            let kind = match self.kind {
+                BallotKind::Ranked => "rankings",
+                BallotKind::Rated => "ratings",
+                BallotKind::Choice => "single choices",
+            };
+            return Err(format!("The file holds {}, which can't be counted as a {} poll.", kind, method));
+        }
+
+        if self.options.len() < 2 {
+            return Err("The file needs at least 2 options.".to_string());
+        }
+        if self.options.len() > MAX_OPTIONS_LIMIT {
+            return Err(format!("The file has {} options; polls can have at most {}.", self.options.len(), MAX_OPTIONS_LIMIT));
+        }
+        if let Some(option) = self.options.iter().find(|option| option.text.chars().count() > MAX_OPTION_LENGTH) {
+            return Err(format!(
+                "Option \"{}...\" is longer than {} characters.",
+                option.text.chars().take(40).collect::<String>(),
+                MAX_OPTION_LENGTH
+            ));
+        }
+        // Counts come straight from the file, so add them up without trusting them to fit
+        let too_many = || format!("The file has more than {} ballots, the most that can be imported.", MAX_IMPORTED_BALLOTS);
+        let mut total: usize = 0;
+        for ballot in &self.ballots {
+            total = total.checked_add(ballot.count).filter(|total| *total <= MAX_IMPORTED_BALLOTS).ok_or_else(too_many)?;
+        }
+        for (i, ballot) in self.ballots.iter().enumerate() {
+            if ballot.marks.iter().any(|(_, rating)| *rating != 0) {
+                validate_ballot(&method, &ballot.marks, self.options.len())
+                    .map_err(|e| format!("Ballot {} is invalid for a {} poll: {}.", i + 1, method, e))?;
+            }
+        }
+
+        // A question given for the import has to fit; the file's own title is shortened instead
+        let question = match question.map(|question| question.trim().to_string()).filter(|question| !question.is_empty()) {
+            Some(question) if question.chars().count() > MAX_QUESTION_LENGTH => {
+                return Err(format!("The question can be at most {} characters.", MAX_QUESTION_LENGTH));
+            }
+            Some(question) => question,
+            None => match self.title.as_deref().map(str::trim).filter(|title| !title.is_empty()) {
+                Some(title) if title.chars().count() > MAX_QUESTION_LENGTH => {
+                    format!("{}...", title.chars().take(MAX_QUESTION_LENGTH - 3).collect::<String>())
+                }
+                Some(title) => title.to_string(),
+                None => "Imported election".to_string(),
+            },
+        };
+        let mut poll = Poll::new(
+            guild_id,
+            channel_id,
+            creator_id.clone(),
+            question,
+            self.options.iter().map(|option| option.text.clone()).collect(),
+            method,
+            Some(0),
+            None,
+        );
+        for (option, imported) in poll.options.iter_mut().zip(&self.options) {
+            option.withdrawn = imported.withdrawn;
+        }
+        let now = Utc::now();
+        poll.status = PollStatus::Closed;
+        poll.origin = PollOrigin::Imported;
+        poll.closed_at = Some(now);
+        poll.closed_by = Some(creator_id);
+
+        let mut votes = Vec::new();
+        let mut ballots = 0;
+        let mut blank_ballots = 0;
+        let mut voter = 0;
+        for ballot in &self.ballots {
+            // Blank ballots don't count towards any option, but those with explicit zeros are kept:
+            // STAR and approval count them as voters, as they do for members who cleared their votes
+            if !ballot.marks.iter().any(|(_, rating)| *rating != 0) {
+                blank_ballots += ballot.count;
+            } else {
+                ballots += ballot.count;
+            }
+            for _ in 0..ballot.count {
+                voter += 1;
+                let user_id = format!("imported-{}", voter);
+                votes.extend(ballot.marks.iter().map(|(option, rating)| Vote {
+                    user_id: user_id.clone(),
+                    poll_id: poll.id.clone(),
+                    option_id: poll.options[*option].id.clone(),
+                    rating: *rating,
+                    timestamp: now,
+                }));
+            }
+        }
+        if ballots == 0 {
+            return Err("The file has no ballots that mark any option.".to_string());
+        }
+
+        Ok(ImportedPoll { poll, votes, ballots, blank_ballots })
+    }
+}
+
+// Index of the option with this text, adding it if it's new
+fn option_index(options: &mut Vec<ImportedOption>, text: &str) -> usize {
+    match options.iter().position(|option| option.text == text) {
+        Some(i) => i,
+        None => {
+            options.push(ImportedOption { text: text.to_string(), withdrawn: false });
+            options.len() - 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn election(kind: BallotKind, options: &[&str], ballots: Vec<ImportedBallot>) -> ImportedElection {
+        ImportedElection {
+            title: None,
+            kind,
+            method: None,
+            seed: None,
+            options: options.iter().map(|text| ImportedOption { text: text.to_string(), withdrawn: false }).collect(),
+            ballots,
+        }
+    }
+
+    fn ballot(count: usize, marks: &[(usize, i32)]) -> ImportedBallot {
+        ImportedBallot { count, marks: marks.to_vec() }
+    }
+
+    fn import(election: ImportedElection, question: Option<&str>, method: Option<VotingMethod>) -> Result<ImportedPoll, String> {
+        election.into_poll("guild".to_string(), "channel".to_string(), "creator".to_string(), question.map(str::to_string), method)
+    }
+
+    #[test]
+    fn expands_ballots_into_closed_poll_votes() {
+        let ballots = vec![ballot(2, &[(0, 1), (1, 2)]), ballot(1, &[]), ballot(1, &[(1, 1)])];
+        let imported = import(election(BallotKind::Ranked, &["Tacos", "Pizza"], ballots), None, None).unwrap();
+        assert_eq!((imported.ballots, imported.blank_ballots), (3, 1));
+        assert_eq!(imported.votes.len(), 5);
+        assert_eq!(imported.poll.question, "Imported election");
+        assert_eq!(imported.poll.status, PollStatus::Closed);
+        assert_eq!(imported.poll.origin, PollOrigin::Imported);
+        let voters: std::collections::HashSet<&str> = imported.votes.iter().map(|vote| vote.user_id.as_str()).collect();
+        assert_eq!(voters.len(), 3);
+    }
+
+    #[test]
+    fn refuses_ballot_totals_over_the_limit_without_overflowing() {
+        let too_many = vec![ballot(MAX_IMPORTED_BALLOTS, &[(0, 1)]), ballot(1, &[(1, 1)])];
+        assert!(import(election(BallotKind::Ranked, &["A", "B"], too_many), None, None).is_err());
+
+        // Counts that wrap around when added must not slip under the limit
+        let wrapping = vec![ballot(usize::MAX, &[(0, 1)]), ballot(2, &[(1, 1)])];
+        assert!(import(election(BallotKind::Ranked, &["A", "B"], wrapping), None, None).is_err());
+
+        let at_limit = vec![ballot(MAX_IMPORTED_BALLOTS, &[(0, 1)])];
+        assert!(import(election(BallotKind::Ranked, &["A", "B"], at_limit), None, None).is_ok());
+    }
+
+    #[test]
+    fn checks_option_count_and_lengths() {
+        let one_option = election(BallotKind::Ranked, &["A"], vec![ballot(1, &[(0, 1)])]);
+        assert!(import(one_option, None, None).is_err());
+
+        let texts: Vec<String> = (0..=MAX_OPTIONS_LIMIT).map(|i| format!("Option {}", i)).collect();
+        let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let too_many_options = election(BallotKind::Ranked, &texts, vec![ballot(1, &[(0, 1)])]);
+        assert!(import(too_many_options, None, None).is_err());
+
+        let long_option = "x".repeat(MAX_OPTION_LENGTH + 1);
+        let long = election(BallotKind::Ranked, &["A", &long_option], vec![ballot(1, &[(0, 1)])]);
+        assert!(import(long, None, None).is_err());
+    }
+
+    #[test]
+    fn refuses_a_long_question_but_shortens_a_long_title() {
+        let long = "q".repeat(MAX_QUESTION_LENGTH + 1);
+        let ballots = || vec![ballot(1, &[(0, 1)])];
+        assert!(import(election(BallotKind::Ranked, &["A", "B"], ballots()), Some(&long), None).is_err());
+
+        let mut titled = election(BallotKind::Ranked, &["A", "B"], ballots());
+        titled.title = Some(long);
+        let question = import(titled, None, None).unwrap().poll.question;
+        assert_eq!(question.chars().count(), MAX_QUESTION_LENGTH);
+        assert!(question.ends_with("..."));
+
+        let mut titled = election(BallotKind::Ranked, &["A", "B"], ballots());
+        titled.title = Some("  Lunch  ".to_string());
+        assert_eq!(import(titled, Some(" "), None).unwrap().poll.question, "Lunch");
+    }
+
+    #[test]
+    fn checks_ballots_against_the_method() {
+        let tied_ranks = vec![ballot(1, &[(0, 1), (1, 1)])];
+        assert!(import(election(BallotKind::Ranked, &["A", "B"], tied_ranks), None, None).is_err());
+
+        let rankings = || vec![ballot(1, &[(0, 1), (1, 2)])];
+        assert!(import(election(BallotKind::Ranked, &["A", "B"], rankings()), None, Some(VotingMethod::Star)).is_err());
+        assert!(import(election(BallotKind::Ranked, &["A", "B"], rankings()), None, Some(VotingMethod::Plurality)).is_err());
+
+        // One-option rankings are how ranked-only formats hold plurality picks
+        let picks = vec![ballot(1, &[(0, 1)]), ballot(1, &[(1, 1)])];
+        assert!(import(election(BallotKind::Ranked, &["A", "B"], picks), None, Some(VotingMethod::Plurality)).is_ok());
+
+        let blank = vec![ballot(3, &[])];
+        assert!(import(election(BallotKind::Ranked, &["A", "B"], blank), None, None).is_err());
+    }
+
+    #[test]
+    fn picks_a_default_method_from_the_ballots() {
+        let approvals = election(BallotKind::Rated, &["A", "B"], vec![ballot(1, &[(0, 1), (1, 0)])]);
+        assert!(matches!(approvals.default_method(), VotingMethod::Approval));
+        let stars = election(BallotKind::Rated, &["A", "B"], vec![ballot(1, &[(0, 4), (1, 0)])]);
+        assert!(matches!(stars.default_method(), VotingMethod::Star));
+    }
+}
@@ -0,0 +1,6 @@
+// Poll models, vote tallying and ballot file readers. The bot and the `trusty-vote-tally`
+// binary both count votes through `voting::tally`, so a count re-run from an exported file
+// matches the one the bot announced.
+pub mod import;
+pub mod models;
+pub mod voting;
@@ -0,0 +1,623 @@
+use chrono::{DateTime, Datelike, Duration, NaiveTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Poll {
+    pub id: String,
+    pub guild_id: String,
+    pub channel_id: String,
+    pub creator_id: String,
+    pub question: String,
+    pub options: Vec<PollOption>,
+    pub voting_method: VotingMethod,
+    pub created_at: DateTime<Utc>,
+    pub ends_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub starts_at: Option<DateTime<Utc>>, // Scheduled opening time, if voting doesn't open at creation
+    pub status: PollStatus,
+    pub message_id: Option<String>, // Added message_id
+    pub allowed_roles: Option<Vec<String>>, // Restrict voting to these role IDs (if set)
+    pub anonymous: bool, // Hide voter IDs in exports
+    #[serde(default)]
+    pub results_visibility: ResultsVisibility, // Who sees the tally before the poll closes
+    #[serde(default)]
+    pub origin: PollOrigin,
+    pub closed_at: Option<DateTime<Utc>>, // When the poll actually ended
+    pub closed_by: Option<String>, // User ID of whoever ended it, or SCHEDULER_ACTOR
+    pub close_reason: Option<CloseReason>,
+    #[serde(default)]
+    pub edits: Vec<PollEdit>, // Audit log of `/poll edit` changes, oldest first
+}
+
+// One change made to a poll after it was created
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollEdit {
+    pub actor_id: String,
+    pub action: String,  // e.g. "end_time", "rename_option"
+    pub details: String, // Human-readable description shown with the results
+    pub created_at: DateTime<Utc>,
+}
+
+// Poll length when neither the command nor the server settings give one
+pub const DEFAULT_DURATION_MINUTES: i64 = 1440;
+
+// Option cap when the server hasn't configured one, and the most the voting UI can show
+pub const DEFAULT_MAX_OPTIONS: usize = 10;
+pub const MAX_OPTIONS_LIMIT: usize = 20;
+
+// Longest question and option text, in characters. Questions go into embed titles (Discord allows
+// 256, with room left for "Results: "), options into select menu labels (Discord allows 100).
+pub const MAX_QUESTION_LENGTH: usize = 200;
+pub const MAX_OPTION_LENGTH: usize = 100;
+
+// Recorded as `closed_by` when the background task ends a poll
+pub const SCHEDULER_ACTOR: &str = "scheduler";
+
+// A poll closed this long after its end time is reported as closed late
+pub const LATE_CLOSE_GRACE_SECONDS: i64 = 120;
+
+// Prefix for pseudonymous voter IDs written by retention and privacy deletion
+pub const ANONYMOUS_VOTER_PREFIX: &str = "anon:";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollOption {
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub withdrawn: bool, // Withdrawn with `/poll edit`; kept so existing ballots stay valid
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum VotingMethod {
+    Star,
+    Plurality,
+    Ranked,
+    Approval,
+}
+
+impl VotingMethod {
+    // Value stored in the database and used in command choices
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VotingMethod::Star => "star",
+            VotingMethod::Plurality => "plurality",
+            VotingMethod::Ranked => "ranked",
+            VotingMethod::Approval => "approval",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "star" => Some(VotingMethod::Star),
+            "plurality" => Some(VotingMethod::Plurality),
+            "ranked" => Some(VotingMethod::Ranked),
+            "approval" => Some(VotingMethod::Approval),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for VotingMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VotingMethod::Star => write!(f, "STAR"),
+            VotingMethod::Plurality => write!(f, "Plurality"),
+            VotingMethod::Ranked => write!(f, "Ranked Choice"),
+            VotingMethod::Approval => write!(f, "Approval"),
+        }
+    }
+}
+
+// Lifecycle of a poll. Storage only accepts the transitions allowed by `can_transition_to`:
+//   draft -> scheduled | open
+//   scheduled -> open | closed
+//   open <-> paused, and both -> closed
+//   closed -> open (reopen) | archived
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PollStatus {
+    Draft,     // Stored, but its message hasn't been posted yet
+    Scheduled, // Posted as a preview; voting opens at `starts_at`
+    Open,      // Accepting votes
+    Paused,    // Frozen by a moderator; the end time still applies
+    Closed,    // Ended; results announced
+    Archived,  // Closed for good: ballots anonymized or removed by retention, can't be reopened
+}
+
+impl PollStatus {
+    pub const ALL: [PollStatus; 6] = [
+        PollStatus::Draft,
+        PollStatus::Scheduled,
+        PollStatus::Open,
+        PollStatus::Paused,
+        PollStatus::Closed,
+        PollStatus::Archived,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PollStatus::Draft => "draft",
+            PollStatus::Scheduled => "scheduled",
+            PollStatus::Open => "open",
+            PollStatus::Paused => "paused",
+            PollStatus::Closed => "closed",
+            PollStatus::Archived => "archived",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        PollStatus::ALL.into_iter().find(|status| status.as_str() == value)
+    }
+
+    pub fn can_transition_to(&self, next: PollStatus) -> bool {
+        use PollStatus::*;
+        matches!(
+            (self, next),
+            (Draft, Scheduled | Open)
+                | (Scheduled, Open | Closed)
+                | (Open, Paused | Closed)
+                | (Paused, Open | Closed)
+                | (Closed, Open | Archived)
+        )
+    }
+
+    // Every state `next` can be reached from
+    pub fn sources_of(next: PollStatus) -> Vec<PollStatus> {
+        PollStatus::ALL.into_iter().filter(|status| status.can_transition_to(next)).collect()
+    }
+
+    // Posted and not closed yet: listed as active, can be edited and ended
+    pub fn is_active(&self) -> bool {
+        matches!(self, PollStatus::Scheduled | PollStatus::Open | PollStatus::Paused)
+    }
+
+    pub fn accepts_votes(&self) -> bool {
+        *self == PollStatus::Open
+    }
+}
+
+impl fmt::Display for PollStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PollStatus::Draft => write!(f, "Draft"),
+            PollStatus::Scheduled => write!(f, "Scheduled"),
+            PollStatus::Open => write!(f, "Open"),
+            PollStatus::Paused => write!(f, "Paused"),
+            PollStatus::Closed => write!(f, "Closed"),
+            PollStatus::Archived => write!(f, "Archived"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CloseReason {
+    Expired, // Reached its scheduled end time
+    Manual,  // Ended early with `/poll end`
+}
+
+impl CloseReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CloseReason::Expired => "expired",
+            CloseReason::Manual => "manual",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "expired" => Some(CloseReason::Expired),
+            "manual" => Some(CloseReason::Manual),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CloseReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CloseReason::Expired => write!(f, "Reached end time"),
+            CloseReason::Manual => write!(f, "Ended manually"),
+        }
+    }
+}
+
+// Who can see a poll's tally while voting is still going on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ResultsVisibility {
+    #[default]
+    Hidden,    // Nobody until the poll closes
+    AfterVote, // Members who have voted, with `/poll results` and on their vote confirmation
+    Live,      // Everyone; the poll message shows a running tally
+}
+
+impl ResultsVisibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResultsVisibility::Hidden => "hidden",
+            ResultsVisibility::AfterVote => "after_vote",
+            ResultsVisibility::Live => "live",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "hidden" => Some(ResultsVisibility::Hidden),
+            "after_vote" => Some(ResultsVisibility::AfterVote),
+            "live" => Some(ResultsVisibility::Live),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ResultsVisibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResultsVisibility::Hidden => write!(f, "Hidden until the poll closes"),
+            ResultsVisibility::AfterVote => write!(f, "Visible to voters after they vote"),
+            ResultsVisibility::Live => write!(f, "Live on the poll message"),
+        }
+    }
+}
+
+// Where a poll's ballots came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PollOrigin {
+    #[default]
+    Discord,  // Voted on in Discord
+    Imported, // Ballots collected elsewhere and imported from a file, closed from the start
+}
+
+impl PollOrigin {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PollOrigin::Discord => "discord",
+            PollOrigin::Imported => "imported",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "discord" => Some(PollOrigin::Discord),
+            "imported" => Some(PollOrigin::Imported),
+            _ => None,
+        }
+    }
+}
+
+// Who a reminder before close reaches, besides the post in the poll channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReminderNotify {
+    Channel, // Just the channel post
+    Role,    // Also mention the poll's allowed role
+    Dm,      // Also DM eligible members who haven't voted yet
+}
+
+impl ReminderNotify {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReminderNotify::Channel => "channel",
+            ReminderNotify::Role => "role",
+            ReminderNotify::Dm => "dm",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "channel" => Some(ReminderNotify::Channel),
+            "role" => Some(ReminderNotify::Role),
+            "dm" => Some(ReminderNotify::Dm),
+            _ => None,
+        }
+    }
+}
+
+// A Discord side effect queued in the outbox, delivered (and retried) by the outbox worker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutboxAction {
+    EditPollMessage,              // Re-render the poll's message from its stored state
+    PostResults,                  // Announce the results of a closed poll
+    SendReminder(ReminderNotify), // Remind members that the poll closes soon
+}
+
+impl OutboxAction {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            OutboxAction::EditPollMessage => "edit_poll_message",
+            OutboxAction::PostResults => "post_results",
+            OutboxAction::SendReminder(_) => "send_reminder",
+        }
+    }
+
+    pub fn notify(&self) -> Option<ReminderNotify> {
+        match self {
+            OutboxAction::SendReminder(notify) => Some(*notify),
+            _ => None,
+        }
+    }
+
+    pub fn parse(kind: &str, notify: Option<&str>) -> Option<Self> {
+        match kind {
+            "edit_poll_message" => Some(OutboxAction::EditPollMessage),
+            "post_results" => Some(OutboxAction::PostResults),
+            "send_reminder" => notify.and_then(ReminderNotify::parse).map(OutboxAction::SendReminder),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OutboxAction::EditPollMessage => "Update poll message",
+            OutboxAction::PostResults => "Post results",
+            OutboxAction::SendReminder(_) => "Send reminder",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vote {
+    pub user_id: String,
+    pub poll_id: String,
+    pub option_id: String,
+    pub rating: i32,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Poll {
+    pub fn new(
+        guild_id: String,
+        channel_id: String,
+        creator_id: String,
+        question: String,
+        options_text: Vec<String>,
+        voting_method: VotingMethod,
+        duration_minutes: Option<i64>,
+        allowed_roles: Option<Vec<String>>,
+    ) -> Self {
+        let options = options_text
+            .into_iter()
+            .map(|text| PollOption {
+                id: Uuid::new_v4().to_string(),
+                text,
+                withdrawn: false,
+            })
+            .collect();
+
+        let created_at = Utc::now();
+        
+        // Calculate end time if duration is provided
+        let ends_at = match duration_minutes {
+            Some(0) => None, // 0 means manual ending
+            Some(minutes) => Some(created_at + Duration::minutes(minutes)),
+            None => Some(created_at + Duration::minutes(DEFAULT_DURATION_MINUTES)), // Default: 1 day
+        };
+
+        Self {
+            id: Uuid::new_v4().to_string(),
+            guild_id,
+            channel_id,
+            creator_id,
+            question,
+            options,
+            voting_method,
+            created_at,
+            ends_at,
+            starts_at: None,
+            status: PollStatus::Draft,
+            message_id: None, // Initialize message_id as None
+            allowed_roles,
+            anonymous: false,
+            results_visibility: ResultsVisibility::Hidden,
+            origin: PollOrigin::Discord,
+            closed_at: None,
+            closed_by: None,
+            close_reason: None,
+            edits: Vec::new(),
+        }
+    }
+
+    // Open voting at `starts_at` instead of now. The duration counts from the opening time.
+    pub fn schedule_start(&mut self, starts_at: DateTime<Utc>) {
+        let delay = starts_at - self.created_at;
+        self.ends_at = self.ends_at.map(|ends_at| ends_at + delay);
+        self.starts_at = Some(starts_at);
+    }
+
+    // State a new poll moves to once its message is posted
+    pub fn published_status(&self) -> PollStatus {
+        if self.starts_at.is_some() {
+            PollStatus::Scheduled
+        } else {
+            PollStatus::Open
+        }
+    }
+
+    // Copy of the poll without withdrawn options, for building the voting UI
+    pub fn with_votable_options(&self) -> Poll {
+        let mut poll = self.clone();
+        poll.options.retain(|option| !option.withdrawn);
+        poll
+    }
+
+    // The poll message carries a running tally: while voting is open or paused, and the final
+    // results once it closes
+    pub fn shows_live_results(&self) -> bool {
+        self.results_visibility == ResultsVisibility::Live
+            && matches!(self.status, PollStatus::Open | PollStatus::Paused | PollStatus::Closed)
+    }
+
+    // Reached its end time but was only closed well after it, because the bot was offline
+    pub fn closed_late(&self) -> bool {
+        match (self.close_reason, self.ends_at, self.closed_at) {
+            (Some(CloseReason::Expired), Some(ends_at), Some(closed_at)) => {
+                (closed_at - ends_at).num_seconds() > LATE_CLOSE_GRACE_SECONDS
+            }
+            _ => false,
+        }
+    }
+}
+
+// How long closed-poll data is kept. Each value is a number of days after the poll closed;
+// None means "not configured" (fall back to the global policy), 0 means "keep forever".
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub anonymize_after_days: Option<i32>,       // Replace voter IDs with per-poll pseudonyms
+    pub purge_ballots_after_days: Option<i32>,   // Delete ballots, keep the results snapshot
+    pub delete_after_days: Option<i32>,          // Delete the poll entirely
+}
+
+impl RetentionPolicy {
+    // Guild values take precedence over the global ones they leave unset
+    pub fn merged_with(&self, global: &RetentionPolicy) -> RetentionPolicy {
+        RetentionPolicy {
+            anonymize_after_days: self.anonymize_after_days.or(global.anonymize_after_days),
+            purge_ballots_after_days: self.purge_ballots_after_days.or(global.purge_ballots_after_days),
+            delete_after_days: self.delete_after_days.or(global.delete_after_days),
+        }
+    }
+}
+
+// What to do with a forgotten member's ballots in polls that are still open
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActiveBallotPolicy {
+    Drop,      // Delete the ballot so it no longer counts
+    Anonymize, // Keep the ballot under a pseudonym
+}
+
+// Per-server defaults and restrictions set with `/pollconfig`. Unset values fall back to the
+// built-in defaults above.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GuildSettings {
+    pub guild_id: String,
+    pub default_method: Option<VotingMethod>,
+    pub default_duration_minutes: Option<i64>,
+    pub creator_role: Option<String>,         // Only members with this role may create polls
+    pub admin_role: Option<String>,           // Members with this role may end, edit and export any poll
+    pub default_voter_role: Option<String>,   // Used as the allowed role when `/poll create` doesn't give one
+    pub announcement_channel: Option<String>, // Results are posted here instead of the poll's channel
+    pub anonymous_by_default: bool,
+    pub max_options: Option<i32>,
+    pub timezone: Option<String>,             // IANA name used for dates members type in; UTC if unset
+}
+
+impl GuildSettings {
+    pub fn max_options(&self) -> usize {
+        self.max_options
+            .map(|max| (max.max(2) as usize).min(MAX_OPTIONS_LIMIT))
+            .unwrap_or(DEFAULT_MAX_OPTIONS)
+    }
+
+    pub fn timezone(&self) -> Tz {
+        self.timezone.as_deref().and_then(|name| name.parse().ok()).unwrap_or(Tz::UTC)
+    }
+}
+
+// A saved poll a server can post again with `/polltemplate post`, or automatically on a schedule
+#[derive(Debug, Clone)]
+pub struct PollTemplate {
+    pub id: String,
+    pub guild_id: String,
+    pub name: String,
+    pub channel_id: String, // Where scheduled polls are posted
+    pub created_by: String, // Becomes the creator of every poll posted from the template
+    pub question: String,
+    pub options: Vec<String>,
+    pub voting_method: VotingMethod,
+    pub duration_minutes: Option<i64>,
+    pub allowed_roles: Option<Vec<String>>,
+    pub anonymous: bool,
+    pub results_visibility: ResultsVisibility,
+    pub recurrence: Option<Recurrence>,
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+impl PollTemplate {
+    // A fresh poll with the template's settings, ready to be published
+    pub fn instantiate(&self) -> Poll {
+        let mut poll = Poll::new(
+            self.guild_id.clone(),
+            self.channel_id.clone(),
+            self.created_by.clone(),
+            self.question.clone(),
+            self.options.clone(),
+            self.voting_method.clone(),
+            self.duration_minutes,
+            self.allowed_roles.clone(),
+        );
+        poll.anonymous = self.anonymous;
+        poll.results_visibility = self.results_visibility;
+        poll
+    }
+}
+
+// Cron-like schedule: on the given weekdays at a local time in a timezone
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    pub days: Vec<Weekday>, // Monday first, no duplicates
+    pub time: NaiveTime,
+    pub timezone: Tz,
+}
+
+impl Recurrence {
+    // "fri", "mon,wed,fri", "daily", "weekdays" or "weekends"
+    pub fn parse_days(value: &str) -> Option<Vec<Weekday>> {
+        let all = [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun];
+        let requested: Vec<Weekday> = match value.trim().to_lowercase().as_str() {
+            "daily" | "every day" => all.to_vec(),
+            "weekdays" => all[..5].to_vec(),
+            "weekends" => all[5..].to_vec(),
+            days => days
+                .split(',')
+                .map(|day| day.trim().parse::<Weekday>().ok())
+                .collect::<Option<Vec<_>>>()?,
+        };
+        let days: Vec<Weekday> = all.into_iter().filter(|day| requested.contains(day)).collect();
+        (!days.is_empty()).then_some(days)
+    }
+
+    // Stored form of `days`, readable by `parse_days`
+    pub fn days_str(&self) -> String {
+        self.days
+            .iter()
+            .map(|day| day.to_string().to_lowercase())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    // First occurrence strictly after `after`. Local times skipped by a DST change fall on
+    // the next scheduled day; repeated ones use the earlier instant.
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let local_date = after.with_timezone(&self.timezone).date_naive();
+        (0..=7)
+            .filter_map(|offset| local_date.checked_add_signed(Duration::days(offset)))
+            .filter(|date| self.days.contains(&date.weekday()))
+            .filter_map(|date| self.timezone.from_local_datetime(&date.and_time(self.time)).earliest())
+            .map(|time| time.with_timezone(&Utc))
+            .find(|time| *time > after)
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let days = if self.days.len() == 7 {
+            "every day".to_string()
+        } else {
+            let names = self
+                .days
+                .iter()
+                .map(|day| match day {
+                    Weekday::Mon => "Monday",
+                    Weekday::Tue => "Tuesday",
+                    Weekday::Wed => "Wednesday",
+                    Weekday::Thu => "Thursday",
+                    Weekday::Fri => "Friday",
+                    Weekday::Sat => "Saturday",
+                    Weekday::Sun => "Sunday",
+                })
+                .collect::<Vec<_>>();
+            format!("every {}", names.join(", "))
+        };
+        write!(f, "{} at {} ({})", days, self.time.format("%H:%M"), self.timezone.name())
+    }
+}
@@ -0,0 +1,160 @@
+pub mod star;
+pub mod plurality;
+pub mod ranked;
+pub mod approval;
+mod tie_break;
+
+pub use tie_break::TieBreak;
+
+use crate::models::{Poll, Vote, VotingMethod};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// Generic structure for poll results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PollResults {
+    pub winner: String,        // Name of the winning option
+    pub summary: String,       // Detailed results as formatted text
+    pub winner_id: String,     // ID of the winning option
+    pub raw_results: Vec<VoteCount>, // Raw vote counts for all options
+    #[serde(default)]
+    pub rounds: Vec<Round>,    // Every counting step, in order (empty in snapshots from older versions)
+    #[serde(default)]
+    pub seed: u64,             // Seed the tie-breaking lots were drawn with
+}
+
+// One counting step: STAR's scoring and runoff, a ranked-choice round, or the single count of
+// plurality and approval polls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Round {
+    pub label: String,
+    pub unit: String,             // What the scores count, e.g. "total stars" or "votes"
+    pub counts: Vec<VoteCount>,   // Highest score first
+    pub eliminated: Vec<String>,  // IDs of options eliminated after this round
+    pub notes: Vec<String>,       // Ties and how they were broken
+}
+
+// Structure to hold vote counts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteCount {
+    pub option_id: String,
+    pub option_text: String,
+    pub score: f64,
+    pub rank: usize,
+}
+
+// Count a poll's votes the way the bot announces them, drawing lots for ties with the poll's
+// own seed. Withdrawn options are tallied as if they were never on the ballot: for ranked polls
+// they drop out of every ranking (later preferences move up), for STAR, approval and plurality
+// their ratings are simply ignored.
+pub fn calculate_poll_results(poll: &Poll, votes: &[Vote]) -> PollResults {
+    tally(poll, votes, seed_for_poll(&poll.id))
+}
+
+// Count with a chosen tie-breaking seed, as `trusty-vote-tally --seed` does
+pub fn tally(poll: &Poll, votes: &[Vote], seed: u64) -> PollResults {
+    let poll = &poll.with_votable_options();
+    let votes: Vec<Vote> = votes
+        .iter()
+        .filter(|vote| poll.options.iter().any(|option| option.id == vote.option_id))
+        .cloned()
+        .collect();
+    let votes = votes.as_slice();
+    let tie_break = TieBreak::new(seed).with_option_order(poll.options.iter().map(|option| option.id.clone()));
+
+    match poll.voting_method {
+        VotingMethod::Star => star::calculate_results(poll, votes, &tie_break),
+        VotingMethod::Plurality => plurality::calculate_results(poll, votes, &tie_break),
+        VotingMethod::Ranked => ranked::calculate_results(poll, votes, &tie_break),
+        VotingMethod::Approval => approval::calculate_results(poll, votes, &tie_break),
+    }
+}
+
+// The seed a poll's ties are broken with: a hash of its ID, so every recount of it agrees
+pub fn seed_for_poll(poll_id: &str) -> u64 {
+    tie_break::fnv1a(poll_id)
+}
+
+// The ballot rules the voting UI enforces, for ballots that arrive some other way. `marks` are
+// (option index, rating) pairs; for ranked ballots the rating is the rank, 1 = first choice.
+pub fn validate_ballot(method: &VotingMethod, marks: &[(usize, i32)], option_count: usize) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for (option, _) in marks {
+        if *option >= option_count {
+            return Err(format!("option {} doesn't exist", option + 1));
+        }
+        if !seen.insert(*option) {
+            return Err(format!("option {} is marked more than once", option + 1));
+        }
+    }
+
+    match method {
+        VotingMethod::Star => {
+            if let Some((_, rating)) = marks.iter().find(|(_, rating)| !(0..=5).contains(rating)) {
+                return Err(format!("{} is not a rating from 0 to 5 stars", rating));
+            }
+        }
+        VotingMethod::Approval => {
+            if let Some((_, rating)) = marks.iter().find(|(_, rating)| !(0..=1).contains(rating)) {
+                return Err(format!("{} is not an approval (0 or 1)", rating));
+            }
+        }
+        VotingMethod::Plurality => {
+            if marks.iter().any(|(_, rating)| !(0..=1).contains(rating)) {
+                return Err("a plurality ballot can only select options".to_string());
+            }
+            if marks.iter().filter(|(_, rating)| *rating == 1).count() != 1 {
+                return Err("a plurality ballot must select exactly one option".to_string());
+            }
+        }
+        VotingMethod::Ranked => {
+            let mut ranks: Vec<i32> = marks.iter().map(|(_, rank)| *rank).filter(|rank| *rank != 0).collect();
+            ranks.sort_unstable();
+            if ranks.iter().enumerate().any(|(i, rank)| *rank != i as i32 + 1) {
+                return Err("ranks must run 1, 2, 3, ... without ties or gaps".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_ballot_checks_options() {
+        assert!(validate_ballot(&VotingMethod::Star, &[(0, 5), (2, 1)], 3).is_ok());
+        assert!(validate_ballot(&VotingMethod::Star, &[(3, 5)], 3).is_err());
+        assert!(validate_ballot(&VotingMethod::Star, &[(0, 5), (0, 4)], 3).is_err());
+    }
+
+    #[test]
+    fn validate_ballot_checks_ratings_per_method() {
+        assert!(validate_ballot(&VotingMethod::Star, &[(0, 0), (1, 5)], 2).is_ok());
+        assert!(validate_ballot(&VotingMethod::Star, &[(0, 6)], 2).is_err());
+        assert!(validate_ballot(&VotingMethod::Star, &[(0, -1)], 2).is_err());
+
+        assert!(validate_ballot(&VotingMethod::Approval, &[(0, 1), (1, 1)], 2).is_ok());
+        assert!(validate_ballot(&VotingMethod::Approval, &[(0, 2)], 2).is_err());
+
+        assert!(validate_ballot(&VotingMethod::Plurality, &[(0, 0), (1, 1)], 2).is_ok());
+        assert!(validate_ballot(&VotingMethod::Plurality, &[(0, 1), (1, 1)], 2).is_err());
+        assert!(validate_ballot(&VotingMethod::Plurality, &[(0, 0)], 2).is_err());
+        assert!(validate_ballot(&VotingMethod::Plurality, &[(0, 3)], 2).is_err());
+    }
+
+    #[test]
+    fn validate_ballot_wants_ranks_without_ties_or_gaps() {
+        assert!(validate_ballot(&VotingMethod::Ranked, &[(2, 1), (0, 2), (1, 0)], 3).is_ok());
+        assert!(validate_ballot(&VotingMethod::Ranked, &[(0, 1), (1, 3)], 3).is_err());
+        assert!(validate_ballot(&VotingMethod::Ranked, &[(0, 1), (1, 1)], 3).is_err());
+        assert!(validate_ballot(&VotingMethod::Ranked, &[(0, 2)], 3).is_err());
+    }
+
+    #[test]
+    fn poll_seed_is_stable() {
+        assert_eq!(seed_for_poll("poll"), seed_for_poll("poll"));
+        assert_ne!(seed_for_poll("poll"), seed_for_poll("other poll"));
+    }
+}
@@ -1,8 +1,8 @@
 use crate::models::{Poll, Vote};
-use crate::voting::{PollResults, VoteCount};
+use crate::voting::{PollResults, Round, TieBreak, VoteCount};
 use std::collections::HashMap;
 
-pub fn calculate_results(poll: &Poll, votes: &[Vote]) -> PollResults {
+pub fn calculate_results(poll: &Poll, votes: &[Vote], tie_break: &TieBreak) -> PollResults {
     // Count votes for each option
     let mut option_votes: HashMap<String, i32> = HashMap::new();
     let mut option_text: HashMap<String, String> = HashMap::new();
@@ -37,13 +37,8 @@ pub fn calculate_results(poll: &Poll, votes: &[Vote]) -> PollResults {
         })
         .collect();
     
-    // Sort by score (highest first)
-    vote_counts.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-    
-    // Assign ranks
-    for (i, count) in vote_counts.iter_mut().enumerate() {
-        count.rank = i + 1;
-    }
+    // Sort by score (highest first), drawing lots between equal scores, and assign ranks
+    tie_break.rank(&mut vote_counts);
     
     // Determine winner
     if !vote_counts.is_empty() && vote_counts[0].score > 0.0 {
@@ -67,13 +62,25 @@ pub fn calculate_results(poll: &Poll, votes: &[Vote]) -> PollResults {
             ));
         }
         
+        let notes: Vec<String> = tie_break.note(&vote_counts, 0, "votes").into_iter().collect();
+        for note in &notes {
+            summary.push_str(&format!("\n{}", note));
+        }
         summary.push_str(&format!("\nTotal voters: {}", voters.len()));
         
         PollResults {
             winner: format!("{} ({} votes)", winner_text, winner_votes),
             summary,
             winner_id,
+            rounds: vec![Round {
+                label: "Count".to_string(),
+                unit: "votes".to_string(),
+                counts: vote_counts.clone(),
+                eliminated: Vec::new(),
+                notes,
+            }],
             raw_results: vote_counts,
+            seed: tie_break.seed(),
         }
     } else {
         // No votes cast
@@ -82,6 +89,8 @@ pub fn calculate_results(poll: &Poll, votes: &[Vote]) -> PollResults {
             summary: "No votes were cast.".to_string(),
             winner_id: "".to_string(),
             raw_results: Vec::new(),
+            rounds: Vec::new(),
+            seed: tie_break.seed(),
         }
     }
 }
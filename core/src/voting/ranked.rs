@@ -0,0 +1,323 @@
+use crate::models::{Poll, Vote};
+use crate::voting::{PollResults, Round, TieBreak, VoteCount};
+use std::collections::{HashMap, HashSet};
+use log::error;
+
+pub fn calculate_results(poll: &Poll, votes: &[Vote], tie_break: &TieBreak) -> PollResults {
+    // Group votes by user, storing their ranking for each option
+    let mut user_rankings: HashMap<String, HashMap<String, i32>> = HashMap::new();
+    let mut option_text: HashMap<String, String> = HashMap::new();
+
+    // Store option text for reference
+    for option in &poll.options {
+        option_text.insert(option.id.clone(), option.text.clone());
+    }
+
+    // Collect all rankings, ensuring only ranked options (rating > 0) are stored. A ballot that
+    // ranks nothing is blank: like plurality, it isn't counted as a voter (interchange exports
+    // leave blank ballots out, so recounts of them agree)
+    for vote in votes {
+        if vote.rating > 0 {
+            user_rankings
+                .entry(vote.user_id.clone())
+                .or_default()
+                .insert(vote.option_id.clone(), vote.rating);
+        }
+    }
+
+    // If no valid rankings, return early
+    if user_rankings.is_empty() {
+        return PollResults {
+            winner: "No winner".to_string(),
+            summary: "No valid rankings were submitted.".to_string(),
+            winner_id: "".to_string(),
+            raw_results: Vec::new(),
+            rounds: Vec::new(),
+            seed: tie_break.seed(),
+        };
+    }
+
+    // Calculate results using instant-runoff voting
+    let mut eliminated: HashSet<String> = HashSet::new();
+    let total_voters = user_rankings.len(); // Voters who ranked at least one option
+    let majority_threshold = (total_voters as f64 / 2.0).floor() + 1.0; // Votes needed for majority
+    let mut round = 1;
+    let mut summary = String::new();
+    let mut rounds: Vec<Round> = Vec::new();
+    let final_results: Vec<VoteCount>; // Store final round results
+
+    loop {
+        summary.push_str(&format!("**Round {}**\n", round));
+
+        // Count first preferences for each candidate that hasn't been eliminated
+        let mut first_preferences: HashMap<String, i32> = HashMap::new();
+        for option_id in option_text.keys() {
+            if !eliminated.contains(option_id) {
+                first_preferences.insert(option_id.clone(), 0);
+            }
+        }
+
+        // For each voter, find their highest-ranked non-eliminated candidate
+        for user_votes in user_rankings.values() {
+            let mut best_option: Option<String> = None;
+            let mut best_rank = std::i32::MAX;
+
+            // Find the highest ranked option (lowest rank number) that hasn't been eliminated
+            for (option_id, rank) in user_votes {
+                if !eliminated.contains(option_id) && *rank < best_rank {
+                    best_rank = *rank;
+                    best_option = Some(option_id.clone());
+                }
+            }
+
+            // Count this as a first preference
+            if let Some(option_id) = best_option {
+                if let Some(count) = first_preferences.get_mut(&option_id) {
+                    *count += 1;
+                }
+            }
+        }
+
+        // Build vote counts for this round
+        let mut round_counts: Vec<VoteCount> = first_preferences
+            .iter()
+            .map(|(option_id, count)| VoteCount {
+                option_id: option_id.clone(),
+                option_text: option_text.get(option_id).cloned().unwrap_or_default(),
+                score: *count as f64, // Score is the number of first preferences
+                rank: 0, // Will set after sorting
+            })
+            .collect();
+
+        // Sort by score (highest first), drawing lots between equal scores, and assign ranks
+        tie_break.rank(&mut round_counts);
+        let mut round_summary = Round {
+            label: format!("Round {}", round),
+            unit: "votes".to_string(),
+            counts: round_counts.clone(),
+            eliminated: Vec::new(),
+            notes: Vec::new(),
+        };
+
+        // Add round results to summary
+        for count in &round_counts {
+            let percentage = if total_voters > 0 {
+                (count.score / total_voters as f64) * 100.0
+            } else {
+                0.0
+            };
+            summary.push_str(&format!(
+                "• {}: {:.0} votes ({:.1}%)\n",
+                count.option_text, count.score, percentage
+            ));
+        }
+        summary.push('\n');
+
+        // Check if we have a majority winner
+        if !round_counts.is_empty() && round_counts[0].score >= majority_threshold {
+            let winner = &round_counts[0];
+            summary.push_str(&format!("{} has reached a majority!", winner.option_text));
+            rounds.push(round_summary);
+            final_results = round_counts; // Store this round's results
+            break; // Winner found
+        }
+
+        // Check for ties or only one candidate left
+        if round_counts.len() <= 1 {
+             let winner_text = round_counts.first().map_or("No winner (tie or no remaining options)".to_string(), |c| c.option_text.clone());
+             summary.push_str(&format!("{} wins (last remaining).", winner_text));
+             rounds.push(round_summary);
+             final_results = round_counts; // Store this round's results
+             break; // End condition met
+        }
+
+        // Eliminate the lowest-ranked candidate(s) with the minimum score. If every remaining
+        // candidate is tied, only the one that drew the lowest lot (listed last) is eliminated.
+        let min_score = round_counts.last().map_or(0.0, |c| c.score);
+        let candidates_to_eliminate: Vec<String> = if round_counts.iter().all(|c| c.score == min_score) {
+            let last = round_counts.last().map(|c| c.option_text.clone()).unwrap_or_default();
+            let note = format!(
+                "All remaining candidates tied with {} votes; drawing lots (seed {}) eliminated {}",
+                min_score,
+                tie_break.seed(),
+                last
+            );
+            summary.push_str(&format!("{}\n", note));
+            round_summary.notes.push(note);
+            round_counts.last().map(|c| c.option_id.clone()).into_iter().collect()
+        } else {
+            round_counts.iter()
+                .filter(|c| c.score == min_score)
+                .map(|c| c.option_id.clone())
+                .collect()
+        };
+        let mut eliminated_this_round_text = Vec::new();
+
+        for option_id in candidates_to_eliminate {
+             if let Some(text) = option_text.get(&option_id) {
+                 eliminated_this_round_text.push(text.clone());
+             }
+             round_summary.eliminated.push(option_id.clone());
+             eliminated.insert(option_id);
+        }
+        rounds.push(round_summary);
+
+        summary.push_str(&format!("Eliminating: {}\n\n", eliminated_this_round_text.join(", ")));
+
+        round += 1;
+
+        // Safety break to prevent infinite loops in unexpected scenarios
+        if round > poll.options.len() + 5 { // Allow a few extra rounds just in case
+            error!("Ranked choice calculation exceeded expected rounds for poll {}", poll.id);
+            summary.push_str("Calculation stopped due to excessive rounds.");
+            final_results = round_counts; // Store current state
+            break;
+        }
+    }
+
+    // Determine final winner text
+    let winner_text = if !final_results.is_empty() && final_results[0].score >= majority_threshold {
+        format!("{} ({:.0} votes)", final_results[0].option_text, final_results[0].score)
+    } else if final_results.len() == 1 {
+         format!("{} (last remaining)", final_results[0].option_text)
+    } else if !final_results.is_empty() && final_results.iter().all(|c| c.score == final_results[0].score) {
+        "Tie".to_string() // Indicate a tie if multiple winners have same score
+    } else if !final_results.is_empty() {
+         // If no majority but someone has highest score (e.g. due to exhaustion)
+         format!("{} (most votes)", final_results[0].option_text)
+    }
+    else {
+        "No clear winner".to_string()
+    };
+
+    let winner_id = final_results.first().map_or("".to_string(), |c| c.option_id.clone());
+
+    PollResults {
+        winner: winner_text,
+        summary,
+        winner_id,
+        raw_results: final_results, // Return the results of the final round
+        rounds,
+        seed: tie_break.seed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::VotingMethod;
+    use crate::voting::tally;
+    use chrono::Utc;
+
+    // A ranked poll and its votes; each ballot is a rank per option in option order, 0 = unranked
+    fn ranked_poll(options: &[&str], ballots: &[&[i32]]) -> (Poll, Vec<Vote>) {
+        let poll = Poll::new(
+            "guild".to_string(),
+            "channel".to_string(),
+            "creator".to_string(),
+            "Lunch?".to_string(),
+            options.iter().map(|option| option.to_string()).collect(),
+            VotingMethod::Ranked,
+            Some(0),
+            None,
+        );
+        let votes = ballots
+            .iter()
+            .enumerate()
+            .flat_map(|(i, ranks)| {
+                poll.options.iter().zip(ranks.iter()).map(move |(option, rank)| Vote {
+                    user_id: format!("user-{}", i),
+                    poll_id: "poll".to_string(),
+                    option_id: option.id.clone(),
+                    rating: *rank,
+                    timestamp: Utc::now(),
+                })
+            })
+            .collect();
+        (poll, votes)
+    }
+
+    fn eliminated_texts(results: &PollResults) -> Vec<Vec<String>> {
+        results
+            .rounds
+            .iter()
+            .map(|round| {
+                round
+                    .eliminated
+                    .iter()
+                    .filter_map(|id| round.counts.iter().find(|count| &count.option_id == id))
+                    .map(|count| count.option_text.clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn first_round_majority_wins() {
+        let (poll, votes) = ranked_poll(&["Tacos", "Pizza"], &[&[1, 2], &[1, 2], &[2, 1]]);
+        let results = tally(&poll, &votes, 0);
+        assert_eq!(results.winner, "Tacos (2 votes)");
+        assert_eq!(results.rounds.len(), 1);
+    }
+
+    #[test]
+    fn transfers_votes_across_rank_gaps() {
+        // Ranks 1 and 3 with no 2: the next preference is still the lowest remaining rank
+        let (poll, votes) = ranked_poll(
+            &["Tacos", "Pizza", "Sushi"],
+            &[&[1, 0, 0], &[1, 0, 0], &[0, 1, 0], &[0, 1, 0], &[3, 0, 1]],
+        );
+        let results = tally(&poll, &votes, 0);
+        assert_eq!(eliminated_texts(&results)[0], ["Sushi"]);
+        assert_eq!(results.winner, "Tacos (3 votes)");
+        assert_eq!(results.rounds.len(), 2);
+    }
+
+    #[test]
+    fn eliminates_everyone_tied_for_last_together() {
+        let (poll, votes) = ranked_poll(
+            &["Tacos", "Pizza", "Sushi", "Curry"],
+            &[&[1, 0, 0, 0], &[1, 0, 0, 0], &[0, 1, 0, 0], &[0, 1, 0, 0], &[0, 0, 1, 0], &[0, 0, 0, 1]],
+        );
+        let results = tally(&poll, &votes, 0);
+        let mut first = eliminated_texts(&results)[0].clone();
+        first.sort();
+        assert_eq!(first, ["Curry", "Sushi"]);
+        assert!(results.rounds[0].notes.is_empty());
+    }
+
+    #[test]
+    fn an_all_tied_round_eliminates_only_the_lowest_lot() {
+        let (poll, votes) = ranked_poll(&["Tacos", "Pizza", "Sushi"], &[&[1, 2, 3], &[3, 1, 2], &[2, 3, 1]]);
+        let tie_break = TieBreak::new(11);
+        let results = tally(&poll, &votes, 11);
+        let lowest = ["Tacos", "Pizza", "Sushi"].into_iter().min_by_key(|text| tie_break.lot(text)).unwrap();
+        assert_eq!(eliminated_texts(&results)[0], [lowest]);
+        assert!(results.rounds[0].notes[0].contains("seed 11"));
+        assert_eq!(results.seed, 11);
+        // The eliminated option's voters move on, which breaks the tie
+        assert!(results.winner.ends_with("(2 votes)"));
+    }
+
+    #[test]
+    fn blank_ballots_and_withdrawn_options_are_left_out() {
+        // Three real ballots need 2 for a majority; the blank ones would raise that to 3
+        let (mut poll, votes) = ranked_poll(
+            &["Tacos", "Pizza", "Sushi"],
+            &[&[2, 0, 1], &[1, 2, 0], &[0, 1, 0], &[0, 0, 0], &[0, 0, 0]],
+        );
+        poll.options[2].withdrawn = true;
+        let results = tally(&poll, &votes, 0);
+        assert_eq!(results.winner, "Tacos (2 votes)");
+        assert_eq!(results.rounds.len(), 1);
+        assert!(results.rounds[0].counts.iter().all(|count| count.option_text != "Sushi"));
+    }
+
+    #[test]
+    fn no_rankings_means_no_winner() {
+        let (poll, votes) = ranked_poll(&["Tacos", "Pizza"], &[&[0, 0]]);
+        let results = tally(&poll, &votes, 0);
+        assert_eq!(results.winner, "No winner");
+        assert!(results.rounds.is_empty());
+    }
+}
@@ -1,8 +1,8 @@
 use crate::models::{Poll, Vote};
-use crate::voting::{PollResults, VoteCount};
+use crate::voting::{PollResults, Round, TieBreak, VoteCount};
 use std::collections::HashMap;
 
-pub fn calculate_results(poll: &Poll, votes: &[Vote]) -> PollResults {
+pub fn calculate_results(poll: &Poll, votes: &[Vote], tie_break: &TieBreak) -> PollResults {
     // Group votes by user and option, storing the highest rating per user per option
     let mut user_option_ratings: HashMap<String, HashMap<String, i32>> = HashMap::new();
     let mut voters = std::collections::HashSet::new();
@@ -44,13 +44,8 @@ pub fn calculate_results(poll: &Poll, votes: &[Vote]) -> PollResults {
         })
         .collect();
 
-    // Sort by score (highest first)
-    score_counts.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
-
-    // Assign ranks based on score
-    for (i, count) in score_counts.iter_mut().enumerate() {
-        count.rank = i + 1;
-    }
+    // Sort by score (highest first), drawing lots between equal scores, and assign ranks
+    tie_break.rank(&mut score_counts);
 
     let mut summary = "**Scoring Phase Results:**\n".to_string();
     for count in &score_counts {
@@ -59,7 +54,19 @@ pub fn calculate_results(poll: &Poll, votes: &[Vote]) -> PollResults {
             count.option_text, count.score
         ));
     }
+    // Only a tie for second place decides who reaches the runoff
+    let scoring_notes: Vec<String> = tie_break.note(&score_counts, 1, "total stars").into_iter().collect();
+    for note in &scoring_notes {
+        summary.push_str(&format!("{}\n", note));
+    }
     summary.push('\n');
+    let mut rounds = vec![Round {
+        label: "Scoring".to_string(),
+        unit: "total stars".to_string(),
+        counts: score_counts.clone(),
+        eliminated: score_counts.iter().skip(2).map(|c| c.option_id.clone()).collect(),
+        notes: scoring_notes,
+    }];
 
     // --- Runoff Phase ---
     if score_counts.len() < 2 {
@@ -72,6 +79,8 @@ pub fn calculate_results(poll: &Poll, votes: &[Vote]) -> PollResults {
             summary,
             winner_id,
             raw_results: score_counts,
+            rounds,
+            seed: tie_break.seed(),
         };
     }
 
@@ -117,12 +126,43 @@ pub fn calculate_results(poll: &Poll, votes: &[Vote]) -> PollResults {
     summary.push('\n');
 
 
+    // A tied runoff goes to the higher scorer, who is listed first (by lot, if scores tied too)
     let (winner_id, winner_text, winner_score) = if runoff_votes1 >= runoff_votes2 {
         (candidate1_id.clone(), candidate1_text.clone(), runoff_votes1)
     } else {
         (candidate2_id.clone(), candidate2_text.clone(), runoff_votes2)
     };
 
+    let mut runoff_notes = Vec::new();
+    if ties > 0 {
+        runoff_notes.push(format!("Tied preference: {} voters", ties));
+    }
+    if runoff_votes1 == runoff_votes2 {
+        let note = if top_two[0].score > top_two[1].score {
+            format!("Runoff tied; {} wins with the higher score", candidate1_text)
+        } else {
+            format!("Runoff and scores tied; {} won the draw (seed {})", candidate1_text, tie_break.seed())
+        };
+        summary.push_str(&format!("{}\n", note));
+        runoff_notes.push(note);
+    }
+    let mut runoff_counts = vec![
+        VoteCount { option_id: candidate1_id.clone(), option_text: candidate1_text.clone(), score: runoff_votes1 as f64, rank: 1 },
+        VoteCount { option_id: candidate2_id.clone(), option_text: candidate2_text.clone(), score: runoff_votes2 as f64, rank: 2 },
+    ];
+    if runoff_votes2 > runoff_votes1 {
+        runoff_counts.reverse();
+        runoff_counts[0].rank = 1;
+        runoff_counts[1].rank = 2;
+    }
+    rounds.push(Round {
+        label: "Runoff".to_string(),
+        unit: "preferred votes".to_string(),
+        eliminated: vec![runoff_counts[1].option_id.clone()],
+        counts: runoff_counts,
+        notes: runoff_notes,
+    });
+
     summary.push_str(&format!("Total voters: {}", voters.len()));
 
 
@@ -131,5 +171,7 @@ pub fn calculate_results(poll: &Poll, votes: &[Vote]) -> PollResults {
         summary,
         winner_id,
         raw_results: score_counts, // Return the scoring phase results as raw
+        rounds,
+        seed: tie_break.seed(),
     }
 }
@@ -0,0 +1,150 @@
+use super::VoteCount;
+
+// Breaks ties by drawing lots. Each option's lot is a fixed pseudo-random number made from the
+// seed and the option's text (not its ID), so a poll re-created from an exported file draws the
+// same lots as the original when counted with the same seed. Options with the same text draw the
+// same lot and stay in poll order, which exports keep too.
+#[derive(Debug, Clone)]
+pub struct TieBreak {
+    seed: u64,
+    option_order: Vec<String>, // Option IDs in poll order
+}
+
+impl TieBreak {
+    pub fn new(seed: u64) -> Self {
+        TieBreak { seed, option_order: Vec::new() }
+    }
+
+    pub fn with_option_order(mut self, option_ids: impl IntoIterator<Item = String>) -> Self {
+        self.option_order = option_ids.into_iter().collect();
+        self
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // Higher lots win ties
+    pub fn lot(&self, option_text: &str) -> u64 {
+        splitmix64(self.seed ^ fnv1a(option_text))
+    }
+
+    // Sort highest score first, equal scores by lot, and number the ranks from 1
+    pub fn rank(&self, counts: &mut [VoteCount]) {
+        counts.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| self.lot(&b.option_text).cmp(&self.lot(&a.option_text)))
+                .then_with(|| self.position(&a.option_id).cmp(&self.position(&b.option_id)))
+        });
+        for (i, count) in counts.iter_mut().enumerate() {
+            count.rank = i + 1;
+        }
+    }
+
+    fn position(&self, option_id: &str) -> usize {
+        self.option_order.iter().position(|id| id == option_id).unwrap_or(usize::MAX)
+    }
+
+    // Describes the tie with the option at `index` in ranked `counts`, if it has one
+    pub fn note(&self, counts: &[VoteCount], index: usize, unit: &str) -> Option<String> {
+        let score = counts.get(index)?.score;
+        let tied: Vec<&str> = counts
+            .iter()
+            .filter(|count| count.score == score)
+            .map(|count| count.option_text.as_str())
+            .collect();
+        if tied.len() < 2 {
+            return None;
+        }
+        Some(format!(
+            "{} tied with {} {}; drawing lots (seed {}) placed {} ahead",
+            tied.join(", "),
+            score,
+            unit,
+            self.seed,
+            tied[0]
+        ))
+    }
+}
+
+// 64-bit FNV-1a, stable across platforms and Rust versions unlike `DefaultHasher`
+pub(super) fn fnv1a(value: &str) -> u64 {
+    value
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+}
+
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e3779b97f4a7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(options: &[(&str, &str, f64)]) -> Vec<VoteCount> {
+        options
+            .iter()
+            .map(|(id, text, score)| VoteCount { option_id: id.to_string(), option_text: text.to_string(), score: *score, rank: 0 })
+            .collect()
+    }
+
+    fn order(counts: &[VoteCount]) -> Vec<&str> {
+        counts.iter().map(|count| count.option_id.as_str()).collect()
+    }
+
+    #[test]
+    fn ranks_by_score_then_lot() {
+        let tie_break = TieBreak::new(7);
+        let mut ranked = counts(&[("a", "Tacos", 1.0), ("b", "Pizza", 3.0), ("c", "Sushi", 1.0)]);
+        tie_break.rank(&mut ranked);
+        assert_eq!(ranked[0].option_id, "b");
+        let lots_order = if tie_break.lot("Tacos") > tie_break.lot("Sushi") { ["b", "a", "c"] } else { ["b", "c", "a"] };
+        assert_eq!(order(&ranked), lots_order);
+        let ranks: Vec<usize> = ranked.iter().map(|count| count.rank).collect();
+        assert_eq!(ranks, [1, 2, 3]);
+    }
+
+    #[test]
+    fn lots_depend_on_the_text_and_seed_not_the_id() {
+        let tie_break = TieBreak::new(7);
+        let mut first = counts(&[("a", "Tacos", 1.0), ("b", "Pizza", 1.0)]);
+        let mut second = counts(&[("z", "Tacos", 1.0), ("y", "Pizza", 1.0)]);
+        tie_break.rank(&mut first);
+        tie_break.rank(&mut second);
+        let texts = |counts: &[VoteCount]| counts.iter().map(|count| count.option_text.clone()).collect::<Vec<_>>();
+        assert_eq!(texts(&first), texts(&second));
+
+        // Some seed draws the other order
+        assert!((0..64).any(|seed| {
+            let tie_break = TieBreak::new(seed);
+            (tie_break.lot("Tacos") > tie_break.lot("Pizza")) != (TieBreak::new(7).lot("Tacos") > TieBreak::new(7).lot("Pizza"))
+        }));
+    }
+
+    #[test]
+    fn identical_texts_keep_the_option_order() {
+        for option_order in [["x", "y"], ["y", "x"]] {
+            let tie_break = TieBreak::new(3).with_option_order(option_order.iter().map(|id| id.to_string()));
+            let mut ranked = counts(&[("y", "Tacos", 2.0), ("x", "Tacos", 2.0)]);
+            tie_break.rank(&mut ranked);
+            assert_eq!(order(&ranked), option_order);
+        }
+    }
+
+    #[test]
+    fn notes_only_real_ties() {
+        let tie_break = TieBreak::new(5);
+        let mut ranked = counts(&[("a", "Tacos", 2.0), ("b", "Pizza", 2.0), ("c", "Sushi", 1.0)]);
+        tie_break.rank(&mut ranked);
+        let note = tie_break.note(&ranked, 0, "votes").unwrap();
+        assert!(note.contains("seed 5") && note.contains(&format!("placed {} ahead", ranked[0].option_text)));
+        assert_eq!(tie_break.note(&ranked, 2, "votes"), None);
+        assert_eq!(tie_break.note(&ranked, 3, "votes"), None);
+    }
+}
@@ -0,0 +1,28 @@
+use super::canvas::{LABEL_FONT, BAR, GRID, TEXT, WINNER};
+use super::{first_counts, number, titled_canvas, LABEL_WIDTH, MARGIN, TOP, WIDTH};
+use crate::models::Poll;
+use crate::voting::PollResults;
+
+const ROW_HEIGHT: i32 = 30;
+const BAR_HEIGHT: f32 = 18.0;
+
+// A horizontal bar per option, highest first, with the winner's in orange
+pub fn counts(poll: &Poll, results: &PollResults, unit: &str) -> Result<Vec<u8>, String> {
+    let counts = first_counts(results);
+    let height = TOP + counts.len() as i32 * ROW_HEIGHT + MARGIN;
+    let mut canvas = titled_canvas(WIDTH, height as u32, poll, &format!("{}: {} per option", poll.voting_method, unit))?;
+
+    let bar_x = MARGIN + LABEL_WIDTH as i32 + 10;
+    let bar_space = (WIDTH as i32 - bar_x - MARGIN - 70) as f32;
+    let max = counts.iter().map(|count| count.score).fold(0.0, f64::max).max(1.0);
+    for (i, count) in counts.iter().enumerate() {
+        let y = TOP + i as i32 * ROW_HEIGHT;
+        canvas.text(MARGIN, y + 7, &count.option_text, LABEL_FONT, TEXT, LABEL_WIDTH);
+        canvas.rect(bar_x as f32, y as f32 + 5.0, bar_space, BAR_HEIGHT, GRID);
+        let width = (count.score / max) as f32 * bar_space;
+        let fill = if count.option_id == results.winner_id { WINNER } else { BAR };
+        canvas.rect(bar_x as f32, y as f32 + 5.0, width, BAR_HEIGHT, fill);
+        canvas.text(bar_x + bar_space as i32 + 8, y + 7, &number(count.score), LABEL_FONT, TEXT, 62);
+    }
+    canvas.png()
+}
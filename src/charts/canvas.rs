@@ -0,0 +1,106 @@
+use embedded_graphics::mono_font::iso_8859_1::{FONT_10X20, FONT_7X14};
+use embedded_graphics::mono_font::{MonoFont, MonoTextStyle};
+use embedded_graphics::pixelcolor::Rgb888;
+use embedded_graphics::prelude::*;
+use embedded_graphics::text::{Baseline, Text};
+use tiny_skia::{Color, Paint, Pixmap, Rect, Transform};
+
+// Dark theme matching Discord's, with the bot's orange for winners
+pub const BACKGROUND: (u8, u8, u8) = (43, 45, 49);
+pub const TEXT: (u8, u8, u8) = (242, 243, 245);
+pub const MUTED: (u8, u8, u8) = (181, 186, 193);
+pub const GRID: (u8, u8, u8) = (64, 68, 75);
+pub const WINNER: (u8, u8, u8) = (255, 165, 0);
+pub const BAR: (u8, u8, u8) = (88, 101, 242);
+pub const ELIMINATED: (u8, u8, u8) = (218, 55, 60);
+
+pub const TITLE_FONT: &MonoFont = &FONT_10X20;
+pub const LABEL_FONT: &MonoFont = &FONT_7X14;
+
+// A pixel buffer to draw charts on. Shapes go through tiny-skia; text uses embedded-graphics'
+// built-in bitmap fonts, so no font files are needed. Typographic dashes and quotes are drawn as
+// their ASCII lookalikes; anything else outside Latin-1 shows as `?`.
+pub struct Canvas {
+    pixmap: Pixmap,
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32) -> Result<Self, String> {
+        let mut pixmap = Pixmap::new(width, height).ok_or(format!("Can't draw a {}x{} chart", width, height))?;
+        pixmap.fill(color(BACKGROUND));
+        Ok(Canvas { pixmap })
+    }
+
+    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, fill: (u8, u8, u8)) {
+        if let Some(rect) = Rect::from_xywh(x, y, width, height) {
+            let mut paint = Paint::default();
+            paint.set_color(color(fill));
+            paint.anti_alias = false;
+            self.pixmap.fill_rect(rect, &paint, Transform::identity(), None);
+        }
+    }
+
+    // Draws `text` with its top-left corner at (x, y), cut short with `...` past `max_width` pixels
+    pub fn text(&mut self, x: i32, y: i32, text: &str, font: &MonoFont, fill: (u8, u8, u8), max_width: u32) {
+        let text = fit(text, font, max_width);
+        let style = MonoTextStyle::new(font, Rgb888::new(fill.0, fill.1, fill.2));
+        // Drawing into a pixmap can't fail
+        let _ = Text::with_baseline(&text, Point::new(x, y), style, Baseline::Top).draw(self);
+    }
+
+    pub fn png(&self) -> Result<Vec<u8>, String> {
+        self.pixmap.encode_png().map_err(|e| format!("Failed to encode chart: {}", e))
+    }
+}
+
+pub fn text_width(text: &str, font: &MonoFont) -> u32 {
+    text.chars().count() as u32 * (font.character_size.width + font.character_spacing)
+}
+
+fn fit(text: &str, font: &MonoFont, max_width: u32) -> String {
+    let text: String = text
+        .chars()
+        .map(|c| match c {
+            '\n' | '\r' => ' ',
+            '\u{2010}'..='\u{2015}' | '\u{2212}' => '-',
+            '\u{2018}' | '\u{2019}' => '\'',
+            '\u{201C}' | '\u{201D}' => '"',
+            c => c,
+        })
+        .collect();
+    if text_width(&text, font) <= max_width {
+        return text;
+    }
+    let keep = (max_width / (font.character_size.width + font.character_spacing)) as usize;
+    format!("{}...", text.chars().take(keep.saturating_sub(3)).collect::<String>())
+}
+
+fn color((r, g, b): (u8, u8, u8)) -> Color {
+    Color::from_rgba8(r, g, b, 255)
+}
+
+impl OriginDimensions for Canvas {
+    fn size(&self) -> Size {
+        Size::new(self.pixmap.width(), self.pixmap.height())
+    }
+}
+
+impl DrawTarget for Canvas {
+    type Color = Rgb888;
+    type Error = std::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (width, height) = (self.pixmap.width() as i32, self.pixmap.height() as i32);
+        let data = self.pixmap.data_mut();
+        for Pixel(point, pixel) in pixels {
+            if (0..width).contains(&point.x) && (0..height).contains(&point.y) {
+                let i = (point.y * width + point.x) as usize * 4;
+                data[i..i + 4].copy_from_slice(&[pixel.r(), pixel.g(), pixel.b(), 255]);
+            }
+        }
+        Ok(())
+    }
+}
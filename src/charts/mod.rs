@@ -0,0 +1,93 @@
+mod bars;
+mod canvas;
+mod pairwise;
+mod rounds;
+mod stars;
+
+use crate::models::{Poll, Vote, VotingMethod};
+use crate::voting::{PollResults, VoteCount};
+use canvas::{Canvas, LABEL_FONT, MUTED, TEXT, TITLE_FONT};
+use log::warn;
+
+// Charts are drawn at this width unless their columns need more
+const WIDTH: u32 = 800;
+const MARGIN: i32 = 20;
+// Rows start below the title and subtitle
+const TOP: i32 = 76;
+const LABEL_WIDTH: u32 = 200;
+
+// A PNG to attach to the results message
+pub struct Chart {
+    pub file_name: String,
+    pub png: Vec<u8>,
+}
+
+// Charts for a closed poll's results, the first one being the main chart:
+// - plurality and approval: a bar per option
+// - STAR: each option's ratings as a stacked bar, ordered by score
+// - ranked choice: the count of every round, then the pairwise preferences behind it
+// Charts that fail to render are left out; the results embed still has the numbers.
+pub fn render(poll: &Poll, votes: &[Vote], results: &PollResults) -> Vec<Chart> {
+    if results.raw_results.is_empty() {
+        return Vec::new();
+    }
+    // Withdrawn options aren't in the tally, so they aren't in the charts either
+    let poll = poll.with_votable_options();
+    let votes: Vec<&Vote> = votes
+        .iter()
+        .filter(|vote| poll.options.iter().any(|option| option.id == vote.option_id))
+        .collect();
+
+    let charts = match poll.voting_method {
+        VotingMethod::Plurality => vec![("results", bars::counts(&poll, results, "votes"))],
+        VotingMethod::Approval => vec![("results", bars::counts(&poll, results, "approvals"))],
+        // Anonymized or removed ballots leave only the scores to draw
+        VotingMethod::Star if votes.is_empty() => vec![("results", bars::counts(&poll, results, "total stars"))],
+        VotingMethod::Star => vec![("results", stars::distribution(&poll, &votes, results))],
+        VotingMethod::Ranked => {
+            let mut charts = Vec::new();
+            if !results.rounds.is_empty() {
+                charts.push(("rounds", rounds::rounds(&poll, results)));
+            }
+            if !votes.is_empty() {
+                charts.push(("pairwise", pairwise::heatmap(&poll, &votes)));
+            }
+            charts
+        }
+    };
+
+    charts
+        .into_iter()
+        .filter_map(|(name, png)| match png {
+            Ok(png) => Some(Chart { file_name: format!("poll-{}-{}.png", poll.id, name), png }),
+            Err(e) => {
+                warn!("Failed to render the {} chart for poll {}: {}", name, poll.id, e);
+                None
+            }
+        })
+        .collect()
+}
+
+// A canvas with the poll's question as the title and a line saying what the chart shows
+fn titled_canvas(width: u32, height: u32, poll: &Poll, subtitle: &str) -> Result<Canvas, String> {
+    let mut canvas = Canvas::new(width, height)?;
+    let text_width = width - 2 * MARGIN as u32;
+    canvas.text(MARGIN, 16, &poll.question, TITLE_FONT, TEXT, text_width);
+    canvas.text(MARGIN, 44, subtitle, LABEL_FONT, MUTED, text_width);
+    Ok(canvas)
+}
+
+// Counts of the first round, or the only counts older results have
+fn first_counts(results: &PollResults) -> &[VoteCount] {
+    results.rounds.first().map_or(&results.raw_results, |round| &round.counts)
+}
+
+// Whole numbers without a trailing `.0`
+fn number(value: f64) -> String {
+    if value.fract() == 0.0 { format!("{}", value as i64) } else { format!("{:.1}", value) }
+}
+
+fn mix(from: (u8, u8, u8), to: (u8, u8, u8), amount: f32) -> (u8, u8, u8) {
+    let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * amount.clamp(0.0, 1.0)).round() as u8;
+    (channel(from.0, to.0), channel(from.1, to.1), channel(from.2, to.2))
+}
@@ -0,0 +1,89 @@
+use super::canvas::{text_width, LABEL_FONT, BACKGROUND, GRID, MUTED, TEXT};
+use super::{mix, titled_canvas, LABEL_WIDTH, MARGIN, TOP, WIDTH};
+use crate::models::{Poll, Vote};
+use std::collections::HashMap;
+
+const CELL: i32 = 40;
+const HEADER_HEIGHT: i32 = 24;
+const LEGEND_HEIGHT: i32 = 36;
+const WINS: (u8, u8, u8) = (59, 165, 93);
+const LOSES: (u8, u8, u8) = (218, 55, 60);
+
+// Head-to-head preferences from the rankings: each cell counts the voters who ranked the row's
+// option above the column's (ranking an option counts as preferring it to any unranked one).
+// Green cells are contests the row option wins, red ones it loses, deeper for wider margins.
+// A Condorcet winner's row is green all the way across.
+pub fn heatmap(poll: &Poll, votes: &[&Vote]) -> Result<Vec<u8>, String> {
+    let options = &poll.options;
+    let n = options.len();
+    let index: HashMap<&str, usize> = options.iter().enumerate().map(|(i, option)| (option.id.as_str(), i)).collect();
+
+    let mut rankings: HashMap<&str, Vec<i32>> = HashMap::new();
+    for vote in votes {
+        if vote.rating > 0
+            && let Some(&i) = index.get(vote.option_id.as_str())
+        {
+            rankings.entry(vote.user_id.as_str()).or_insert_with(|| vec![0; n])[i] = vote.rating;
+        }
+    }
+    let mut preferred = vec![vec![0usize; n]; n];
+    for ranks in rankings.values() {
+        for i in 0..n {
+            for j in 0..n {
+                if i != j && ranks[i] > 0 && (ranks[j] == 0 || ranks[i] < ranks[j]) {
+                    preferred[i][j] += 1;
+                }
+            }
+        }
+    }
+    let widest = (0..n)
+        .flat_map(|i| (0..n).map(move |j| (i, j)))
+        .map(|(i, j)| preferred[i][j].abs_diff(preferred[j][i]))
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let width = WIDTH.max(MARGIN as u32 * 2 + LABEL_WIDTH + 10 + CELL as u32 * n as u32);
+    let height = TOP + HEADER_HEIGHT + CELL * n as i32 + LEGEND_HEIGHT + MARGIN;
+    let subtitle = format!("Head-to-head: voters preferring the row option over the column option ({} ballots)", rankings.len());
+    let mut canvas = titled_canvas(width, height as u32, poll, &subtitle)?;
+
+    let grid_x = MARGIN + LABEL_WIDTH as i32 + 10;
+    let grid_y = TOP + HEADER_HEIGHT;
+    for (i, option) in options.iter().enumerate() {
+        let label = format!("{}. {}", i + 1, option.text);
+        canvas.text(MARGIN, grid_y + i as i32 * CELL + 13, &label, LABEL_FONT, TEXT, LABEL_WIDTH);
+        let header = (i + 1).to_string();
+        let header_x = grid_x + i as i32 * CELL + (CELL - text_width(&header, LABEL_FONT) as i32) / 2;
+        canvas.text(header_x, TOP, &header, LABEL_FONT, MUTED, CELL as u32);
+    }
+    for (i, row) in preferred.iter().enumerate() {
+        for (j, &wins) in row.iter().enumerate() {
+            let (x, y) = (grid_x + j as i32 * CELL, grid_y + i as i32 * CELL);
+            if i == j {
+                canvas.rect(x as f32 + 1.0, y as f32 + 1.0, CELL as f32 - 2.0, CELL as f32 - 2.0, GRID);
+                continue;
+            }
+            let loses = preferred[j][i];
+            let strength = 0.35 + 0.65 * wins.abs_diff(loses) as f32 / widest as f32;
+            let fill = match wins.cmp(&loses) {
+                std::cmp::Ordering::Greater => mix(BACKGROUND, WINS, strength),
+                std::cmp::Ordering::Less => mix(BACKGROUND, LOSES, strength),
+                std::cmp::Ordering::Equal => GRID,
+            };
+            canvas.rect(x as f32 + 1.0, y as f32 + 1.0, CELL as f32 - 2.0, CELL as f32 - 2.0, fill);
+            let value = wins.to_string();
+            let value_x = x + (CELL - text_width(&value, LABEL_FONT) as i32) / 2;
+            canvas.text(value_x, y + 13, &value, LABEL_FONT, TEXT, CELL as u32);
+        }
+    }
+
+    let legend_y = grid_y + CELL * n as i32 + 14;
+    let mut x = grid_x;
+    for (fill, label) in [(WINS, "Row wins"), (LOSES, "Row loses"), (GRID, "Tied")] {
+        canvas.rect(x as f32, legend_y as f32 + 2.0, 12.0, 12.0, fill);
+        canvas.text(x + 16, legend_y, label, LABEL_FONT, MUTED, 90);
+        x += 110;
+    }
+    canvas.png()
+}
@@ -0,0 +1,84 @@
+use super::canvas::{LABEL_FONT, BAR, ELIMINATED, GRID, MUTED, TEXT, WINNER};
+use super::{number, titled_canvas, LABEL_WIDTH, MARGIN, TOP, WIDTH};
+use crate::models::Poll;
+use crate::voting::PollResults;
+
+const HEADER_HEIGHT: i32 = 24;
+const ROW_HEIGHT: i32 = 30;
+const BAR_HEIGHT: f32 = 18.0;
+const LEGEND_HEIGHT: i32 = 36;
+
+// Ranked-choice rounds side by side: a column per round and a row per option, so transfers show
+// as bars growing from one column to the next. Options are listed by how far they got, and each
+// one's bar turns red in the round it was eliminated.
+pub fn rounds(poll: &Poll, results: &PollResults) -> Result<Vec<u8>, String> {
+    let rounds = &results.rounds;
+    let column_width: u32 = if rounds.len() <= 5 { 110 } else { 90 };
+    let width = WIDTH.max(MARGIN as u32 * 2 + LABEL_WIDTH + 10 + column_width * rounds.len() as u32);
+
+    // Finalists in their final order, then the rest from the last eliminated back to the first
+    let mut order: Vec<(&str, &str)> = rounds
+        .last()
+        .map(|round| round.counts.iter().map(|c| (c.option_id.as_str(), c.option_text.as_str())).collect())
+        .unwrap_or_default();
+    for round in rounds.iter().rev() {
+        for id in &round.eliminated {
+            if !order.iter().any(|(option_id, _)| option_id == id)
+                && let Some(count) = round.counts.iter().find(|count| &count.option_id == id)
+            {
+                order.push((id, &count.option_text));
+            }
+        }
+    }
+    // Options nobody ranked first stay in the first round's order
+    for count in rounds.first().map(|round| round.counts.as_slice()).unwrap_or_default() {
+        if !order.iter().any(|(id, _)| *id == count.option_id) {
+            order.push((&count.option_id, &count.option_text));
+        }
+    }
+
+    let height = TOP + HEADER_HEIGHT + order.len() as i32 * ROW_HEIGHT + LEGEND_HEIGHT + MARGIN;
+    let subtitle = format!("Ranked Choice: votes in each of {} rounds", rounds.len());
+    let mut canvas = titled_canvas(width, height as u32, poll, &subtitle)?;
+
+    let grid_x = MARGIN + LABEL_WIDTH as i32 + 10;
+    let bar_space = (column_width - 44) as f32;
+    let max = rounds
+        .iter()
+        .flat_map(|round| round.counts.iter().map(|count| count.score))
+        .fold(0.0, f64::max)
+        .max(1.0);
+    for (r, round) in rounds.iter().enumerate() {
+        let x = grid_x + r as i32 * column_width as i32;
+        canvas.text(x, TOP, &round.label, LABEL_FONT, MUTED, column_width - 6);
+        for (row, (id, _)) in order.iter().enumerate() {
+            let Some(count) = round.counts.iter().find(|count| count.option_id == *id) else {
+                continue;
+            };
+            let y = TOP + HEADER_HEIGHT + row as i32 * ROW_HEIGHT;
+            let fill = if round.eliminated.iter().any(|eliminated| eliminated == id) {
+                ELIMINATED
+            } else if r == rounds.len() - 1 && *id == results.winner_id {
+                WINNER
+            } else {
+                BAR
+            };
+            canvas.rect(x as f32, y as f32 + 5.0, bar_space, BAR_HEIGHT, GRID);
+            canvas.rect(x as f32, y as f32 + 5.0, (count.score / max) as f32 * bar_space, BAR_HEIGHT, fill);
+            canvas.text(x + bar_space as i32 + 4, y + 7, &number(count.score), LABEL_FONT, TEXT, 38);
+        }
+    }
+    for (row, (_, text)) in order.iter().enumerate() {
+        let y = TOP + HEADER_HEIGHT + row as i32 * ROW_HEIGHT;
+        canvas.text(MARGIN, y + 7, text, LABEL_FONT, TEXT, LABEL_WIDTH);
+    }
+
+    let legend_y = TOP + HEADER_HEIGHT + order.len() as i32 * ROW_HEIGHT + 14;
+    let mut x = grid_x;
+    for (fill, label) in [(WINNER, "Winner"), (BAR, "Continuing"), (ELIMINATED, "Eliminated")] {
+        canvas.rect(x as f32, legend_y as f32 + 2.0, 12.0, 12.0, fill);
+        canvas.text(x + 16, legend_y, label, LABEL_FONT, MUTED, 90);
+        x += 110;
+    }
+    canvas.png()
+}
@@ -0,0 +1,65 @@
+use super::canvas::{LABEL_FONT, MUTED, TEXT, WINNER};
+use super::{number, titled_canvas, LABEL_WIDTH, MARGIN, TOP, WIDTH};
+use crate::models::{Poll, Vote};
+use crate::voting::PollResults;
+use std::collections::{HashMap, HashSet};
+
+const ROW_HEIGHT: i32 = 30;
+const BAR_HEIGHT: f32 = 18.0;
+const LEGEND_HEIGHT: i32 = 36;
+// From 0 stars (grey) to 5 stars (gold)
+const RATING_COLORS: [(u8, u8, u8); 6] = [
+    (79, 84, 92),
+    (70, 90, 160),
+    (88, 101, 242),
+    (150, 110, 210),
+    (235, 135, 60),
+    (255, 190, 0),
+];
+
+// A bar per option showing how many voters gave it each rating, 5 stars on the left, in the
+// order of the scoring round. Options a voter left unrated count as 0 stars, as in the tally.
+pub fn distribution(poll: &Poll, votes: &[&Vote], results: &PollResults) -> Result<Vec<u8>, String> {
+    let voters: HashSet<&str> = votes.iter().map(|vote| vote.user_id.as_str()).collect();
+    let mut ratings: HashMap<&str, [usize; 6]> = HashMap::new();
+    for vote in votes {
+        if let Ok(stars) = usize::try_from(vote.rating)
+            && stars <= 5
+        {
+            ratings.entry(vote.option_id.as_str()).or_default()[stars] += 1;
+        }
+    }
+
+    let counts = &results.raw_results;
+    let height = TOP + counts.len() as i32 * ROW_HEIGHT + LEGEND_HEIGHT + MARGIN;
+    let subtitle = format!("STAR: ratings from {} voters, ordered by total stars", voters.len());
+    let mut canvas = titled_canvas(WIDTH, height as u32, poll, &subtitle)?;
+
+    let bar_x = (MARGIN + LABEL_WIDTH as i32 + 10) as f32;
+    let bar_space = WIDTH as f32 - bar_x - MARGIN as f32 - 70.0;
+    for (i, count) in counts.iter().enumerate() {
+        let y = TOP + i as i32 * ROW_HEIGHT;
+        let label_color = if count.option_id == results.winner_id { WINNER } else { TEXT };
+        canvas.text(MARGIN, y + 7, &count.option_text, LABEL_FONT, label_color, LABEL_WIDTH);
+
+        let mut given = ratings.get(count.option_id.as_str()).copied().unwrap_or_default();
+        given[0] = voters.len().saturating_sub(given[1..].iter().sum());
+        let mut x = bar_x;
+        for stars in (0..=5).rev() {
+            let width = given[stars] as f32 / voters.len().max(1) as f32 * bar_space;
+            canvas.rect(x, y as f32 + 5.0, width, BAR_HEIGHT, RATING_COLORS[stars]);
+            x += width;
+        }
+        canvas.text(bar_x as i32 + bar_space as i32 + 8, y + 7, &number(count.score), LABEL_FONT, TEXT, 62);
+    }
+
+    let legend_y = TOP + counts.len() as i32 * ROW_HEIGHT + 14;
+    let mut x = bar_x as i32;
+    for stars in (0..=5).rev() {
+        canvas.rect(x as f32, legend_y as f32 + 2.0, 12.0, 12.0, RATING_COLORS[stars]);
+        let label = if stars == 1 { "1 star".to_string() } else { format!("{} stars", stars) };
+        canvas.text(x + 16, legend_y, &label, LABEL_FONT, MUTED, 60);
+        x += 80;
+    }
+    canvas.png()
+}
@@ -0,0 +1,57 @@
+use crate::db::Database;
+use crate::import::ImportFormat;
+use crate::models::VotingMethod;
+
+pub const USAGE: &str = "Usage: trusty-vote import <file> --guild <id> --channel <id> --created-by <user id> \
+                         [--method star|plurality|ranked|approval] [--format csv|json|blt|abif] [--question <text>]";
+
+// `trusty-vote import ...`: store a ballot file as a closed poll without starting the bot. The
+// results are announced by the outbox once a bot instance is running.
+pub async fn import(args: &[String]) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut file = None;
+    let (mut guild, mut channel, mut created_by) = (None, None, None);
+    let (mut method, mut format, mut question) = (None, None, None);
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().ok_or_else(|| format!("{} needs a value\n{}", arg, USAGE));
+        match arg.as_str() {
+            "--guild" => guild = Some(value()?),
+            "--channel" => channel = Some(value()?),
+            "--created-by" => created_by = Some(value()?),
+            "--method" => {
+                let name = value()?;
+                method = Some(VotingMethod::parse(&name).ok_or_else(|| format!("Unknown voting method `{}`", name))?);
+            }
+            "--format" => {
+                let name = value()?;
+                format = Some(ImportFormat::parse(&name).ok_or_else(|| format!("Unknown format `{}`", name))?);
+            }
+            "--question" => question = Some(value()?),
+            _ if arg.starts_with("--") || file.is_some() => return Err(format!("Unexpected argument `{}`\n{}", arg, USAGE).into()),
+            _ => file = Some(arg.clone()),
+        }
+    }
+
+    let missing = |what: &str| format!("Missing {}\n{}", what, USAGE);
+    let file = file.ok_or_else(|| missing("the ballot file"))?;
+    let guild = guild.ok_or_else(|| missing("--guild"))?;
+    let channel = channel.ok_or_else(|| missing("--channel"))?;
+    let created_by = created_by.ok_or_else(|| missing("--created-by"))?;
+    let format = format
+        .or_else(|| ImportFormat::from_file_name(&file))
+        .ok_or("Unrecognized file type; pass --format")?;
+
+    let text = std::fs::read_to_string(&file).map_err(|e| format!("Failed to read {}: {}", file, e))?;
+    let imported = format.read(&text)?.into_poll(guild, channel, created_by, question, method)?;
+    let database = Database::new().await?;
+    let results = database.create_imported_poll(&imported).await?;
+
+    println!(
+        "Imported {} ballots ({} blank skipped) into poll {} \"{}\" ({})",
+        imported.ballots, imported.blank_ballots, imported.poll.id, imported.poll.question, imported.poll.voting_method
+    );
+    println!("Winner: {}", results.winner);
+    println!("The results will be announced in the poll's channel by the running bot.");
+    Ok(())
+}
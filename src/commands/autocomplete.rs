@@ -0,0 +1,109 @@
+use crate::commands::poll::can_see_results_so_far;
+use crate::db::Database;
+use crate::models::{Poll, PollOrigin, PollStatus};
+use crate::permissions::{is_poll_admin, Caller};
+use serenity::model::application::interaction::application_command::CommandDataOption;
+use serenity::model::application::interaction::autocomplete::AutocompleteInteraction;
+use serenity::prelude::*;
+
+// Discord shows at most 25 suggestions, each named in up to 100 characters
+const MAX_CHOICES: usize = 25;
+const CHOICE_MAX_CHARS: usize = 100;
+const SHORT_ID_CHARS: usize = 8;
+// Results suggestions drop active polls the member can't see yet, so fetch some spare
+const FETCH_LIMIT: i64 = 50;
+
+// Suggestions for the `poll_id` options of `/poll`: polls whose question contains what the member
+// has typed so far, limited to those the subcommand accepts and the member may act on
+pub async fn handle_autocomplete(
+    database: &Database,
+    ctx: &Context,
+    autocomplete: &AutocompleteInteraction,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let choices = match (autocomplete.data.name.as_str(), autocomplete.guild_id) {
+        ("poll", Some(guild_id)) => poll_choices(database, autocomplete, &guild_id.to_string()).await?,
+        _ => Vec::new(),
+    };
+
+    autocomplete
+        .create_autocomplete_response(&ctx.http, |response| {
+            for (name, value) in choices {
+                response.add_string_choice(name, value);
+            }
+            response
+        })
+        .await?;
+    Ok(())
+}
+
+async fn poll_choices(
+    database: &Database,
+    autocomplete: &AutocompleteInteraction,
+    guild_id: &str,
+) -> Result<Vec<(String, String)>, Box<dyn std::error::Error + Send + Sync>> {
+    // `/poll edit` nests its subcommands one level deeper
+    let Some(mut subcommand) = autocomplete.data.options.first() else { return Ok(Vec::new()) };
+    let name = subcommand.name.as_str();
+    if name == "edit" {
+        let Some(action) = subcommand.options.first() else { return Ok(Vec::new()) };
+        subcommand = action;
+    }
+    let Some(typed) = focused_poll_id(subcommand) else { return Ok(Vec::new()) };
+
+    let (statuses, managed): (&[PollStatus], bool) = match name {
+        "end" | "edit" => (&[PollStatus::Scheduled, PollStatus::Open, PollStatus::Paused], true),
+        "pause" => (&[PollStatus::Open], true),
+        "resume" => (&[PollStatus::Paused], true),
+        "reopen" => (&[PollStatus::Closed], true),
+        "export" => (&[PollStatus::Closed, PollStatus::Archived], true),
+        "stats" => (&[PollStatus::Open, PollStatus::Paused, PollStatus::Closed, PollStatus::Archived], true),
+        "results" => (&[PollStatus::Open, PollStatus::Paused, PollStatus::Closed, PollStatus::Archived], false),
+        _ => return Ok(Vec::new()),
+    };
+
+    // Members who aren't poll admins only manage the polls they created
+    let user_id = autocomplete.user.id.to_string();
+    let settings = database.get_guild_settings(guild_id).await?;
+    let caller = Caller::new(&autocomplete.user, autocomplete.member.as_ref());
+    let creator_id = (managed && !is_poll_admin(&caller, &settings)).then_some(user_id.as_str());
+
+    let polls = database.search_polls_by_guild(guild_id, typed, statuses, creator_id, FETCH_LIMIT).await?;
+    let mut choices = Vec::new();
+    for poll in polls {
+        if choices.len() == MAX_CHOICES {
+            break;
+        }
+        let offered = match name {
+            "reopen" => poll.origin != PollOrigin::Imported,
+            "results" if poll.status.is_active() => can_see_results_so_far(database, &poll, &user_id).await?,
+            _ => true,
+        };
+        if offered {
+            choices.push((choice_name(&poll), poll.id));
+        }
+    }
+    Ok(choices)
+}
+
+// What the member has typed into `poll_id`, if that's the option being completed
+fn focused_poll_id(subcommand: &CommandDataOption) -> Option<&str> {
+    subcommand
+        .options
+        .iter()
+        .find(|option| option.focused && option.name == "poll_id")
+        .map(|option| option.value.as_ref().and_then(|value| value.as_str()).unwrap_or_default())
+}
+
+// "Question · short ID", with the question cut to fit
+fn choice_name(poll: &Poll) -> String {
+    let short_id: String = poll.id.chars().take(SHORT_ID_CHARS).collect();
+    let suffix = format!(" · {}", short_id);
+    let room = CHOICE_MAX_CHARS - suffix.chars().count();
+    let question = poll.question.split_whitespace().collect::<Vec<_>>().join(" ");
+    let question = if question.chars().count() > room {
+        format!("{}...", question.chars().take(room - 3).collect::<String>())
+    } else {
+        question
+    };
+    format!("{}{}", question, suffix)
+}
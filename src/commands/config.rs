@@ -0,0 +1,266 @@
+use crate::commands::poll::send_error_response;
+use crate::db::Database;
+use crate::models::{GuildSettings, VotingMethod, DEFAULT_DURATION_MINUTES, MAX_OPTIONS_LIMIT};
+use crate::permissions::Caller;
+use chrono_tz::Tz;
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::channel::ChannelType;
+use serenity::model::permissions::Permissions;
+use serenity::prelude::*;
+
+pub fn create_config_command(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("pollconfig")
+        .description("Configure poll defaults for this server")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("show")
+                .description("Show this server's poll settings")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("set")
+                .description("Change one or more poll settings")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("method")
+                        .description("Default voting method when /poll create doesn't give one")
+                        .kind(CommandOptionType::String)
+                        .add_string_choice("STAR", "star")
+                        .add_string_choice("Plurality", "plurality")
+                        .add_string_choice("Ranked Choice", "ranked")
+                        .add_string_choice("Approval", "approval")
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("duration")
+                        .description("Default duration in minutes (0 for manual close)")
+                        .kind(CommandOptionType::Integer)
+                        .min_int_value(0)
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("creator_role")
+                        .description("Only members with this role can create polls")
+                        .kind(CommandOptionType::Role)
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("admin_role")
+                        .description("Members with this role can end, edit and export any poll")
+                        .kind(CommandOptionType::Role)
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("voter_role")
+                        .description("Default role allowed to vote when /poll create doesn't give one")
+                        .kind(CommandOptionType::Role)
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("announcement_channel")
+                        .description("Channel where poll results are posted")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text, ChannelType::News])
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("anonymous")
+                        .description("Make new polls anonymous by default")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("timezone")
+                        .description("Timezone for dates and schedules, e.g. Europe/Berlin (default: UTC)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("max_options")
+                        .description("Maximum number of options per poll")
+                        .kind(CommandOptionType::Integer)
+                        .min_int_value(2)
+                        .max_int_value(MAX_OPTIONS_LIMIT as u64)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("clear")
+                .description("Reset a setting to the built-in default")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("setting")
+                        .description("Setting to reset")
+                        .kind(CommandOptionType::String)
+                        .add_string_choice("Default method", "method")
+                        .add_string_choice("Default duration", "duration")
+                        .add_string_choice("Creator role", "creator_role")
+                        .add_string_choice("Poll admin role", "admin_role")
+                        .add_string_choice("Voter role", "voter_role")
+                        .add_string_choice("Announcement channel", "announcement_channel")
+                        .add_string_choice("Anonymous by default", "anonymous")
+                        .add_string_choice("Maximum options", "max_options")
+                        .add_string_choice("Timezone", "timezone")
+                        .add_string_choice("All settings", "all")
+                        .required(true)
+                })
+        })
+}
+
+pub async fn handle_config_command(
+    database: &Database,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let guild_id = match command.guild_id {
+        Some(id) => id.to_string(),
+        None => {
+            send_error_response(ctx, command, "This command can only be used in a server").await?;
+            return Ok(());
+        }
+    };
+
+    if !Caller::new(&command.user, command.member.as_ref()).has_manage_guild() {
+        send_error_response(ctx, command, "You need the Manage Server permission to change poll settings.").await?;
+        return Ok(());
+    }
+
+    let subcommand = match command.data.options.first() {
+        Some(option) => option,
+        None => {
+            send_error_response(ctx, command, "No subcommand provided").await?;
+            return Ok(());
+        }
+    };
+
+    let mut settings = database.get_guild_settings(&guild_id).await?;
+
+    let content = match subcommand.name.as_str() {
+        "show" => format_settings(&settings),
+        "set" => {
+            for option in &subcommand.options {
+                let value = match option.value.as_ref() {
+                    Some(value) => value,
+                    None => continue,
+                };
+                match option.name.as_str() {
+                    "method" => settings.default_method = value.as_str().and_then(VotingMethod::parse),
+                    "duration" => settings.default_duration_minutes = value.as_i64(),
+                    "creator_role" => settings.creator_role = value.as_str().map(|s| s.to_string()),
+                    "admin_role" => settings.admin_role = value.as_str().map(|s| s.to_string()),
+                    "voter_role" => settings.default_voter_role = value.as_str().map(|s| s.to_string()),
+                    "announcement_channel" => settings.announcement_channel = value.as_str().map(|s| s.to_string()),
+                    "anonymous" => settings.anonymous_by_default = value.as_bool().unwrap_or(false),
+                    "max_options" => settings.max_options = value.as_i64().map(|v| v as i32),
+                    "timezone" => {
+                        let name = value.as_str().unwrap_or_default().trim();
+                        match name.parse::<Tz>() {
+                            Ok(tz) => settings.timezone = Some(tz.name().to_string()),
+                            Err(_) => {
+                                let msg = format!("Unknown timezone `{}`. Use an IANA name such as `Europe/Berlin` or `America/New_York`.", name);
+                                send_error_response(ctx, command, &msg).await?;
+                                return Ok(());
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            database.save_guild_settings(&settings).await?;
+            format!("Settings updated.\n\n{}", format_settings(&settings))
+        }
+        "clear" => {
+            let setting = subcommand
+                .options
+                .first()
+                .and_then(|option| option.value.as_ref())
+                .and_then(|value| value.as_str())
+                .unwrap_or("");
+            match setting {
+                "method" => settings.default_method = None,
+                "duration" => settings.default_duration_minutes = None,
+                "creator_role" => settings.creator_role = None,
+                "admin_role" => settings.admin_role = None,
+                "voter_role" => settings.default_voter_role = None,
+                "announcement_channel" => settings.announcement_channel = None,
+                "anonymous" => settings.anonymous_by_default = false,
+                "max_options" => settings.max_options = None,
+                "timezone" => settings.timezone = None,
+                "all" => {
+                    settings = GuildSettings {
+                        guild_id: guild_id.clone(),
+                        ..Default::default()
+                    }
+                }
+                _ => {
+                    send_error_response(ctx, command, "Unknown setting").await?;
+                    return Ok(());
+                }
+            }
+            database.save_guild_settings(&settings).await?;
+            format!("Setting reset.\n\n{}", format_settings(&settings))
+        }
+        _ => {
+            send_error_response(ctx, command, "Unknown subcommand").await?;
+            return Ok(());
+        }
+    };
+
+    command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| message.ephemeral(true).content(content))
+        })
+        .await?;
+
+    Ok(())
+}
+
+fn format_settings(settings: &GuildSettings) -> String {
+    let duration = match settings.default_duration_minutes {
+        Some(0) => "manual close".to_string(),
+        Some(minutes) => format!("{} minutes", minutes),
+        None => format!("{} minutes (built-in default)", DEFAULT_DURATION_MINUTES),
+    };
+
+    format!(
+        "**Poll settings for this server**\n\
+        • Default method: {}\n\
+        • Default duration: {}\n\
+        • Who can create polls: {}\n\
+        • Poll admin role: {}\n\
+        • Default voter role: {}\n\
+        • Results posted in: {}\n\
+        • Anonymous by default: {}\n\
+        • Maximum options: {}\n\
+        • Timezone: {}",
+        settings.default_method.as_ref().map_or("STAR (built-in default)".to_string(), |m| m.to_string()),
+        duration,
+        settings.creator_role.as_ref().map_or("everyone".to_string(), |role| format!("<@&{}>", role)),
+        settings.admin_role.as_ref().map_or("Manage Server only".to_string(), |role| format!("<@&{}> and Manage Server", role)),
+        settings.default_voter_role.as_ref().map_or("everyone".to_string(), |role| format!("<@&{}>", role)),
+        settings.announcement_channel.as_ref().map_or("the poll's channel".to_string(), |channel| format!("<#{}>", channel)),
+        if settings.anonymous_by_default { "yes" } else { "no" },
+        settings.max_options(),
+        settings.timezone.as_deref().unwrap_or("UTC (built-in default)"),
+    )
+}
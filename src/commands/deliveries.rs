@@ -0,0 +1,164 @@
+use crate::commands::poll::send_error_response;
+use crate::db::{Database, OutboxEntry};
+use crate::permissions::{is_poll_admin, Caller};
+use crate::tasks::outbox;
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::*;
+use log::info;
+
+// Entries shown per list; the reply has to fit in one message
+const LIST_LIMIT: i64 = 15;
+const ERROR_PREVIEW_CHARS: usize = 150;
+
+pub fn create_deliveries_command(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("polldeliveries")
+        .description("Inspect and retry poll messages the bot failed to deliver (poll admins only)")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("failed")
+                .description("List deliveries that kept failing and were given up on")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("pending")
+                .description("List deliveries that are queued or waiting for a retry")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("replay")
+                .description("Retry failed deliveries")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("delivery_id")
+                        .description("The delivery to retry (default: every failed delivery)")
+                        .kind(CommandOptionType::Integer)
+                        .min_int_value(1)
+                        .required(false)
+                })
+        })
+}
+
+pub async fn handle_deliveries_command(
+    database: &Database,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let guild_id = match command.guild_id {
+        Some(id) => id.to_string(),
+        None => {
+            send_error_response(ctx, command, "This command can only be used in a server").await?;
+            return Ok(());
+        }
+    };
+
+    let settings = database.get_guild_settings(&guild_id).await?;
+    if !is_poll_admin(&Caller::new(&command.user, command.member.as_ref()), &settings) {
+        send_error_response(ctx, command, "You don't have permission to manage poll deliveries in this server.").await?;
+        return Ok(());
+    }
+
+    let subcommand = match command.data.options.first() {
+        Some(option) => option,
+        None => {
+            send_error_response(ctx, command, "No subcommand provided").await?;
+            return Ok(());
+        }
+    };
+
+    let content = match subcommand.name.as_str() {
+        "failed" => {
+            let entries = database.get_outbox_actions(&guild_id, true, LIST_LIMIT).await?;
+            if entries.is_empty() {
+                "No failed deliveries.".to_string()
+            } else {
+                format!(
+                    "**Failed deliveries** (retry with `/polldeliveries replay`)\n{}",
+                    format_entries(&entries)
+                )
+            }
+        }
+        "pending" => {
+            let entries = database.get_outbox_actions(&guild_id, false, LIST_LIMIT).await?;
+            if entries.is_empty() {
+                "Nothing is waiting to be delivered.".to_string()
+            } else {
+                format!("**Pending deliveries**\n{}", format_entries(&entries))
+            }
+        }
+        "replay" => {
+            let delivery_id = subcommand
+                .options
+                .iter()
+                .find(|option| option.name == "delivery_id")
+                .and_then(|option| option.value.as_ref())
+                .and_then(|value| value.as_i64());
+            let requeued = database.replay_outbox_actions(&guild_id, delivery_id).await?;
+            if requeued > 0 {
+                info!("{} requeued {} failed deliveries in guild {}", command.user.id, requeued, guild_id);
+                outbox::wake(ctx).await;
+            }
+            match (delivery_id, requeued) {
+                (Some(id), 0) => format!("There is no failed delivery #{} in this server.", id),
+                (Some(id), _) => format!("Delivery #{} will be retried now.", id),
+                (None, 0) => "There are no failed deliveries to retry.".to_string(),
+                (None, 1) => "1 failed delivery will be retried now.".to_string(),
+                (None, n) => format!("{} failed deliveries will be retried now.", n),
+            }
+        }
+        _ => {
+            send_error_response(ctx, command, "Unknown subcommand").await?;
+            return Ok(());
+        }
+    };
+
+    command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| message.ephemeral(true).content(content))
+        })
+        .await?;
+
+    Ok(())
+}
+
+fn format_entries(entries: &[OutboxEntry]) -> String {
+    let mut lines = Vec::new();
+    for entry in entries {
+        let when = match entry.dead_at {
+            Some(dead_at) => format!("gave up <t:{}:R>", dead_at.timestamp()),
+            None if entry.attempts == 0 => format!("queued <t:{}:R>", entry.created_at.timestamp()),
+            None => format!("next try <t:{}:R>", entry.next_attempt_at.timestamp()),
+        };
+        lines.push(format!(
+            "`#{}` {} for **{}**: {} attempt{}, {}",
+            entry.id,
+            entry.action.label(),
+            entry.question,
+            entry.attempts,
+            if entry.attempts == 1 { "" } else { "s" },
+            when
+        ));
+        if let Some(error) = &entry.last_error {
+            let preview: String = error.chars().take(ERROR_PREVIEW_CHARS).collect();
+            let ellipsis = if error.chars().count() > ERROR_PREVIEW_CHARS { "…" } else { "" };
+            lines.push(format!("  Last error: {}{}", preview, ellipsis));
+        }
+    }
+
+    // Keep within Discord's 2000 character message limit
+    let text = lines.join("\n");
+    if text.chars().count() > 1800 {
+        format!("{}…\n(list truncated)", text.chars().take(1800).collect::<String>())
+    } else {
+        text
+    }
+}
@@ -0,0 +1,177 @@
+use crate::commands::poll::send_error_response;
+use crate::db::{Database, MonthActivity};
+use crate::export::csv;
+use chrono::{DateTime, Days, Duration, NaiveDate, NaiveTime, Utc};
+use chrono_tz::Tz;
+use log::info;
+use serenity::builder::CreateEmbed;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::channel::AttachmentType;
+use serenity::prelude::*;
+use std::borrow::Cow;
+
+const TOP_CREATORS: i64 = 5;
+// Months listed in the embed; the CSV has every poll
+const MONTHS_SHOWN: usize = 12;
+
+// `/poll history`: how the server has been using polls, from aggregate counts only. Individual
+// ballots never appear, so anonymous polls are summarized like any other.
+pub async fn handle_poll_history(
+    database: &Database,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let options = command.data.options.first().map(|sub| sub.options.as_slice()).unwrap_or_default();
+    let option = |name: &str| options.iter().find(|o| o.name == name).and_then(|o| o.value.as_ref());
+    let guild_id = command.guild_id.ok_or("Missing guild ID")?.to_string();
+    let timezone = database.get_guild_settings(&guild_id).await?.timezone();
+
+    // `to` includes the whole day, so the range ends at the start of the next one
+    let mut range = [None, None];
+    for (i, name) in ["from", "to"].into_iter().enumerate() {
+        let Some(value) = option(name).and_then(|v| v.as_str()) else { continue };
+        let date = NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").ok();
+        let date = if name == "to" { date.and_then(|day| day.checked_add_days(Days::new(1))) } else { date };
+        match date {
+            Some(date) => range[i] = Some(date),
+            None => {
+                let msg = format!("Couldn't read `{}`. Use a date like `2026-01-31` (server timezone, see `/pollconfig`).", name);
+                send_error_response(ctx, command, &msg).await?;
+                return Ok(());
+            }
+        }
+    }
+    let [from, to] = range;
+    if let (Some(from), Some(to)) = (from, to)
+        && from >= to
+    {
+        send_error_response(ctx, command, "`from` must be on or before `to`.").await?;
+        return Ok(());
+    }
+    let with_csv = option("csv").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                .interaction_response_data(|message| message.ephemeral(true))
+        })
+        .await?;
+
+    let (from, to) = (from.map(|day| day_start(day, timezone)), to.map(|day| day_start(day, timezone)));
+    let months = database.get_monthly_poll_activity(&guild_id, from, to, timezone.name()).await?;
+    if months.is_empty() {
+        command
+            .edit_original_interaction_response(&ctx.http, |response| {
+                response.content("No polls ran in this server in that period.")
+            })
+            .await?;
+        return Ok(());
+    }
+    let voters = database.count_history_voters(&guild_id, from, to).await?;
+    let creators = database.get_top_poll_creators(&guild_id, from, to, TOP_CREATORS).await?;
+    let methods = database.get_voting_method_usage(&guild_id, from, to).await?;
+
+    let mut embed = CreateEmbed::default();
+    let period = match (from, to) {
+        (Some(from), Some(to)) => format!("<t:{}:D> to <t:{}:D>", from.timestamp(), to.timestamp() - 1),
+        (Some(from), None) => format!("Since <t:{}:D>", from.timestamp()),
+        (None, Some(to)) => format!("Up to <t:{}:D>", to.timestamp() - 1),
+        (None, None) => "All time".to_string(),
+    };
+    embed
+        .title("📊 Poll History")
+        .description(format!("{}. Counts cover polls that opened in this server; imported polls aren't included.", period))
+        .field("Overview", overview(&months, voters), false)
+        .field("By Month", by_month(&months), false)
+        .footer(|f| f.text(format!("Months in {}", timezone.name())))
+        .color((255, 165, 0));
+
+    let total: i64 = months.iter().map(|month| month.polls).sum();
+    let share = |polls: i64| polls as f64 * 100.0 / total as f64;
+    let creators = creators
+        .iter()
+        .enumerate()
+        .map(|(i, (user_id, polls))| format!("{}. <@{}> — {} poll{}", i + 1, user_id, polls, if *polls == 1 { "" } else { "s" }))
+        .collect::<Vec<_>>()
+        .join("\n");
+    embed.field("Top Poll Creators", creators, false);
+    let methods = methods
+        .iter()
+        .map(|(method, polls)| format!("{}: {} ({:.0}%)", method, polls, share(*polls)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    embed.field("Voting Methods", methods, false);
+
+    command
+        .edit_original_interaction_response(&ctx.http, |response| response.set_embed(embed))
+        .await?;
+
+    if with_csv {
+        let polls = database.get_poll_activity(&guild_id, from, to).await?;
+        command
+            .create_followup_message(&ctx.http, |message| {
+                message
+                    .ephemeral(true)
+                    .content(format!("{} poll{} with their ballot counts.", polls.len(), if polls.len() == 1 { "" } else { "s" }))
+                    .add_file(AttachmentType::Bytes {
+                        data: Cow::Owned(csv::history(&polls).into_bytes()),
+                        filename: format!("poll-history-{}.csv", guild_id),
+                    })
+            })
+            .await?;
+    }
+    info!("Sent poll history for guild {} to {}", guild_id, command.user.id);
+    Ok(())
+}
+
+// Midnight at the start of `day` in the server's timezone
+fn day_start(day: NaiveDate, timezone: Tz) -> DateTime<Utc> {
+    let midnight = day.and_time(NaiveTime::MIN);
+    // A few timezones skip midnight when the clocks change, starting the day an hour later
+    midnight
+        .and_local_timezone(timezone)
+        .earliest()
+        .or_else(|| (midnight + Duration::hours(1)).and_local_timezone(timezone).earliest())
+        .map_or_else(|| midnight.and_utc(), |start| start.with_timezone(&Utc))
+}
+
+fn overview(months: &[MonthActivity], voters: i64) -> String {
+    let polls: i64 = months.iter().map(|month| month.polls).sum();
+    let ballots: i64 = months.iter().map(|month| month.ballots).sum();
+    format!(
+        "**{}** poll{} with **{}** ballot{} from **{}** member{}\nAverage turnout: **{:.1}** ballots per poll",
+        polls,
+        if polls == 1 { "" } else { "s" },
+        ballots,
+        if ballots == 1 { "" } else { "s" },
+        voters,
+        if voters == 1 { "" } else { "s" },
+        ballots as f64 / polls.max(1) as f64
+    )
+}
+
+fn by_month(months: &[MonthActivity]) -> String {
+    let mut lines: Vec<String> = months
+        .iter()
+        .rev()
+        .take(MONTHS_SHOWN)
+        .map(|month| {
+            format!(
+                "`{}` {} poll{} · {} ballots · {:.1} per poll · {} members",
+                month.month.format("%Y-%m"),
+                month.polls,
+                if month.polls == 1 { "" } else { "s" },
+                month.ballots,
+                month.ballots as f64 / month.polls.max(1) as f64,
+                month.voters
+            )
+        })
+        .collect();
+    lines.reverse();
+    if months.len() > MONTHS_SHOWN {
+        lines.insert(0, format!("Latest {} of {} months:", MONTHS_SHOWN, months.len()));
+    }
+    lines.join("\n")
+}
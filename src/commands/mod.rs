@@ -1 +1,11 @@
-pub mod poll;
\ No newline at end of file
+pub mod autocomplete;
+pub mod config;
+pub mod deliveries;
+pub mod history;
+pub mod myvotes;
+pub mod poll;
+pub mod privacy;
+pub mod results;
+pub mod stats;
+pub mod retention;
+pub mod template;
\ No newline at end of file
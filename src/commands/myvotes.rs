@@ -0,0 +1,171 @@
+use crate::commands::poll::truncate;
+use crate::db::Database;
+use crate::handlers::vote::format_user_vote;
+use crate::models::{Poll, PollStatus};
+use log::warn;
+use serenity::model::application::component::ButtonStyle;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::prelude::*;
+
+// Ten polls with their buttons fit in two action rows and one embed
+const MAX_POLLS: usize = 10;
+const QUESTION_MAX_CHARS: usize = 100;
+const BALLOT_MAX_CHARS: usize = 350;
+
+// A poll the member may vote in, with their current ballot
+struct Entry {
+    poll: Poll,
+    votes: Vec<crate::models::Vote>,
+}
+
+// `/poll myvotes`: the member's ballot in every active poll they may vote in, with a button per
+// open poll that brings up the usual voting UI
+pub async fn handle_my_votes(
+    database: &Database,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let guild_id = command.guild_id.ok_or("Missing guild ID")?.to_string();
+    let user_id = command.user.id.to_string();
+    let roles: Vec<String> = command
+        .member
+        .as_ref()
+        .map(|member| member.roles.iter().map(|role| role.to_string()).collect())
+        .unwrap_or_default();
+
+    // Loading every active poll and ballot can outlast the 3 seconds Discord gives a response
+    command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                .interaction_response_data(|message| message.ephemeral(true))
+        })
+        .await?;
+
+    let mut entries = match load_entries(database, &guild_id, &user_id, &roles).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            command
+                .edit_original_interaction_response(&ctx.http, |response| {
+                    response.content(format!("Failed to load your ballots: {}", e))
+                })
+                .await?;
+            return Ok(());
+        }
+    };
+    if entries.is_empty() {
+        command
+            .edit_original_interaction_response(&ctx.http, |response| {
+                response.content("There are no active polls you can vote in right now.")
+            })
+            .await?;
+        return Ok(());
+    }
+
+    // Polls closing soonest first; those without an end time last
+    entries.sort_by_key(|entry| (entry.poll.ends_at.is_none(), entry.poll.ends_at));
+    let total = entries.len();
+    entries.truncate(MAX_POLLS);
+    let description = if total > MAX_POLLS {
+        format!("Your ballots in the {} polls closing soonest, out of {} you can vote in:", MAX_POLLS, total)
+    } else {
+        "Your ballots in the active polls you can vote in:".to_string()
+    };
+
+    command
+        .edit_original_interaction_response(&ctx.http, |response| {
+            response
+                .embed(|e| {
+                    e.title("🗳️ My Votes").description(description).color((255, 165, 0));
+                    for (i, entry) in entries.iter().enumerate() {
+                        e.field(format!("{}. {}", i + 1, truncate(&entry.poll.question, QUESTION_MAX_CHARS)), ballot_summary(entry), false);
+                    }
+                    e
+                })
+                .components(|c| {
+                    let open: Vec<(usize, &Entry)> = entries
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, entry)| entry.poll.status.accepts_votes())
+                        .collect();
+                    for row in open.chunks(5) {
+                        c.create_action_row(|r| {
+                            for (i, entry) in row {
+                                let voted = has_voted(entry);
+                                r.create_button(|btn| {
+                                    btn.custom_id(format!("openVote_{}", entry.poll.id))
+                                        .label(format!("{}. {}", i + 1, if voted { "Change vote" } else { "Vote" }))
+                                        .style(if voted { ButtonStyle::Secondary } else { ButtonStyle::Primary })
+                                });
+                            }
+                            r
+                        });
+                    }
+                    c
+                })
+        })
+        .await?;
+    Ok(())
+}
+
+async fn load_entries(
+    database: &Database,
+    guild_id: &str,
+    user_id: &str,
+    roles: &[String],
+) -> Result<Vec<Entry>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut entries = Vec::new();
+    for partial in database.get_active_polls_by_guild(guild_id).await? {
+        let poll = match database.get_poll(&partial.id).await {
+            Ok(poll) => poll.with_votable_options(),
+            Err(e) => {
+                warn!("Failed to load poll {} for /poll myvotes: {}", partial.id, e);
+                continue;
+            }
+        };
+        // Same rule as the vote buttons: members need one of the allowed roles
+        if let Some(allowed_roles) = &poll.allowed_roles
+            && !roles.iter().any(|role| allowed_roles.contains(role))
+        {
+            continue;
+        }
+        let votes = database.get_user_poll_votes(&poll.id, user_id).await?;
+        entries.push(Entry { poll, votes });
+    }
+    Ok(entries)
+}
+
+fn has_voted(entry: &Entry) -> bool {
+    entry.votes.iter().any(|vote| vote.rating > 0)
+}
+
+// Where the poll stands and the member's ballot, cut short to keep the embed within Discord's limits
+fn ballot_summary(entry: &Entry) -> String {
+    let poll = &entry.poll;
+    let status = match (poll.status, poll.starts_at, poll.ends_at) {
+        (PollStatus::Scheduled, Some(starts_at), _) => format!("Voting opens <t:{}:R>", starts_at.timestamp()),
+        (PollStatus::Scheduled, None, _) => "Voting hasn't opened yet".to_string(),
+        (PollStatus::Paused, _, _) => "Voting is paused".to_string(),
+        (_, _, Some(ends_at)) => format!("{} · closes <t:{}:R>", poll.voting_method, ends_at.timestamp()),
+        _ => format!("{} · closes when ended", poll.voting_method),
+    };
+    if !has_voted(entry) {
+        let state = if entry.votes.is_empty() { "Not voted yet" } else { "Started, nothing marked yet" };
+        return format!("{}\n*{}*", status, state);
+    }
+
+    let mut ballot = String::new();
+    let formatted = format_user_vote(poll, &entry.votes);
+    let lines: Vec<&str> = formatted.lines().collect();
+    for (shown, line) in lines.iter().enumerate() {
+        if ballot.chars().count() + line.chars().count() + 1 > BALLOT_MAX_CHARS {
+            ballot.push_str(&format!("…and {} more", lines.len() - shown));
+            break;
+        }
+        ballot.push_str(line);
+        ballot.push('\n');
+    }
+    format!("{} · ✅ Voted\n{}", status, ballot.trim_end())
+}
+
@@ -1,15 +1,28 @@
-use crate::db::Database;
-use crate::models::{Poll, VotingMethod};
+use crate::db::{Database, PollIdMatch};
+use crate::export::ExportFormat;
+use crate::import::ImportFormat;
+use crate::models::{CloseReason, OutboxAction, Poll, PollOrigin, PollStatus, ReminderNotify, ResultsVisibility, VotingMethod, MAX_OPTION_LENGTH, MAX_QUESTION_LENGTH, SCHEDULER_ACTOR};
+use crate::voting::calculate_poll_results;
+use crate::permissions::{can_create_poll, can_manage_poll, is_poll_admin, Caller, PollAction};
+use crate::tasks::{outbox, scheduler};
 use chrono::Utc;
-use serenity::builder::{CreateActionRow, CreateEmbed};
-use serenity::builder::CreateApplicationCommand;
+use serenity::builder::{CreateComponents, CreateEmbed};
+use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption};
+use serenity::http::HttpError;
 use serenity::model::application::component::ButtonStyle;
 use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
 use serenity::model::application::interaction::InteractionResponseType;
-use serenity::model::id::{ChannelId, MessageId};
+use serenity::model::channel::AttachmentType;
+use serenity::model::id::{AttachmentId, ChannelId, MessageId};
 use serenity::prelude::*;
+use std::borrow::Cow;
 use log::{info, warn, error};
 
+// Standings shown on poll messages stay within an embed field's 1024 characters
+const STANDINGS_MAX_CHARS: usize = 950;
+// Stays under Discord's attachment limit for servers without boosts
+const MAX_ATTACHMENT_BYTES: usize = 8 * 1024 * 1024;
+
 pub fn create_poll_command(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
     command
         .name("poll")
@@ -24,6 +37,7 @@ pub fn create_poll_command(command: &mut CreateApplicationCommand) -> &mut Creat
                         .name("question")
                         .description("The poll question")
                         .kind(serenity::model::application::command::CommandOptionType::String)
+                        .max_length(MAX_QUESTION_LENGTH as u16)
                         .required(true)
                 })
                 .create_sub_option(|sub_option| {
@@ -36,18 +50,18 @@ pub fn create_poll_command(command: &mut CreateApplicationCommand) -> &mut Creat
                 .create_sub_option(|sub_option| {
                     sub_option
                         .name("method")
-                        .description("Voting method to use")
+                        .description("Voting method to use (default: server setting, otherwise STAR)")
                         .kind(serenity::model::application::command::CommandOptionType::String)
                         .add_string_choice("STAR", "star")
                         .add_string_choice("Plurality", "plurality")
                         .add_string_choice("Ranked Choice", "ranked")
                         .add_string_choice("Approval", "approval")
-                        .required(true)
+                        .required(false)
                 })
                 .create_sub_option(|sub_option| {
                     sub_option
                         .name("duration")
-                        .description("Duration in minutes (default: 1440 = 24 hours, 0 for manual close)")
+                        .description("Duration in minutes (default: server setting or 1440 = 24 hours, 0 for manual close)")
                         .kind(serenity::model::application::command::CommandOptionType::Integer)
                         .required(false)
                 })
@@ -58,13 +72,37 @@ pub fn create_poll_command(command: &mut CreateApplicationCommand) -> &mut Creat
                         .kind(serenity::model::application::command::CommandOptionType::Role)
                         .required(false)
                 })
-                // .create_sub_option(|sub_option| {
-                //     sub_option
-                //         .name("anonymous")
-                //         .description("Whether votes should be anonymous (default: true)")
-                //         .kind(serenity::model::application::command::CommandOptionType::Boolean)
-                //         .required(false)
-                // })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("anonymous")
+                        .description("Hide voter IDs in exports (default: server setting)")
+                        .kind(serenity::model::application::command::CommandOptionType::Boolean)
+                        .required(false)
+                })
+                .create_sub_option(results_visibility_option)
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("reminders")
+                        .description("Remind before the poll closes, e.g. 24h,1h")
+                        .kind(serenity::model::application::command::CommandOptionType::String)
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("reminder_ping")
+                        .description("Who reminders reach besides the channel post (poll admins only, default: nobody)")
+                        .kind(serenity::model::application::command::CommandOptionType::String)
+                        .add_string_choice("Mention the voter role", "role")
+                        .add_string_choice("DM members who haven't voted", "dm")
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("starts_at")
+                        .description("Open voting later, e.g. 2026-11-01 18:00 (server timezone) or a Unix timestamp")
+                        .kind(serenity::model::application::command::CommandOptionType::String)
+                        .required(false)
+                })
         })
         .create_option(|option| {
             option
@@ -76,6 +114,7 @@ pub fn create_poll_command(command: &mut CreateApplicationCommand) -> &mut Creat
                         .name("poll_id")
                         .description("ID of the poll to end")
                         .kind(serenity::model::application::command::CommandOptionType::String)
+                        .set_autocomplete(true)
                         .required(false)
                 })
         })
@@ -89,6 +128,7 @@ pub fn create_poll_command(command: &mut CreateApplicationCommand) -> &mut Creat
                         .name("poll_id")
                         .description("ID of the poll to show results for")
                         .kind(serenity::model::application::command::CommandOptionType::String)
+                        .set_autocomplete(true)
                         .required(false)
                 })
         })
@@ -104,21 +144,241 @@ pub fn create_poll_command(command: &mut CreateApplicationCommand) -> &mut Creat
                 .description("Show help information")
                 .kind(serenity::model::application::command::CommandOptionType::SubCommand)
         })
+        .create_option(|option| {
+            option
+                .name("edit")
+                .description("Change an active poll")
+                .kind(serenity::model::application::command::CommandOptionType::SubCommandGroup)
+                .create_sub_option(|sub_command| {
+                    sub_command
+                        .name("end_time")
+                        .description("Extend or shorten the poll")
+                        .kind(serenity::model::application::command::CommandOptionType::SubCommand)
+                        .create_sub_option(|sub_option| poll_id_option(sub_option, "ID of the poll to edit"))
+                        .create_sub_option(|sub_option| {
+                            sub_option
+                                .name("minutes")
+                                .description("Minutes from now (or from the start of a scheduled poll) until it closes, 0 for manual")
+                                .kind(serenity::model::application::command::CommandOptionType::Integer)
+                                .min_int_value(0)
+                                .required(true)
+                        })
+                })
+                .create_sub_option(|sub_command| {
+                    sub_command
+                        .name("rename_option")
+                        .description("Fix the text of an option")
+                        .kind(serenity::model::application::command::CommandOptionType::SubCommand)
+                        .create_sub_option(|sub_option| poll_id_option(sub_option, "ID of the poll to edit"))
+                        .create_sub_option(|sub_option| option_number_option(sub_option))
+                        .create_sub_option(|sub_option| {
+                            sub_option
+                                .name("text")
+                                .description("New text for the option")
+                                .kind(serenity::model::application::command::CommandOptionType::String)
+                                .max_length(MAX_OPTION_LENGTH as u16)
+                                .required(true)
+                        })
+                })
+                .create_sub_option(|sub_command| {
+                    sub_command
+                        .name("add_option")
+                        .description("Add a late option to the poll")
+                        .kind(serenity::model::application::command::CommandOptionType::SubCommand)
+                        .create_sub_option(|sub_option| poll_id_option(sub_option, "ID of the poll to edit"))
+                        .create_sub_option(|sub_option| {
+                            sub_option
+                                .name("text")
+                                .description("Text of the new option")
+                                .kind(serenity::model::application::command::CommandOptionType::String)
+                                .max_length(MAX_OPTION_LENGTH as u16)
+                                .required(true)
+                        })
+                })
+                .create_sub_option(|sub_command| {
+                    sub_command
+                        .name("withdraw_option")
+                        .description("Withdraw an option; existing ballots are kept")
+                        .kind(serenity::model::application::command::CommandOptionType::SubCommand)
+                        .create_sub_option(|sub_option| poll_id_option(sub_option, "ID of the poll to edit"))
+                        .create_sub_option(|sub_option| option_number_option(sub_option))
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("myvotes")
+                .description("Your ballots in every active poll you can vote in, with buttons to change them")
+                .kind(serenity::model::application::command::CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("stats")
+                .description("Turnout, voting times and ballot patterns for a poll")
+                .kind(serenity::model::application::command::CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| poll_id_option(sub_option, "ID of the poll"))
+        })
+        .create_option(|option| {
+            option
+                .name("history")
+                .description("Polls per month, turnout, top poll creators and voting methods in this server")
+                .kind(serenity::model::application::command::CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("from")
+                        .description("First day to include, as YYYY-MM-DD in the server timezone (default: the first poll)")
+                        .kind(serenity::model::application::command::CommandOptionType::String)
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("to")
+                        .description("Last day to include, as YYYY-MM-DD in the server timezone (default: today)")
+                        .kind(serenity::model::application::command::CommandOptionType::String)
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("csv")
+                        .description("Also attach every poll of the period with its ballot count as CSV")
+                        .kind(serenity::model::application::command::CommandOptionType::Boolean)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("pause")
+                .description("Freeze voting on an open poll; the end time still applies")
+                .kind(serenity::model::application::command::CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| poll_id_option(sub_option, "ID of the poll to pause"))
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("reason")
+                        .description("Why voting is paused, recorded with the poll's edits")
+                        .kind(serenity::model::application::command::CommandOptionType::String)
+                        .max_length(200)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("resume")
+                .description("Accept votes on a paused poll again")
+                .kind(serenity::model::application::command::CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| poll_id_option(sub_option, "ID of the poll to resume"))
+        })
+        .create_option(|option| {
+            option
+                .name("reopen")
+                .description("Open a closed poll again, keeping its ballots")
+                .kind(serenity::model::application::command::CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| poll_id_option(sub_option, "ID of the poll to reopen"))
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("minutes")
+                        .description("Minutes from now until it closes, 0 for manual (default: original end time if still ahead)")
+                        .kind(serenity::model::application::command::CommandOptionType::Integer)
+                        .min_int_value(0)
+                        .required(false)
+                })
+        })
         .create_option(|option| {
             option
                 .name("export")
-                .description("Export vote data for a completed poll as CSV")
+                .description("Export vote data for a completed poll as a file")
                 .kind(serenity::model::application::command::CommandOptionType::SubCommand)
                 .create_sub_option(|sub_option| {
                     sub_option
                         .name("poll_id")
                         .description("ID of the poll to export")
                         .kind(serenity::model::application::command::CommandOptionType::String)
+                        .set_autocomplete(true)
                         .required(true)
                 })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("format")
+                        .description("File format (default: CSV, one row per vote)")
+                        .kind(serenity::model::application::command::CommandOptionType::String);
+                    for format in ExportFormat::ALL {
+                        sub_option.add_string_choice(format.label(), format.as_str());
+                    }
+                    sub_option.required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("import")
+                .description("Tally ballots collected elsewhere from a CSV, JSON, BLT or ABIF file")
+                .kind(serenity::model::application::command::CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("file")
+                        .description("Ballot file (.csv, .json, .blt or .abif)")
+                        .kind(serenity::model::application::command::CommandOptionType::Attachment)
+                        .required(true)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("method")
+                        .description("Voting method to count the ballots with (default: the one the ballots fit)")
+                        .kind(serenity::model::application::command::CommandOptionType::String)
+                        .add_string_choice("STAR", "star")
+                        .add_string_choice("Plurality", "plurality")
+                        .add_string_choice("Ranked Choice", "ranked")
+                        .add_string_choice("Approval", "approval")
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("question")
+                        .description("Question to show with the results (default: the file's title)")
+                        .kind(serenity::model::application::command::CommandOptionType::String)
+                        .max_length(MAX_QUESTION_LENGTH as u16)
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("format")
+                        .description("File format (default: from the file extension)")
+                        .kind(serenity::model::application::command::CommandOptionType::String)
+                        .add_string_choice("CSV", "csv")
+                        .add_string_choice("JSON", "json")
+                        .add_string_choice("BLT", "blt")
+                        .add_string_choice("ABIF", "abif")
+                        .required(false)
+                })
         })
 }
 
+fn poll_id_option<'a>(option: &'a mut CreateApplicationCommandOption, description: &str) -> &'a mut CreateApplicationCommandOption {
+    option
+        .name("poll_id")
+        .description(description)
+        .kind(serenity::model::application::command::CommandOptionType::String)
+        .set_autocomplete(true)
+        .required(true)
+}
+
+pub fn results_visibility_option(option: &mut CreateApplicationCommandOption) -> &mut CreateApplicationCommandOption {
+    option
+        .name("results")
+        .description("Who sees the tally before the poll closes (default: nobody)")
+        .kind(serenity::model::application::command::CommandOptionType::String)
+        .add_string_choice("Hidden until close", ResultsVisibility::Hidden.as_str())
+        .add_string_choice("Voters, after they vote", ResultsVisibility::AfterVote.as_str())
+        .add_string_choice("Everyone, live on the poll", ResultsVisibility::Live.as_str())
+        .required(false)
+}
+
+fn option_number_option(option: &mut CreateApplicationCommandOption) -> &mut CreateApplicationCommandOption {
+    option
+        .name("option")
+        .description("Option number as shown on the poll")
+        .kind(serenity::model::application::command::CommandOptionType::Integer)
+        .min_int_value(1)
+        .required(true)
+}
+
 pub async fn handle_poll_command(
     database: &Database,
     ctx: &Context,
@@ -138,6 +398,12 @@ pub async fn handle_poll_command(
         "results" => handle_poll_results(database, ctx, command).await?,
         "list" => handle_list_polls(database, ctx, command).await?,
         "export" => handle_export_poll(database, ctx, command).await?,
+        "import" => handle_import_poll(database, ctx, command).await?,
+        "stats" => super::stats::handle_poll_stats(database, ctx, command).await?,
+        "history" => super::history::handle_poll_history(database, ctx, command).await?,
+        "myvotes" => super::myvotes::handle_my_votes(database, ctx, command).await?,
+        "edit" => handle_edit_poll(database, ctx, command).await?,
+        "pause" | "resume" | "reopen" => handle_poll_lifecycle(database, ctx, command, subcommand_name).await?,
         "help" => {
             command.create_interaction_response(&ctx.http, |resp| {
                 resp.kind(serenity::model::application::interaction::InteractionResponseType::ChannelMessageWithSource)
@@ -148,7 +414,9 @@ pub async fn handle_poll_command(
                                 .field("📝 Creating Polls", 
                                     "Use `/poll create` with a question, comma-separated options, and your preferred voting method.\n\
                                     For longer polls, set the duration in minutes (use 0 for manual closing).\n\
-                                    Optionally restrict voting to a specific role.", 
+                                    Use `starts_at` to post a preview now and open voting later, and `reminders` (e.g. `24h,1h`) to nudge voters before it closes.\n\
+                                    Optionally restrict voting to a specific role, make the poll anonymous, or show the `results` to voters or live on the poll.\n\
+                                    Server admins can set defaults with `/pollconfig`.", 
                                     false)
                                 .field("🗳️ Voting Methods", 
                                     "**STAR Voting**: Rate each option 0-5 stars. Combines scoring and an automatic runoff between top choices.\n\
@@ -160,8 +428,13 @@ pub async fn handle_poll_command(
                                     "• End active polls with `/poll end` (interactive) or `/poll end [poll-id]`\n\
                                     • See results with `/poll results` (interactive) or `/poll results [poll-id]`\n\
                                     • See all server polls with `/poll list`\n\
-                                    • Export vote data with `/poll export [poll-id]` (for completed polls)\n\
-                                    • Poll IDs are shown in poll embeds for reference", 
+                                    • Check or change your ballots in every open poll with `/poll myvotes`\n\
+                                    • See turnout, when people voted and ballot patterns with `/poll stats [poll-id]`\n\
+                                    • See how the server uses polls with `/poll history [from] [to]`, optionally as CSV\n\
+                                    • Freeze voting with `/poll pause`, continue with `/poll resume`, or `/poll reopen` a poll closed by mistake\n\
+                                    • Export vote data as CSV, JSON, BLT, PrefLib or ABIF with `/poll export [poll-id] [format]` (for completed polls)\n\
+                                    • Tally an election held elsewhere with `/poll import`, attaching a CSV, JSON, BLT or ABIF ballot file\n\
+                                    • Start typing a poll's question in any `poll-id` option to pick it from suggestions; the 8-character short ID works too", 
                                     false)
                                 .field("💡 Tips", 
                                     "> Keep option lists concise for better mobile experience\n\
@@ -195,12 +468,29 @@ async fn handle_create_poll(
         }
     };
 
+    let guild_id = command.guild_id.ok_or("Missing guild ID")?.to_string();
+    let settings = database.get_guild_settings(&guild_id).await?;
+
+    let caller = Caller::new(&command.user, command.member.as_ref());
+    if !can_create_poll(&caller, &settings) {
+        let msg = format!(
+            "Only members with the <@&{}> role can create polls in this server.",
+            settings.creator_role.as_deref().unwrap_or_default()
+        );
+        send_error_response(ctx, command, &msg).await?;
+        return Ok(());
+    }
+
     let mut question = String::new();
     let mut options_str = String::new();
     let mut method_str = String::new();
-    let mut duration: Option<i64> = None;
-    let mut allowed_roles: Option<Vec<String>> = None;
-    // let mut anonymous = true;
+    let mut duration: Option<i64> = settings.default_duration_minutes;
+    let mut allowed_roles: Option<Vec<String>> = settings.default_voter_role.clone().map(|role| vec![role]);
+    let mut anonymous = settings.anonymous_by_default;
+    let mut results_visibility = ResultsVisibility::Hidden;
+    let mut starts_at_str: Option<String> = None;
+    let mut reminders_str: Option<String> = None;
+    let mut reminder_notify = ReminderNotify::Channel;
 
     for option in options {
         match option.name.as_str() {
@@ -226,6 +516,27 @@ async fn handle_create_poll(
                     }
                 }
             }
+            "anonymous" => {
+                if let Some(value) = option.value.as_ref().and_then(|v| v.as_bool()) {
+                    anonymous = value;
+                }
+            }
+            "results" => {
+                if let Some(visibility) = option.value.as_ref().and_then(|v| v.as_str()).and_then(ResultsVisibility::parse) {
+                    results_visibility = visibility;
+                }
+            }
+            "starts_at" => {
+                starts_at_str = option.value.as_ref().and_then(|v| v.as_str()).map(|s| s.trim().to_string());
+            }
+            "reminders" => {
+                reminders_str = option.value.as_ref().and_then(|v| v.as_str()).map(|s| s.trim().to_string());
+            }
+            "reminder_ping" => {
+                if let Some(notify) = option.value.as_ref().and_then(|v| v.as_str()).and_then(ReminderNotify::parse) {
+                    reminder_notify = notify;
+                }
+            }
             _ => {}
         }
     }
@@ -241,23 +552,44 @@ async fn handle_create_poll(
         return Ok(());
     }
 
-    if options_vec.len() > 10 {
-        send_error_response(ctx, command, "Maximum 10 options allowed").await?;
+    let max_options = settings.max_options();
+    if options_vec.len() > max_options {
+        send_error_response(ctx, command, &format!("Maximum {} options allowed", max_options)).await?;
         return Ok(());
     }
 
-    let voting_method = match method_str.as_str() {
-        "star" => VotingMethod::Star,
-        "plurality" => VotingMethod::Plurality,
-        "ranked" => VotingMethod::Ranked,
-        "approval" => VotingMethod::Approval,
-        _ => {
-            send_error_response(ctx, command, "Invalid voting method").await?;
-            return Ok(());
+    if options_vec.iter().any(|option| option.chars().count() > MAX_OPTION_LENGTH) {
+        send_error_response(ctx, command, &format!("Options can be at most {} characters", MAX_OPTION_LENGTH)).await?;
+        return Ok(());
+    }
+
+    let voting_method = if method_str.is_empty() {
+        settings.default_method.clone().unwrap_or(VotingMethod::Star)
+    } else {
+        match VotingMethod::parse(&method_str) {
+            Some(method) => method,
+            None => {
+                send_error_response(ctx, command, "Invalid voting method").await?;
+                return Ok(());
+            }
         }
     };
 
-    let guild_id = command.guild_id.ok_or("Missing guild ID")?.to_string();
+    let starts_at = match starts_at_str.as_deref() {
+        Some(value) => match parse_start_time(value, settings.timezone()) {
+            Some(time) if time > Utc::now() => Some(time),
+            Some(_) => {
+                send_error_response(ctx, command, "The start time must be in the future").await?;
+                return Ok(());
+            }
+            None => {
+                send_error_response(ctx, command, "Couldn't read the start time. Use `YYYY-MM-DD HH:MM` (server timezone, see `/pollconfig`) or a Unix timestamp.").await?;
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
     let channel_id = command.channel_id.to_string();
     let creator_id = command.user.id.to_string();
 
@@ -271,8 +603,50 @@ async fn handle_create_poll(
         duration,
         allowed_roles,
     );
+    poll.anonymous = anonymous;
+    poll.results_visibility = results_visibility;
+    if let Some(starts_at) = starts_at {
+        poll.schedule_start(starts_at);
+    }
+
+    let reminder_offsets = match reminders_str.as_deref() {
+        Some(value) => {
+            let offsets = match parse_reminder_offsets(value) {
+                Some(offsets) => offsets,
+                None => {
+                    send_error_response(ctx, command, "Couldn't read the reminders. Use a list like `24h,1h` or `2d,30m`.").await?;
+                    return Ok(());
+                }
+            };
+            let open_minutes = match (poll.ends_at, poll.starts_at.unwrap_or(poll.created_at)) {
+                (Some(ends_at), opens_at) => (ends_at - opens_at).num_minutes(),
+                (None, _) => {
+                    send_error_response(ctx, command, "Reminders need a poll with an end time").await?;
+                    return Ok(());
+                }
+            };
+            if offsets.iter().any(|offset| *offset >= open_minutes) {
+                send_error_response(ctx, command, "Each reminder must fall while the poll is open").await?;
+                return Ok(());
+            }
+            offsets
+        }
+        None => Vec::new(),
+    };
+
+    // Pinging a role or DMing members reaches people who never asked to hear from this poll
+    if reminder_notify != ReminderNotify::Channel && !is_poll_admin(&caller, &settings) {
+        send_error_response(ctx, command, "Only server managers and poll admins can have reminders mention a role or DM members.").await?;
+        return Ok(());
+    }
 
+    // Stored as a draft until its message is posted, but rendered as it will be once published
     database.create_poll(&poll).await?;
+    if !reminder_offsets.is_empty() {
+        database.add_poll_reminders(&poll.id, &reminder_offsets, reminder_notify).await?;
+    }
+    poll.status = poll.published_status();
+    let live_results = live_results_text(database, &poll).await?;
 
     let interaction_response = command
         .create_interaction_response(&ctx.http, |response| {
@@ -280,42 +654,68 @@ async fn handle_create_poll(
                 .kind(InteractionResponseType::ChannelMessageWithSource)
                 .interaction_response_data(|message| {
                     message
-                        .embed(|e| create_poll_embed(e, &poll))
-                        .components(|c| {
-                            c.create_action_row(|row| create_poll_components(row, &poll))
-                        })
+                        .embed(|e| create_poll_embed(e, &poll, live_results.as_deref()))
+                        .components(|c| create_poll_components(c, &poll))
                 })
         })
         .await;
 
-    if let Ok(_) = interaction_response {
-        match command.get_interaction_response(&ctx.http).await {
-            Ok(message) => {
-                let message_id_str = message.id.to_string();
-                poll.message_id = Some(message_id_str.clone());
-                if let Err(e) = database.update_poll_message_id(&poll.id, &message_id_str).await {
-                    error!("Failed to update message ID for poll {}: {}", poll.id, e);
-                } else {
-                    info!("Stored message ID {} for poll {}", message_id_str, poll.id);
-                }
-            }
-            Err(e) => {
-                error!(
-                    "Failed to get interaction response message for poll {}: {}",
-                    poll.id, e
-                );
-            }
-        }
-    } else if let Err(e) = interaction_response {
-        error!(
-            "Failed to create interaction response for poll {}: {}",
-            poll.id, e
-        );
+    if let Err(e) = interaction_response {
+        error!("Failed to create interaction response for poll {}: {}", poll.id, e);
+        database.delete_poll(&poll.id).await?;
+        return Ok(());
     }
 
+    // Without the message ID the poll still works; it just can't be re-rendered later
+    poll.message_id = match command.get_interaction_response(&ctx.http).await {
+        Ok(message) => Some(message.id.to_string()),
+        Err(e) => {
+            error!("Failed to get interaction response message for poll {}: {}", poll.id, e);
+            None
+        }
+    };
+    database.mark_poll_published(&poll.id, poll.status, poll.message_id.as_deref()).await?;
+    scheduler::sync_poll(ctx, &poll).await;
+    info!("Published poll {} with message ID {:?}", poll.id, poll.message_id);
+
     Ok(())
 }
 
+// Accepts RFC 3339, `YYYY-MM-DD HH:MM` in the server's timezone, or a Unix timestamp
+fn parse_start_time(value: &str, timezone: chrono_tz::Tz) -> Option<chrono::DateTime<Utc>> {
+    if let Ok(seconds) = value.parse::<i64>() {
+        return chrono::DateTime::from_timestamp(seconds, 0);
+    }
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| chrono::NaiveDateTime::parse_from_str(value, format).ok())
+        .and_then(|naive| naive.and_local_timezone(timezone).earliest())
+        .map(|time| time.with_timezone(&Utc))
+}
+
+// "24h,1h", "2d,30m"; a bare number is minutes. Returns offsets in minutes, largest first.
+fn parse_reminder_offsets(value: &str) -> Option<Vec<i64>> {
+    let mut offsets = value
+        .split(',')
+        .map(|part| {
+            let part = part.trim().to_lowercase();
+            let (number, unit_minutes) = match part.chars().last()? {
+                'm' => (&part[..part.len() - 1], 1),
+                'h' => (&part[..part.len() - 1], 60),
+                'd' => (&part[..part.len() - 1], 1440),
+                _ => (part.as_str(), 1),
+            };
+            number.trim().parse::<i64>().ok().filter(|n| *n > 0).and_then(|n| n.checked_mul(unit_minutes))
+        })
+        .collect::<Option<Vec<i64>>>()?;
+    offsets.sort_unstable_by(|a, b| b.cmp(a));
+    offsets.dedup();
+    Some(offsets)
+}
+
 async fn handle_poll_results(
     database: &Database,
     ctx: &Context,
@@ -330,11 +730,11 @@ async fn handle_poll_results(
         .and_then(|value| value.as_str())
         .map(|s| s.to_string());
 
+    let guild_id = command.guild_id.ok_or("Missing guild ID")?.to_string();
     let poll_id = match poll_id_opt {
         Some(id) => id,
         None => {
             // No ID provided, show selection menu
-            let guild_id = command.guild_id.ok_or("Missing guild ID")?.to_string();
             let ended_polls = database.get_recently_ended_polls_by_guild(&guild_id, 25).await?;
 
             if ended_polls.is_empty() {
@@ -356,11 +756,7 @@ async fn handle_poll_results(
                                             .placeholder("Choose a poll...")
                                             .options(|opts| {
                                                 for poll in ended_polls.iter() {
-                                                    let label = if poll.question.len() > 90 {
-                                                        format!("{}...", &poll.question[..87])
-                                                    } else {
-                                                        poll.question.clone()
-                                                    };
+                                                    let label = truncate(&poll.question, 90);
                                                     opts.create_option(|o| o.label(label).value(&poll.id));
                                                 }
                                                 opts
@@ -374,27 +770,36 @@ async fn handle_poll_results(
         }
     };
 
-    let poll = match database.get_poll(&poll_id).await {
-        Ok(p) => p,
-        Err(_) => {
-            send_error_response(ctx, command, "Poll not found").await?;
-            return Ok(());
-        }
+    let Some(poll) = find_guild_poll(database, ctx, command, &guild_id, &poll_id).await? else {
+        return Ok(());
     };
 
-    if poll.is_active {
+    if poll.status == PollStatus::Scheduled {
+        let msg = match poll.starts_at {
+            Some(time) => format!("Voting on this poll doesn't open until <t:{}:f>.", time.timestamp()),
+            None => "Voting on this poll hasn't opened yet.".to_string(),
+        };
+        send_error_response(ctx, command, &msg).await?;
+        return Ok(());
+    }
+
+    let is_active = poll.status.is_active();
+    if is_active && !can_see_results_so_far(database, &poll, &command.user.id.to_string()).await? {
         let ends_at_msg = match poll.ends_at {
             Some(time) => format!("<t:{}:R>", time.timestamp()),
             None => "manually ended".to_string(),
         };
-        
-        let msg = format!("Poll is still active. Wait until {} or use `/poll end {}` to end the polling early.", ends_at_msg, poll_id);
+
+        let msg = if poll.results_visibility == ResultsVisibility::AfterVote {
+            format!("Results so far are shown to members who have voted. Vote first, or wait until the poll closes {}.", ends_at_msg)
+        } else {
+            format!("Poll is still active. Wait until {} or use `/poll end {}` to end the polling early.", ends_at_msg, poll.id)
+        };
         send_error_response(ctx, command, &msg).await?;
         return Ok(());
     }
 
-    let votes = database.get_poll_votes(&poll_id).await?;
-    let results = calculate_poll_results(&poll, &votes);
+    let results = get_poll_results(database, &poll).await?;
 
     command
         .create_interaction_response(&ctx.http, |response| {
@@ -404,15 +809,8 @@ async fn handle_poll_results(
                     message
                         .ephemeral(true)
                         .embed(|e| create_results_embed(e, &poll, &results))
-                        .components(|c| {
-                            c.create_action_row(|row| {
-                                row.create_button(|btn| {
-                                    btn.custom_id(format!("shareResults_{}", poll.id))
-                                       .label("Share Results")
-                                       .style(ButtonStyle::Primary)
-                                })
-                            })
-                        })
+                        // Results so far aren't for sharing with members who haven't voted
+                        .components(|c| super::results::create_results_components(c, &poll, &results, !is_active))
                 })
         })
         .await?;
@@ -420,7 +818,20 @@ async fn handle_poll_results(
     Ok(())
 }
 
-fn create_poll_embed<'a>(embed: &'a mut CreateEmbed, poll: &Poll) -> &'a mut CreateEmbed {
+// Whether a member may see an active poll's tally, according to its results visibility
+pub async fn can_see_results_so_far(
+    database: &Database,
+    poll: &Poll,
+    user_id: &str,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    Ok(match poll.results_visibility {
+        ResultsVisibility::Hidden => false,
+        ResultsVisibility::AfterVote => !database.get_user_poll_votes(&poll.id, user_id).await?.is_empty(),
+        ResultsVisibility::Live => true,
+    })
+}
+
+fn create_poll_embed<'a>(embed: &'a mut CreateEmbed, poll: &Poll, live_results: Option<&str>) -> &'a mut CreateEmbed {
     let method_name = match poll.voting_method {
         VotingMethod::Star => "STAR Voting",
         VotingMethod::Plurality => "Plurality Voting",
@@ -436,7 +847,14 @@ fn create_poll_embed<'a>(embed: &'a mut CreateEmbed, poll: &Poll) -> &'a mut Cre
     let options_list = poll
         .options
         .iter()
-        .map(|option| format!("• {}", option.text))
+        .enumerate()
+        .map(|(i, option)| {
+            if option.withdrawn {
+                format!("{}. ~~{}~~ (withdrawn)", i + 1, option.text)
+            } else {
+                format!("{}. {}", i + 1, option.text)
+            }
+        })
         .collect::<Vec<String>>()
         .join("\n");
 
@@ -453,20 +871,45 @@ fn create_poll_embed<'a>(embed: &'a mut CreateEmbed, poll: &Poll) -> &'a mut Cre
         }
     }
 
-    embed.footer(|f| f.text("Click the buttons below to vote!")).timestamp(poll.created_at.to_rfc3339())
+    if poll.results_visibility == ResultsVisibility::AfterVote && poll.status.is_active() {
+        embed = embed.field("Results", "Shown to you after you vote, or with `/poll results`", false);
+    }
+
+    if let Some(live_results) = live_results {
+        let name = if matches!(poll.status, PollStatus::Closed | PollStatus::Archived) { "📊 Final Results" } else { "📊 Live Results" };
+        embed = embed.field(name, live_results, false);
+    }
+
+    let footer = match poll.status {
+        PollStatus::Scheduled => {
+            if let Some(starts_at) = poll.starts_at {
+                embed = embed.field("Voting Opens", format!("<t:{0}:f> (<t:{0}:R>)", starts_at.timestamp()), false);
+            }
+            "Preview: the vote button appears when voting opens"
+        }
+        PollStatus::Paused => {
+            embed = embed.field("⏸ Voting Paused", "A moderator has paused voting on this poll.", false);
+            "Voting resumes when a moderator unpauses the poll"
+        }
+        PollStatus::Closed | PollStatus::Archived => "Voting has ended",
+        PollStatus::Draft | PollStatus::Open => "Click the buttons below to vote!",
+    };
+    embed.footer(|f| f.text(footer)).timestamp(poll.created_at.to_rfc3339())
 }
 
-// Using camelCase format for consistency
-fn create_poll_components<'a>(row: &'a mut CreateActionRow, poll: &Poll) -> &'a mut CreateActionRow {
-    if !poll.is_active {
-        return row;
+// Discord rejects empty action rows, so polls that can't be voted on get no row at all
+fn create_poll_components<'a>(components: &'a mut CreateComponents, poll: &Poll) -> &'a mut CreateComponents {
+    if !poll.status.accepts_votes() {
+        return components;
     }
 
-    row.create_button(|button| {
-        button
-            .custom_id("voteButton") // Using camelCase format for consistency
-            .style(ButtonStyle::Primary)
-            .label("Cast Your Vote")
+    components.create_action_row(|row| {
+        row.create_button(|button| {
+            button
+                .custom_id("voteButton") // Using camelCase format for consistency
+                .style(ButtonStyle::Primary)
+                .label("Cast Your Vote")
+        })
     })
 }
 
@@ -474,108 +917,182 @@ pub async fn end_poll_logic(
     database: &Database,
     ctx: &Context,
     poll_id: &str,
-    channel_id_str: &str,
-    message_id_opt: Option<String>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    closed_by: &str,
+    reason: CloseReason,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
     info!("Attempting to end poll: {}", poll_id);
 
-    database.end_poll(poll_id).await?;
-    info!("Marked poll {} as inactive in DB", poll_id);
+    // Only the caller that actually closes the poll announces it, so a poll ended by two
+    // processes (or by a member and the scheduler at once) is announced exactly once.
+    // Closing queues the message edit and results post in the outbox.
+    if !database.end_poll(poll_id, closed_by, reason).await? {
+        info!("Poll {} was already closed, not announcing it again", poll_id);
+        return Ok(false);
+    }
+    info!("Marked poll {} as inactive in DB ({}, closed by {})", poll_id, reason.as_str(), closed_by);
 
     let poll = database.get_poll(poll_id).await?;
-    info!("Fetched poll data for {}", poll_id);
-
-    let votes = database.get_poll_votes(poll_id).await?;
-    info!("Fetched {} votes for poll {}", votes.len(), poll_id);
-
-    let results = calculate_poll_results(&poll, &votes);
-    info!("Calculated results for poll {}", poll_id);
-
-    if let (Some(message_id_str), Ok(channel_id)) =
-        (message_id_opt, channel_id_str.parse::<ChannelId>())
-    {
-        if let Ok(message_id) = message_id_str.parse::<MessageId>() {
-            match ctx.http.get_message(channel_id.0, message_id.0).await {
-                Ok(mut message) => {
-                    if let Err(e) = message
-                        .edit(&ctx.http, |m| {
-                            m.components(|c| c).embed(|e| create_poll_embed(e, &poll))
-                        })
-                        .await
-                    {
-                        error!(
-                            "Failed to edit original poll message {} in channel {}: {}. Check bot permissions (View Channel, Manage Messages).",
-                            message_id_str, channel_id_str, e
-                        );
-                    } else {
-                        info!(
-                            "Successfully removed buttons from poll message {}",
-                            message_id_str
-                        );
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Failed to fetch original poll message {} in channel {}: {}. Check bot permissions (View Channel, Read Message History).",
-                        message_id_str, channel_id_str, e
-                    );
-                }
-            }
-        } else {
-            error!(
-                "Failed to parse message ID {} for poll {}",
-                message_id_str, poll_id
-            );
-        }
-    } else {
-        warn!(
-            "Could not edit original message for poll {}: Missing message_id or invalid channel_id",
-            poll_id
-        );
+    scheduler::sync_poll(ctx, &poll).await;
+
+    // Store the snapshot now so `/poll results` has it even while Discord is unreachable
+    if let Err(e) = closed_poll_results(database, &poll).await {
+        error!("Failed to store results snapshot for poll {}: {}", poll_id, e);
+    }
+
+    outbox::wake(ctx).await;
+    Ok(true)
+}
+
+// Announce a closed poll's results, in the server's announcement channel if one is configured;
+// used by the outbox worker
+pub async fn post_poll_results(
+    database: &Database,
+    ctx: &Context,
+    poll_id: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let poll = database.get_poll(poll_id).await?;
+    // Reopened since the announcement was queued; closing it again queues a new one
+    if !matches!(poll.status, PollStatus::Closed | PollStatus::Archived) {
+        info!("Poll {} is {} again, skipping its results announcement", poll.id, poll.status);
+        return Ok(());
     }
+    let results = closed_poll_results(database, &poll).await?;
+    let votes = database.get_poll_votes(&poll.id).await?;
+    let charts = crate::charts::render(&poll, &votes, &results);
 
-    let channel_id = channel_id_str.parse::<ChannelId>()?;
-    if let Err(e) = channel_id
+    let results_channel_str = match database.get_guild_settings(&poll.guild_id).await {
+        Ok(settings) => settings.announcement_channel.unwrap_or_else(|| poll.channel_id.clone()),
+        Err(e) => {
+            warn!("Failed to load settings for guild {}, announcing in poll channel: {}", poll.guild_id, e);
+            poll.channel_id.clone()
+        }
+    };
+    let channel_id = results_channel_str.parse::<ChannelId>()?;
+    channel_id
         .send_message(&ctx.http, |m| {
-            m.content(format!("Poll '{}' has ended!", poll.question))
-                .embed(|e| create_results_embed(e, &poll, &results))
+            let content = match poll.origin {
+                PollOrigin::Imported => format!("Results imported for '{}'", poll.question),
+                PollOrigin::Discord => format!("Poll '{}' has ended!", poll.question),
+            };
+            m.content(content).embed(|e| {
+                create_results_embed(e, &poll, &results);
+                // The main chart shows inside the embed; any others follow as attachments
+                if let Some(chart) = charts.first() {
+                    e.image(format!("attachment://{}", chart.file_name));
+                }
+                e
+            });
+            m.components(|c| super::results::create_results_components(c, &poll, &results, false));
+            for chart in &charts {
+                m.add_file(AttachmentType::Bytes {
+                    data: Cow::Borrowed(&chart.png),
+                    filename: chart.file_name.clone(),
+                });
+            }
+            m
         })
         .await
-    {
-        error!(
-            "Failed to send results for poll {} to channel {}: {}. Check bot permissions (View Channel, Send Messages, Embed Links).",
-            poll_id, channel_id_str, e
-        );
-    } else {
-        info!("Successfully sent results for poll {}", poll_id);
-    }
-
+        .map_err(|e| {
+            format!(
+                "Failed to send results to channel {}: {}. Check bot permissions (View Channel, Send Messages, Embed Links).",
+                results_channel_str, e
+            )
+        })?;
+    info!("Successfully sent results for poll {}", poll_id);
     Ok(())
 }
 
-async fn handle_end_poll(
+// Post a poll created outside an interaction (templates, schedules) to its channel
+pub async fn publish_poll(
     database: &Database,
     ctx: &Context,
-    command: &ApplicationCommandInteraction,
+    poll: &mut Poll,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let poll_id_opt = command
-        .data
-        .options
-        .first()
-        .and_then(|option| option.options.first())
-        .and_then(|option| option.value.as_ref())
-        .and_then(|value| value.as_str())
+    database.create_poll(poll).await?;
+    poll.status = poll.published_status();
+    let live_results = live_results_text(database, poll).await?;
+
+    let channel_id = poll.channel_id.parse::<ChannelId>()?;
+    let posted = {
+        let poll: &Poll = poll;
+        channel_id
+            .send_message(&ctx.http, |m| {
+                m.embed(|e| create_poll_embed(e, poll, live_results.as_deref()))
+                    .components(|c| create_poll_components(c, poll))
+            })
+            .await
+    };
+    let message = match posted {
+        Ok(message) => message,
+        Err(e) => {
+            // Don't leave a poll behind that nobody can see or vote on
+            error!(
+                "Failed to post poll {} in channel {}: {}. Check bot permissions (View Channel, Send Messages, Embed Links).",
+                poll.id, poll.channel_id, e
+            );
+            database.delete_poll(&poll.id).await?;
+            return Err(e.into());
+        }
+    };
+
+    let message_id = message.id.to_string();
+    database.mark_poll_published(&poll.id, poll.status, Some(&message_id)).await?;
+    poll.message_id = Some(message_id);
+    scheduler::sync_poll(ctx, poll).await;
+    info!("Posted poll {} in channel {}", poll.id, poll.channel_id);
+    Ok(())
+}
+
+// Open voting on a pending poll; the vote button is added to its preview message through the outbox
+pub async fn open_poll_logic(
+    database: &Database,
+    ctx: &Context,
+    poll_id: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if !database.open_poll(poll_id).await? {
+        info!("Poll {} was already open or has ended, nothing to do", poll_id);
+        return Ok(());
+    }
+    info!("Opened voting on poll {}", poll_id);
+
+    // Opening queued the message edit that adds the vote button
+    let poll = database.get_poll(poll_id).await?;
+    scheduler::sync_poll(ctx, &poll).await;
+    outbox::wake(ctx).await;
+    Ok(())
+}
+
+async fn handle_end_poll(
+    database: &Database,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let poll_id_opt = command
+        .data
+        .options
+        .first()
+        .and_then(|option| option.options.first())
+        .and_then(|option| option.value.as_ref())
+        .and_then(|value| value.as_str())
         .map(|s| s.to_string());
 
+    let guild_id = command.guild_id.ok_or("Missing guild ID")?.to_string();
+    let settings = database.get_guild_settings(&guild_id).await?;
+    let caller = Caller::new(&command.user, command.member.as_ref());
+
     let poll_id = match poll_id_opt {
         Some(id) => id,
         None => {
-            // No ID provided, show selection menu
-            let guild_id = command.guild_id.ok_or("Missing guild ID")?.to_string();
-            let active_polls = database.get_active_polls_by_guild(&guild_id).await?;
+            // No ID provided, show selection menu of the polls this member may end
+            let active_polls: Vec<Poll> = database
+                .get_active_polls_by_guild(&guild_id)
+                .await?
+                .into_iter()
+                .filter(|poll| can_manage_poll(&caller, poll, &settings))
+                .collect();
 
             if active_polls.is_empty() {
-                send_error_response(ctx, command, "No active polls found to end.").await?;
+                send_error_response(ctx, command, "No active polls found that you can end.").await?;
                 return Ok(());
             }
 
@@ -593,11 +1110,7 @@ async fn handle_end_poll(
                                             .placeholder("Choose a poll...")
                                             .options(|opts| {
                                                 for poll in active_polls.iter().take(25) {
-                                                    let label = if poll.question.len() > 90 {
-                                                        format!("{}...", &poll.question[..87])
-                                                    } else {
-                                                        poll.question.clone()
-                                                    };
+                                                    let label = truncate(&poll.question, 90);
                                                     opts.create_option(|o| o.label(label).value(&poll.id));
                                                 }
                                                 opts
@@ -611,26 +1124,16 @@ async fn handle_end_poll(
         }
     };
 
-    let poll = match database.get_poll(&poll_id).await {
-        Ok(p) => p,
-        Err(_) => {
-            send_error_response(ctx, command, "Poll not found").await?;
-            return Ok(());
-        }
+    let Some(poll) = find_guild_poll(database, ctx, command, &guild_id, &poll_id).await? else {
+        return Ok(());
     };
 
-    if !poll.is_active {
-        let votes = database.get_poll_votes(&poll_id).await?;
-        let results = calculate_poll_results(&poll, &votes);
+    if !poll.status.is_active() {
+        let results = get_poll_results(database, &poll).await?;
         
-        let msg = if let Some(ends_at) = poll.ends_at {
-            if ends_at < Utc::now() {
-                format!("This poll has already ended at <t:{}:f>, here are the results:", ends_at.timestamp())
-            } else {
-                "This poll has already ended, here are the results:".to_string()
-            }
-        } else {
-            "This poll has already ended, here are the results:".to_string()
+        let msg = match poll.closed_at.or(poll.ends_at.filter(|ends_at| *ends_at < Utc::now())) {
+            Some(ended_at) => format!("This poll has already ended at <t:{}:f>, here are the results:", ended_at.timestamp()),
+            None => "This poll has already ended, here are the results:".to_string(),
         };
 
         command
@@ -642,12 +1145,18 @@ async fn handle_end_poll(
                             .ephemeral(true)
                             .content(msg)
                             .embed(|e| create_results_embed(e, &poll, &results))
+                            .components(|c| super::results::create_results_components(c, &poll, &results, false))
                     })
             })
             .await?;
         return Ok(());
     }
 
+    if !can_manage_poll(&caller, &poll, &settings) {
+        send_error_response(ctx, command, PollAction::End.refusal_message()).await?;
+        return Ok(());
+    }
+
     command
         .create_interaction_response(&ctx.http, |response| {
             response
@@ -656,16 +1165,24 @@ async fn handle_end_poll(
         })
         .await?;
 
-    match end_poll_logic(database, ctx, &poll_id, &poll.channel_id, poll.message_id).await {
-        Ok(_) => {
+    let closed_by = command.user.id.to_string();
+    match end_poll_logic(database, ctx, &poll.id, &closed_by, CloseReason::Manual).await {
+        Ok(true) => {
             command
                 .edit_original_interaction_response(&ctx.http, |response| {
                     response.content("Poll ended successfully.")
                 })
                 .await?;
         }
+        Ok(false) => {
+            command
+                .edit_original_interaction_response(&ctx.http, |response| {
+                    response.content("This poll had already ended.")
+                })
+                .await?;
+        }
         Err(e) => {
-            error!("Error ending poll {} manually: {}", poll_id, e);
+            error!("Error ending poll {} manually: {}", poll.id, e);
             command
                 .edit_original_interaction_response(&ctx.http, |response| {
                     response.content(format!("Failed to end poll: {}", e))
@@ -677,6 +1194,292 @@ async fn handle_end_poll(
     Ok(())
 }
 
+// `/poll edit <action>`: change an active poll and record the change in its audit log
+async fn handle_edit_poll(
+    database: &Database,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let action = match command.data.options.first().and_then(|group| group.options.first()) {
+        Some(action) => action,
+        None => {
+            send_error_response(ctx, command, "No edit action provided").await?;
+            return Ok(());
+        }
+    };
+
+    let mut poll_id = String::new();
+    let mut option_number: Option<i64> = None;
+    let mut minutes: Option<i64> = None;
+    let mut text = String::new();
+    for option in &action.options {
+        let value = match option.value.as_ref() {
+            Some(value) => value,
+            None => continue,
+        };
+        match option.name.as_str() {
+            "poll_id" => poll_id = value.as_str().unwrap_or_default().to_string(),
+            "option" => option_number = value.as_i64(),
+            "minutes" => minutes = value.as_i64(),
+            "text" => text = value.as_str().unwrap_or_default().trim().to_string(),
+            _ => {}
+        }
+    }
+
+    let guild_id = command.guild_id.ok_or("Missing guild ID")?.to_string();
+    let Some(poll) = find_guild_poll(database, ctx, command, &guild_id, &poll_id).await? else {
+        return Ok(());
+    };
+
+    let settings = database.get_guild_settings(&guild_id).await?;
+    let caller = Caller::new(&command.user, command.member.as_ref());
+    if !can_manage_poll(&caller, &poll, &settings) {
+        send_error_response(ctx, command, PollAction::Edit.refusal_message()).await?;
+        return Ok(());
+    }
+
+    if !poll.status.is_active() {
+        send_error_response(ctx, command, "This poll has already ended and can no longer be edited.").await?;
+        return Ok(());
+    }
+
+    // Options are numbered as they appear on the poll, withdrawn ones included
+    let selected_option = option_number
+        .and_then(|n| usize::try_from(n).ok())
+        .and_then(|n| n.checked_sub(1))
+        .and_then(|index| poll.options.get(index));
+    let votable_count = poll.options.iter().filter(|option| !option.withdrawn).count();
+    let actor_id = command.user.id.to_string();
+
+    let details = match action.name.as_str() {
+        "end_time" => {
+            let minutes = minutes.unwrap_or(0);
+            // Scheduled polls count from their opening time so they can't close before they open
+            let from = poll.starts_at.filter(|_| poll.status == PollStatus::Scheduled).unwrap_or_else(Utc::now);
+            let ends_at = (minutes > 0).then(|| from + chrono::Duration::minutes(minutes));
+            let describe = |time: Option<chrono::DateTime<Utc>>| {
+                time.map_or("manual close".to_string(), |t| format!("<t:{}:f>", t.timestamp()))
+            };
+            let details = format!("Changed the end time from {} to {}", describe(poll.ends_at), describe(ends_at));
+            database.set_poll_end_time(&poll.id, ends_at, &actor_id, &details).await?;
+            details
+        }
+        "rename_option" => {
+            let option = match selected_option {
+                Some(option) => option,
+                None => {
+                    send_error_response(ctx, command, "That option number doesn't exist on this poll").await?;
+                    return Ok(());
+                }
+            };
+            if text.is_empty() {
+                send_error_response(ctx, command, "The new option text can't be empty").await?;
+                return Ok(());
+            }
+            let details = format!("Renamed option {} from \"{}\" to \"{}\"", option_number.unwrap_or_default(), option.text, text);
+            database.rename_poll_option(&poll.id, &option.id, &text, &actor_id, &details).await?;
+            details
+        }
+        "add_option" => {
+            if text.is_empty() {
+                send_error_response(ctx, command, "The new option text can't be empty").await?;
+                return Ok(());
+            }
+            let max_options = settings.max_options();
+            if votable_count >= max_options {
+                send_error_response(ctx, command, &format!("Maximum {} options allowed", max_options)).await?;
+                return Ok(());
+            }
+            let option = crate::models::PollOption {
+                id: uuid::Uuid::new_v4().to_string(),
+                text: text.clone(),
+                withdrawn: false,
+            };
+            let details = format!("Added option {}: \"{}\"", poll.options.len() + 1, text);
+            database.add_poll_option(&poll.id, &option, &actor_id, &details).await?;
+            details
+        }
+        "withdraw_option" => {
+            let option = match selected_option {
+                Some(option) if !option.withdrawn => option,
+                Some(_) => {
+                    send_error_response(ctx, command, "That option has already been withdrawn").await?;
+                    return Ok(());
+                }
+                None => {
+                    send_error_response(ctx, command, "That option number doesn't exist on this poll").await?;
+                    return Ok(());
+                }
+            };
+            if votable_count <= 2 {
+                send_error_response(ctx, command, "A poll needs at least 2 options; end the poll instead").await?;
+                return Ok(());
+            }
+            let details = format!("Withdrew option {}: \"{}\"", option_number.unwrap_or_default(), option.text);
+            database.withdraw_poll_option(&poll.id, &option.id, &actor_id, &details).await?;
+            details
+        }
+        _ => {
+            send_error_response(ctx, command, "Unknown edit action").await?;
+            return Ok(());
+        }
+    };
+    info!("Poll {} edited by {}: {}", poll.id, actor_id, details);
+
+    let poll = database.get_poll(&poll.id).await?;
+    scheduler::sync_poll(ctx, &poll).await;
+    let content = match refresh_poll_message(database, ctx, &poll).await {
+        Ok(()) => format!("Poll updated. {}.", details),
+        Err(e) => {
+            warn!("Failed to re-render poll message for poll {}, queueing a retry: {}", poll.id, e);
+            database.enqueue_outbox_action(&poll.id, OutboxAction::EditPollMessage).await?;
+            outbox::wake(ctx).await;
+            format!("Poll updated. {}.\nThe poll message couldn't be refreshed yet; the bot will keep retrying.", details)
+        }
+    };
+
+    command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| message.ephemeral(true).content(content))
+        })
+        .await?;
+
+    Ok(())
+}
+
+// `/poll pause`, `/poll resume` and `/poll reopen`
+async fn handle_poll_lifecycle(
+    database: &Database,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    action: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let options = command.data.options.first().map(|sub| sub.options.as_slice()).unwrap_or_default();
+    let option_value = |name: &str| options.iter().find(|o| o.name == name).and_then(|o| o.value.as_ref());
+    let poll_id = option_value("poll_id").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+
+    let guild_id = command.guild_id.ok_or("Missing guild ID")?.to_string();
+    let Some(poll) = find_guild_poll(database, ctx, command, &guild_id, &poll_id).await? else {
+        return Ok(());
+    };
+
+    let settings = database.get_guild_settings(&guild_id).await?;
+    let caller = Caller::new(&command.user, command.member.as_ref());
+    let permission = if action == "reopen" { PollAction::Reopen } else { PollAction::Pause };
+    if !can_manage_poll(&caller, &poll, &settings) {
+        send_error_response(ctx, command, permission.refusal_message()).await?;
+        return Ok(());
+    }
+
+    let actor_id = command.user.id.to_string();
+    let (changed, content) = match (action, poll.status) {
+        ("pause", PollStatus::Open) => {
+            let reason = option_value("reason").and_then(|v| v.as_str()).map(str::trim).filter(|r| !r.is_empty());
+            let details = match reason {
+                Some(reason) => format!("Paused voting: {}", reason),
+                None => "Paused voting".to_string(),
+            };
+            let changed = database.pause_poll(&poll.id, &actor_id, &details).await?;
+            (changed, "Voting is paused. Use `/poll resume` to continue; the poll still closes at its end time.".to_string())
+        }
+        ("resume", PollStatus::Paused) => {
+            let changed = database.resume_poll(&poll.id, &actor_id, "Resumed voting").await?;
+            (changed, "Voting has resumed.".to_string())
+        }
+        ("reopen", PollStatus::Closed) if poll.origin == PollOrigin::Imported => {
+            send_error_response(ctx, command, "This poll's ballots were imported from a file, so it can't be reopened for voting.").await?;
+            return Ok(());
+        }
+        ("reopen", PollStatus::Closed) => {
+            let now = Utc::now();
+            let ends_at = match option_value("minutes").and_then(|v| v.as_i64()) {
+                Some(0) => None,
+                Some(minutes) => Some(now + chrono::Duration::minutes(minutes)),
+                None => poll.ends_at.filter(|ends_at| *ends_at > now),
+            };
+            let details = match ends_at {
+                Some(ends_at) => format!("Reopened the poll until <t:{}:f>", ends_at.timestamp()),
+                None => "Reopened the poll until it is ended manually".to_string(),
+            };
+            let changed = database.reopen_poll(&poll.id, ends_at, &actor_id, &details).await?;
+            (changed, format!("{}. Ballots cast before it closed still count; the results are announced again when it closes.", details))
+        }
+        ("reopen", PollStatus::Archived) => {
+            let msg = "This poll's ballots were anonymized or removed by the data retention policy, so it can't be reopened.";
+            send_error_response(ctx, command, msg).await?;
+            return Ok(());
+        }
+        (_, status) => {
+            let msg = match action {
+                "pause" => format!("Only open polls can be paused; this poll is {}.", status.to_string().to_lowercase()),
+                "resume" => format!("Only paused polls can be resumed; this poll is {}.", status.to_string().to_lowercase()),
+                _ => format!("Only closed polls can be reopened; this poll is {}.", status.to_string().to_lowercase()),
+            };
+            send_error_response(ctx, command, &msg).await?;
+            return Ok(());
+        }
+    };
+
+    // Lost a race with the scheduler or another moderator
+    if !changed {
+        send_error_response(ctx, command, "The poll changed state in the meantime; check `/poll list` and try again.").await?;
+        return Ok(());
+    }
+    info!("Poll {} {}d by {}", poll.id, action, actor_id);
+
+    // The state change queued the message re-render in the outbox
+    let poll = database.get_poll(&poll.id).await?;
+    scheduler::sync_poll(ctx, &poll).await;
+    outbox::wake(ctx).await;
+
+    command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| message.ephemeral(true).content(content))
+        })
+        .await?;
+
+    Ok(())
+}
+
+// Re-render a poll's original message after the poll changed
+pub async fn refresh_poll_message(
+    database: &Database,
+    ctx: &Context,
+    poll: &Poll,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let message_id = match poll.message_id.as_deref() {
+        Some(id) => id.parse::<MessageId>()?,
+        None => {
+            warn!("Poll {} has no stored message ID, nothing to refresh", poll.id);
+            return Ok(());
+        }
+    };
+    let live_results = live_results_text(database, poll).await?;
+    let channel_id = poll.channel_id.parse::<ChannelId>()?;
+    channel_id
+        .edit_message(&ctx.http, message_id, |m| {
+            m.embed(|e| create_poll_embed(e, poll, live_results.as_deref()))
+                .components(|c| create_poll_components(c, poll))
+        })
+        .await?;
+    Ok(())
+}
+
+// Discord's "Unknown Channel" and "Unknown Message" errors: the poll message is gone for good
+pub fn is_missing_message_error(error: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    match error.downcast_ref::<serenity::Error>() {
+        Some(serenity::Error::Http(http)) => matches!(
+            &**http,
+            HttpError::UnsuccessfulRequest(response) if matches!(response.error.code, 10003 | 10008)
+        ),
+        _ => false,
+    }
+}
+
 async fn handle_list_polls(
     database: &Database,
     ctx: &Context,
@@ -704,7 +1507,16 @@ async fn handle_list_polls(
                                         "Manual".to_string(),
                                         |t| format!("<t:{}:R>", t.timestamp()),
                                     );
-                                    format!("`{}`: {} (Ends: {})", p.id, p.question, ends)
+                                    match (p.status, p.starts_at) {
+                                        (PollStatus::Scheduled, Some(starts)) => format!(
+                                            "`{}`: {} (Opens: <t:{}:R>, Ends: {})",
+                                            p.id, p.question, starts.timestamp(), ends
+                                        ),
+                                        (PollStatus::Paused, _) => {
+                                            format!("`{}`: {} (Paused, Ends: {})", p.id, p.question, ends)
+                                        }
+                                        _ => format!("`{}`: {} (Ends: {})", p.id, p.question, ends),
+                                    }
                                 })
                                 .collect::<Vec<_>>()
                                 .join("\n");
@@ -717,7 +1529,7 @@ async fn handle_list_polls(
                             let recent_list = recent_polls
                                 .iter()
                                 .map(|p| {
-                                    let ended = p.ends_at.map_or(
+                                    let ended = p.closed_at.or(p.ends_at).map_or(
                                         "N/A".to_string(),
                                         |t| format!("<t:{}:R>", t.timestamp()),
                                     );
@@ -739,16 +1551,80 @@ async fn handle_list_polls(
     Ok(())
 }
 
-pub fn calculate_poll_results(
+// Results for a poll: the snapshot stored at close time for ended polls, a fresh tally otherwise
+pub async fn get_poll_results(
+    database: &Database,
     poll: &crate::models::Poll,
-    votes: &[crate::models::Vote],
-) -> crate::voting::PollResults {
-    match poll.voting_method {
-        crate::models::VotingMethod::Star => crate::voting::star::calculate_results(poll, votes),
-        crate::models::VotingMethod::Plurality => crate::voting::plurality::calculate_results(poll, votes),
-        crate::models::VotingMethod::Ranked => crate::voting::ranked::calculate_results(poll, votes),
-        crate::models::VotingMethod::Approval => crate::voting::approval::calculate_results(poll, votes),
+) -> Result<crate::voting::PollResults, Box<dyn std::error::Error + Send + Sync>> {
+    if matches!(poll.status, PollStatus::Closed | PollStatus::Archived) {
+        return closed_poll_results(database, poll).await;
+    }
+    let votes = database.get_poll_votes(&poll.id).await?;
+    Ok(calculate_poll_results(poll, &votes))
+}
+
+// Running tally shown on the message of a live-results poll; None for other polls
+async fn live_results_text(
+    database: &Database,
+    poll: &Poll,
+) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+    if !poll.shows_live_results() {
+        return Ok(None);
     }
+    Ok(Some(results_so_far(database, poll).await?))
+}
+
+// Compact standings for the poll message and vote confirmations, short enough for an embed field
+pub async fn results_so_far(
+    database: &Database,
+    poll: &Poll,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let votes = database.get_poll_votes(&poll.id).await?;
+    let ballots = votes.iter().map(|vote| vote.user_id.as_str()).collect::<std::collections::HashSet<_>>().len();
+    if ballots == 0 {
+        return Ok("No votes yet.".to_string());
+    }
+    let closed = matches!(poll.status, PollStatus::Closed | PollStatus::Archived);
+    let results = if closed { closed_poll_results(database, poll).await? } else { calculate_poll_results(poll, &votes) };
+
+    let unit = match poll.voting_method {
+        VotingMethod::Star => "★",
+        VotingMethod::Plurality => "votes",
+        VotingMethod::Ranked => "votes in the last round",
+        VotingMethod::Approval => "approvals",
+    };
+    let mut text = format!("**{}:** {}\n", if closed { "Winner" } else { "Leading" }, results.winner);
+    for count in &results.raw_results {
+        let line = format!("`{}.` {} — {:.0} {}\n", count.rank, count.option_text, count.score, unit);
+        if text.chars().count() + line.chars().count() > STANDINGS_MAX_CHARS {
+            text.push_str("…\n");
+            break;
+        }
+        text.push_str(&line);
+    }
+    text.push_str(&format!("{} ballot{}", ballots, if ballots == 1 { "" } else { "s" }));
+    Ok(text)
+}
+
+// Results of a closed poll as stored in its snapshot, taking the snapshot first if needed, so
+// every announcement and `/poll results` call shows the same numbers
+pub async fn closed_poll_results(
+    database: &Database,
+    poll: &Poll,
+) -> Result<crate::voting::PollResults, Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(results) = database.get_results_snapshot(&poll.id).await? {
+        return Ok(results);
+    }
+    let votes = database.get_poll_votes(&poll.id).await?;
+    let results = calculate_poll_results(poll, &votes);
+    if database.save_results_snapshot_if_missing(&poll.id, &results).await? {
+        return Ok(results);
+    }
+    // Someone else stored one first
+    database
+        .get_results_snapshot(&poll.id)
+        .await?
+        .ok_or_else(|| format!("Results snapshot for poll {} disappeared", poll.id).into())
 }
 
 pub fn create_results_embed<'a>(
@@ -756,110 +1632,328 @@ pub fn create_results_embed<'a>(
     poll: &crate::models::Poll,
     results: &crate::voting::PollResults,
 ) -> &'a mut CreateEmbed {
-    // Truncate summary if it's too long for an embed field
-    let summary_display = if results.summary.len() > 1024 {
-        format!("{}...", &results.summary[..1020]) // Leave space for "..."
-    } else {
-        results.summary.clone()
+    let description = match (poll.closed_at, poll.closed_by.as_deref()) {
+        (Some(closed_at), Some(user_id)) if poll.origin == PollOrigin::Imported => format!(
+            "These results were tabulated from ballots imported by <@{}> <t:{}:f>:",
+            user_id,
+            closed_at.timestamp()
+        ),
+        (Some(closed_at), Some(SCHEDULER_ACTOR)) if poll.closed_late() => format!(
+            "Voting was due to close <t:{}:f>, but the bot was offline, so the poll closed late <t:{}:f>. Here are the results:",
+            poll.ends_at.unwrap_or(closed_at).timestamp(),
+            closed_at.timestamp()
+        ),
+        (Some(closed_at), Some(SCHEDULER_ACTOR)) => format!(
+            "The poll closed automatically <t:{}:f>. Here are the results:",
+            closed_at.timestamp()
+        ),
+        (Some(closed_at), Some(user_id)) => format!(
+            "The poll was ended by <@{}> <t:{}:f>. Here are the results:",
+            user_id,
+            closed_at.timestamp()
+        ),
+        _ if poll.status.is_active() => "Voting is still open. These are the results so far and may still change:".to_string(),
+        _ => "The poll has ended. Here are the results:".to_string(),
     };
 
     embed
         .title(format!("Results: {}", poll.question))
-        .description("The poll has ended. Here are the results:")
-        .field("Winner", &results.winner, false)
-        .field("Details", &summary_display, false) // Use the potentially truncated summary
+        .description(description)
+        .field("Winner", &results.winner, false);
+    super::results::add_summary_fields(embed, results);
+
+    if !poll.edits.is_empty() {
+        let mut edit_log = poll
+            .edits
+            .iter()
+            .map(|edit| format!("<t:{}:f> <@{}>: {}", edit.created_at.timestamp(), edit.actor_id, edit.details))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if edit_log.chars().count() > 1024 {
+            edit_log = format!("{}...", edit_log.chars().take(1020).collect::<String>());
+        }
+        embed.field("Edits", edit_log, false);
+    }
+
+    embed
         .footer(|f| f.text(format!("Poll ID: {}", poll.id)))
-        .timestamp(Utc::now().to_rfc3339())
+        .timestamp(poll.closed_at.unwrap_or_else(Utc::now).to_rfc3339())
 }
 
-// Export poll votes as CSV
+// Export a poll as an attachment: long or wide CSV, JSON, or BLT, PrefLib or ABIF ballots
 async fn handle_export_poll(
     database: &Database,
     ctx: &Context,
     command: &ApplicationCommandInteraction,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let poll_id = match command
-        .data
-        .options
-        .first()
-        .and_then(|option| option.options.first())
-        .and_then(|option| option.value.as_ref())
-        .and_then(|value| value.as_str())
-    {
+    let options = command.data.options.first().map(|sub| sub.options.as_slice()).unwrap_or_default();
+    let option_str = |name: &str| {
+        options
+            .iter()
+            .find(|o| o.name == name)
+            .and_then(|o| o.value.as_ref())
+            .and_then(|v| v.as_str())
+    };
+    let poll_id = match option_str("poll_id") {
         Some(id) => id.to_string(),
         None => {
             send_error_response(ctx, command, "No poll ID provided").await?;
             return Ok(());
         }
     };
+    let format = option_str("format").and_then(ExportFormat::parse).unwrap_or(ExportFormat::CsvLong);
 
-    let poll = match database.get_poll(&poll_id).await {
-        Ok(p) => p,
-        Err(_) => {
-            send_error_response(ctx, command, "Poll not found").await?;
-            return Ok(());
-        }
+    let guild_id = command.guild_id.ok_or("Missing guild ID")?.to_string();
+    let Some(poll) = find_guild_poll(database, ctx, command, &guild_id, &poll_id).await? else {
+        return Ok(());
     };
 
-    if poll.is_active {
+    let settings = database.get_guild_settings(&guild_id).await?;
+    let caller = Caller::new(&command.user, command.member.as_ref());
+    if !can_manage_poll(&caller, &poll, &settings) {
+        send_error_response(ctx, command, PollAction::Export.refusal_message()).await?;
+        return Ok(());
+    }
+
+    if poll.status.is_active() {
         send_error_response(ctx, command, "Cannot export data for an active poll. End the poll first with `/poll end`.").await?;
         return Ok(());
     }
 
-    let votes = database.get_poll_votes(&poll_id).await?;
-    
-    // Generate CSV content
-    let mut csv_content = String::new();
-    csv_content.push_str("User ID,Option ID,Option Text,Rating,Timestamp\n");
-    
-    for vote in &votes {
-        let option_text = poll.options.iter()
-            .find(|opt| opt.id == vote.option_id)
-            .map(|opt| opt.text.clone())
-            .unwrap_or_else(|| "Unknown Option".to_string());
-        
-        // Escape CSV values that contain commas or quotes
-        let escaped_text = if option_text.contains(',') || option_text.contains('"') {
-            format!("\"{}\"", option_text.replace('"', "\"\""))
-        } else {
-            option_text
-        };
-        
-        csv_content.push_str(&format!(
-            "{},{},{},{},{}\n",
-            vote.user_id,
-            vote.option_id,
-            escaped_text,
-            vote.rating,
-            vote.timestamp.format("%Y-%m-%d %H:%M:%S UTC")
-        ));
+    if !format.supports(&poll.voting_method) {
+        let msg = format!(
+            "{} files only hold rankings. Export {} polls as PrefLib or ABIF instead.",
+            format.label(),
+            poll.voting_method
+        );
+        send_error_response(ctx, command, &msg).await?;
+        return Ok(());
     }
 
-    // Send the CSV content as text since file attachments aren't supported in responses
+    // Large polls take a moment to render, longer than Discord waits for a first response
     command
         .create_interaction_response(&ctx.http, |response| {
             response
-                .kind(InteractionResponseType::ChannelMessageWithSource)
-                .interaction_response_data(|message| {
-                    message
-                        .ephemeral(true)
-                        .content(format!("**Exported {} votes from poll: \"{}\"**\n\n```csv\n{}\n```", 
-                            votes.len(), 
-                            poll.question,
-                            if csv_content.len() > 1800 { 
-                                format!("{}...\n(CSV truncated - too many votes to display)", &csv_content[..1800])
-                            } else { 
-                                csv_content 
-                            }
-                        ))
+                .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                .interaction_response_data(|message| message.ephemeral(true))
+        })
+        .await?;
+
+    let export = async {
+        let votes = database.get_poll_votes(&poll.id).await?;
+        let results = closed_poll_results(database, &poll).await?;
+        let voters = votes.iter().map(|vote| vote.user_id.as_str()).collect::<std::collections::HashSet<_>>().len();
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>((format.render(&poll, &votes, &results)?, voters))
+    };
+    let (file, voters) = match export.await {
+        Ok(export) => export,
+        Err(e) => {
+            error!("Failed to export poll {}: {}", poll.id, e);
+            command
+                .edit_original_interaction_response(&ctx.http, |response| {
+                    response.content(format!("Failed to export the poll: {}", e))
                 })
+                .await?;
+            return Ok(());
+        }
+    };
+
+    if file.data.len() > MAX_ATTACHMENT_BYTES {
+        command
+            .edit_original_interaction_response(&ctx.http, |response| {
+                response.content(format!(
+                    "The export is {:.1} MB, more than Discord accepts as an attachment. Try another format.",
+                    file.data.len() as f64 / (1024.0 * 1024.0)
+                ))
+            })
+            .await?;
+        return Ok(());
+    }
+
+    command
+        .edit_original_interaction_response(&ctx.http, |response| {
+            response.content(format!(
+                "Exported {} ballot{} from poll **{}** ({}). The file is attached below.",
+                voters,
+                if voters == 1 { "" } else { "s" },
+                poll.question,
+                format.label()
+            ))
+        })
+        .await?;
+    command
+        .create_followup_message(&ctx.http, |message| {
+            message
+                .ephemeral(true)
+                .add_file(AttachmentType::Bytes {
+                    data: Cow::Owned(file.data),
+                    filename: file.file_name,
+                })
+        })
+        .await?;
+    info!("Exported poll {} as {} for {}", poll.id, format.as_str(), command.user.id);
+
+    Ok(())
+}
+
+// Create a closed poll from a ballot file and announce its results
+async fn handle_import_poll(
+    database: &Database,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let options = command.data.options.first().map(|sub| sub.options.as_slice()).unwrap_or_default();
+    let option_str = |name: &str| {
+        options
+            .iter()
+            .find(|o| o.name == name)
+            .and_then(|o| o.value.as_ref())
+            .and_then(|v| v.as_str())
+    };
+
+    let guild_id = command.guild_id.ok_or("Missing guild ID")?.to_string();
+    let settings = database.get_guild_settings(&guild_id).await?;
+    let caller = Caller::new(&command.user, command.member.as_ref());
+    if !can_create_poll(&caller, &settings) {
+        let msg = format!(
+            "Only members with the <@&{}> role can create polls in this server.",
+            settings.creator_role.as_deref().unwrap_or_default()
+        );
+        send_error_response(ctx, command, &msg).await?;
+        return Ok(());
+    }
+
+    let attachment = option_str("file")
+        .and_then(|id| id.parse::<u64>().ok())
+        .and_then(|id| command.data.resolved.attachments.get(&AttachmentId(id)));
+    let attachment = match attachment {
+        Some(attachment) => attachment,
+        None => {
+            send_error_response(ctx, command, "No ballot file attached").await?;
+            return Ok(());
+        }
+    };
+    let format = match option_str("format").and_then(ImportFormat::parse).or_else(|| ImportFormat::from_file_name(&attachment.filename)) {
+        Some(format) => format,
+        None => {
+            send_error_response(ctx, command, "Unrecognized file type. Use a .csv, .json, .blt or .abif file, or pick the `format`.").await?;
+            return Ok(());
+        }
+    };
+    if attachment.size as usize > MAX_ATTACHMENT_BYTES {
+        send_error_response(ctx, command, "The ballot file is larger than 8 MB.").await?;
+        return Ok(());
+    }
+    let method = option_str("method").and_then(VotingMethod::parse);
+    let question = option_str("question").map(str::to_string);
+
+    // Downloading and tallying a large file takes longer than Discord waits for a first response
+    command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                .interaction_response_data(|message| message.ephemeral(true))
+        })
+        .await?;
+
+    let content = match attachment.download().await {
+        Ok(bytes) => match String::from_utf8(bytes) {
+            Ok(text) => format
+                .read(&text)
+                .and_then(|election| {
+                    election.into_poll(guild_id, command.channel_id.to_string(), command.user.id.to_string(), question, method)
+                }),
+            Err(_) => Err("The ballot file isn't UTF-8 text.".to_string()),
+        },
+        Err(e) => Err(format!("Failed to download the ballot file: {}", e)),
+    };
+    let imported = match content {
+        Ok(imported) => imported,
+        Err(e) => {
+            command
+                .edit_original_interaction_response(&ctx.http, |response| response.content(format!("Error: {}", e)))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    if let Err(e) = database.create_imported_poll(&imported).await {
+        error!("Failed to import ballots from {}: {}", attachment.filename, e);
+        command
+            .edit_original_interaction_response(&ctx.http, |response| response.content("Failed to save the imported poll."))
+            .await?;
+        return Ok(());
+    }
+    outbox::wake(ctx).await;
+    info!(
+        "Imported poll {} with {} ballots from {} for {}",
+        imported.poll.id, imported.ballots, attachment.filename, command.user.id
+    );
+
+    let blank = match imported.blank_ballots {
+        0 => String::new(),
+        1 => " 1 blank ballot was skipped.".to_string(),
+        n => format!(" {} blank ballots were skipped.", n),
+    };
+    command
+        .edit_original_interaction_response(&ctx.http, |response| {
+            response.content(format!(
+                "Imported {} ballot{} into poll **{}** (`{}`, {}).{} The results are being announced.",
+                imported.ballots,
+                if imported.ballots == 1 { "" } else { "s" },
+                imported.poll.question,
+                imported.poll.id,
+                imported.poll.voting_method,
+                blank
+            ))
         })
         .await?;
 
     Ok(())
 }
 
-async fn send_error_response(
+// `text` cut to `max_chars` characters, ending in "..." when shortened. Counts characters
+// rather than bytes, so it never splits one.
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    format!("{}...", text.chars().take(max_chars - 3).collect::<String>())
+}
+
+// The server's poll a typed ID (full, or the short form autocomplete shows) refers to. Tells the
+// member and returns None when there's no such poll or a short ID matches several.
+pub async fn find_guild_poll(
+    database: &Database,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    guild_id: &str,
+    poll_id: &str,
+) -> Result<Option<Poll>, Box<dyn std::error::Error + Send + Sync>> {
+    let poll_id = match database.resolve_poll_id(guild_id, poll_id).await? {
+        PollIdMatch::Id(poll_id) => poll_id,
+        PollIdMatch::Ambiguous => {
+            let msg = format!("`{}` matches several polls; type more characters of the ID.", poll_id.trim());
+            send_error_response(ctx, command, &msg).await?;
+            return Ok(None);
+        }
+    };
+    // A missing row or another guild's poll is "not found"; any other database error is real
+    match database.get_poll(&poll_id).await {
+        Ok(poll) if poll.guild_id == guild_id => Ok(Some(poll)),
+        Ok(_) => {
+            send_error_response(ctx, command, "Poll not found").await?;
+            Ok(None)
+        }
+        Err(e) if matches!(e.downcast_ref::<sqlx::Error>(), Some(sqlx::Error::RowNotFound)) => {
+            send_error_response(ctx, command, "Poll not found").await?;
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub async fn send_error_response(
     ctx: &Context,
     command: &ApplicationCommandInteraction,
     error_message: &str,
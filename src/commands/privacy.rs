@@ -0,0 +1,191 @@
+use crate::commands::poll::send_error_response;
+use crate::db::Database;
+use crate::models::ActiveBallotPolicy;
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::channel::AttachmentType;
+use serenity::model::permissions::Permissions;
+use serenity::prelude::*;
+use std::borrow::Cow;
+use std::env;
+use log::{info, error};
+
+// Privacy requests touch data from every server, so they are limited to the bot's operators,
+// listed as comma-separated user IDs in BOT_ADMIN_USER_IDS.
+fn is_bot_admin(user_id: &str) -> bool {
+    env::var("BOT_ADMIN_USER_IDS")
+        .map(|ids| ids.split(',').any(|id| id.trim() == user_id))
+        .unwrap_or(false)
+}
+
+pub fn create_privacy_command(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("privacy")
+        .description("Handle member data requests (bot operators only)")
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .create_option(|option| {
+            option
+                .name("export")
+                .description("Export every poll a member created and every ballot they cast as JSON")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("user")
+                        .description("Member whose data to export")
+                        .kind(CommandOptionType::User)
+                        .required(true)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("forget")
+                .description("Remove a member's identity from all of their ballots in every server")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("user")
+                        .description("Member to forget")
+                        .kind(CommandOptionType::User)
+                        .required(true)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("active_polls")
+                        .description("What to do with ballots in polls that are still open")
+                        .kind(CommandOptionType::String)
+                        .add_string_choice("Drop the ballot", "drop")
+                        .add_string_choice("Keep it anonymized (the member could vote again)", "anonymize")
+                        .required(true)
+                })
+        })
+}
+
+pub async fn handle_privacy_command(
+    database: &Database,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if !is_bot_admin(&command.user.id.to_string()) {
+        send_error_response(ctx, command, "Only the bot's operators can handle privacy requests.").await?;
+        return Ok(());
+    }
+
+    let subcommand = match command.data.options.first() {
+        Some(option) => option,
+        None => {
+            send_error_response(ctx, command, "No subcommand provided").await?;
+            return Ok(());
+        }
+    };
+
+    let mut target_user_id: Option<String> = None;
+    let mut active_policy = ActiveBallotPolicy::Drop;
+    for option in &subcommand.options {
+        match option.name.as_str() {
+            "user" => {
+                target_user_id = option.value.as_ref().and_then(|v| v.as_str()).map(|s| s.to_string());
+            }
+            "active_polls" if option.value.as_ref().and_then(|v| v.as_str()) == Some("anonymize") => {
+                active_policy = ActiveBallotPolicy::Anonymize;
+            }
+            _ => {}
+        }
+    }
+
+    let target_user_id = match target_user_id {
+        Some(id) => id,
+        None => {
+            send_error_response(ctx, command, "No user provided").await?;
+            return Ok(());
+        }
+    };
+
+    command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                .interaction_response_data(|message| message.ephemeral(true))
+        })
+        .await?;
+
+    match subcommand.name.as_str() {
+        "export" => {
+            let export = match database.export_user_data(&target_user_id).await {
+                Ok(export) => export,
+                Err(e) => {
+                    error!("Failed to export data for user {}: {}", target_user_id, e);
+                    command
+                        .edit_original_interaction_response(&ctx.http, |response| {
+                            response.content(format!("Failed to export data: {}", e))
+                        })
+                        .await?;
+                    return Ok(());
+                }
+            };
+            let json = serde_json::to_vec_pretty(&export)?;
+            info!(
+                "Privacy export for user {} requested by {} ({} polls, {} ballots)",
+                target_user_id,
+                command.user.id,
+                export.polls_created.len(),
+                export.ballots.len()
+            );
+
+            command
+                .edit_original_interaction_response(&ctx.http, |response| {
+                    response.content(format!(
+                        "Data for <@{}>: {} polls created, {} ballots cast. The JSON export is attached below.",
+                        target_user_id,
+                        export.polls_created.len(),
+                        export.ballots.len()
+                    ))
+                })
+                .await?;
+            command
+                .create_followup_message(&ctx.http, |message| {
+                    message
+                        .ephemeral(true)
+                        .add_file(AttachmentType::Bytes {
+                            data: Cow::Owned(json),
+                            filename: format!("user-{}-data.json", target_user_id),
+                        })
+                })
+                .await?;
+        }
+        "forget" => {
+            let content = match database.forget_user_ballots(&target_user_id, active_policy).await {
+                Ok(report) => {
+                    info!(
+                        "Forgot user {} on request of {}: {} polls pseudonymized, {} dropped, {} archived, {} vote rows",
+                        target_user_id,
+                        command.user.id,
+                        report.polls_pseudonymized,
+                        report.polls_dropped,
+                        report.polls_archived,
+                        report.vote_rows
+                    );
+                    format!(
+                        "Removed <@{}> from their ballots: {} polls kept under a pseudonym, {} active-poll ballots dropped, {} closed polls archived ({} vote rows).",
+                        target_user_id, report.polls_pseudonymized, report.polls_dropped, report.polls_archived, report.vote_rows
+                    )
+                }
+                Err(e) => {
+                    error!("Failed to forget user {}: {}", target_user_id, e);
+                    format!("Failed to remove member data: {}", e)
+                }
+            };
+            command
+                .edit_original_interaction_response(&ctx.http, |response| response.content(content))
+                .await?;
+        }
+        _ => {
+            command
+                .edit_original_interaction_response(&ctx.http, |response| response.content("Unknown subcommand"))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use crate::models::Poll;
+use crate::voting::PollResults;
+use serenity::builder::{CreateComponents, CreateEmbed};
+use serenity::model::application::component::ButtonStyle;
+
+// Discord allows 1024 characters per embed field and 6000 per message
+const FIELD_MAX_CHARS: usize = 1024;
+// The results embed also carries the description, winner and edit log
+const EMBED_SECTIONS: usize = 3;
+// Breakdown pages hold nothing else, and five full fields still fit in a message
+const PAGE_SECTIONS: usize = 5;
+
+// The results summary cut into sections that each fit an embed field. Cuts fall between
+// rounds where possible and between lines otherwise, so a round or tie-break note is only
+// split when it alone is longer than a field.
+pub fn sections(summary: &str) -> Vec<String> {
+    let paragraphs = summary
+        .split("\n\n")
+        .map(|paragraph| paragraph.trim_matches('\n'))
+        .filter(|paragraph| !paragraph.is_empty())
+        .flat_map(|paragraph| pack(paragraph.lines().flat_map(cut), "\n"));
+    pack(paragraphs, "\n\n")
+}
+
+// Joins `parts` with `separator` into as few field-sized strings as it can
+fn pack(parts: impl IntoIterator<Item = String>, separator: &str) -> Vec<String> {
+    let mut packed: Vec<String> = Vec::new();
+    for part in parts {
+        match packed.last_mut() {
+            Some(last) if last.chars().count() + separator.len() + part.chars().count() <= FIELD_MAX_CHARS => {
+                last.push_str(separator);
+                last.push_str(&part);
+            }
+            _ => packed.push(part),
+        }
+    }
+    packed
+}
+
+// A line longer than a field, in field-sized pieces
+fn cut(line: &str) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    chars.chunks(FIELD_MAX_CHARS).map(|chunk| chunk.iter().collect()).collect()
+}
+
+fn section_name(index: usize) -> &'static str {
+    if index == 0 { "Details" } else { "Details (continued)" }
+}
+
+// Whether the results embed leaves part of the summary for the full breakdown
+pub fn is_truncated(results: &PollResults) -> bool {
+    sections(&results.summary).len() > EMBED_SECTIONS
+}
+
+// The summary fields of the results embed: as many sections as fit, then a pointer to the rest
+pub fn add_summary_fields<'a>(embed: &'a mut CreateEmbed, results: &PollResults) -> &'a mut CreateEmbed {
+    let sections = sections(&results.summary);
+    for (i, section) in sections.iter().take(EMBED_SECTIONS).enumerate() {
+        embed.field(section_name(i), section, false);
+    }
+    if sections.len() > EMBED_SECTIONS {
+        let remaining = sections.len() - EMBED_SECTIONS;
+        embed.field(
+            "Full breakdown",
+            format!(
+                "{} more section{} of the count. Press **Show full breakdown** to read every round and tie-break.",
+                remaining,
+                if remaining == 1 { "" } else { "s" }
+            ),
+            false,
+        );
+    }
+    embed
+}
+
+pub fn page_count(results: &PollResults) -> usize {
+    sections(&results.summary).len().div_ceil(PAGE_SECTIONS).max(1)
+}
+
+// One page of the full breakdown, `page` counting from 0
+pub fn create_breakdown_embed<'a>(
+    embed: &'a mut CreateEmbed,
+    poll: &Poll,
+    results: &PollResults,
+    page: usize,
+) -> &'a mut CreateEmbed {
+    let sections = sections(&results.summary);
+    let pages = sections.len().div_ceil(PAGE_SECTIONS).max(1);
+    let page = page.min(pages - 1);
+    let question: String = poll.question.chars().take(230).collect();
+    embed.title(format!("Full breakdown: {}", question));
+    for (i, section) in sections.iter().enumerate().skip(page * PAGE_SECTIONS).take(PAGE_SECTIONS) {
+        embed.field(section_name(i), section, false);
+    }
+    embed.footer(|f| f.text(format!("Page {} of {} • Poll ID: {}", page + 1, pages, poll.id)))
+}
+
+// Previous and Next buttons for the breakdown, on pages that have somewhere to go
+pub fn create_breakdown_components<'a>(
+    components: &'a mut CreateComponents,
+    poll: &Poll,
+    page: usize,
+    pages: usize,
+) -> &'a mut CreateComponents {
+    if pages <= 1 {
+        return components;
+    }
+    components.create_action_row(|row| {
+        row.create_button(|btn| {
+            btn.custom_id(format!("breakdownPage_{}_{}", poll.id, page.saturating_sub(1)))
+                .label("◀ Previous")
+                .style(ButtonStyle::Secondary)
+                .disabled(page == 0)
+        })
+        .create_button(|btn| {
+            btn.custom_id(format!("breakdownPage_{}_{}", poll.id, page + 1))
+                .label("Next ▶")
+                .style(ButtonStyle::Secondary)
+                .disabled(page + 1 >= pages)
+        })
+    })
+}
+
+// Buttons under a results embed: "Share Results" where offered, and "Show full breakdown" when
+// the embed couldn't hold the whole summary. Discord rejects empty action rows, so there may be none.
+pub fn create_results_components<'a>(
+    components: &'a mut CreateComponents,
+    poll: &Poll,
+    results: &PollResults,
+    shareable: bool,
+) -> &'a mut CreateComponents {
+    let truncated = is_truncated(results);
+    if !shareable && !truncated {
+        return components;
+    }
+    components.create_action_row(|row| {
+        if shareable {
+            row.create_button(|btn| {
+                btn.custom_id(format!("shareResults_{}", poll.id))
+                    .label("Share Results")
+                    .style(ButtonStyle::Primary)
+            });
+        }
+        if truncated {
+            row.create_button(|btn| {
+                btn.custom_id(format!("showBreakdown_{}", poll.id))
+                    .label("Show full breakdown")
+                    .style(ButtonStyle::Secondary)
+            });
+        }
+        row
+    })
+}
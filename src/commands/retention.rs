@@ -0,0 +1,160 @@
+use crate::commands::poll::send_error_response;
+use crate::db::Database;
+use crate::models::RetentionPolicy;
+use crate::permissions::Caller;
+use crate::tasks::retention::{format_retention_report, global_policy_from_env};
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::permissions::Permissions;
+use serenity::prelude::*;
+
+pub fn create_retention_command(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("pollretention")
+        .description("Configure how long poll data is kept in this server")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("show")
+                .description("Show this server's retention policy")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("set")
+                .description("Change retention periods (days after a poll closes, 0 = keep forever)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("anonymize_after_days")
+                        .description("Replace voter IDs with pseudonyms after this many days")
+                        .kind(CommandOptionType::Integer)
+                        .min_int_value(0)
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("purge_ballots_after_days")
+                        .description("Delete ballots (keeping the results) after this many days")
+                        .kind(CommandOptionType::Integer)
+                        .min_int_value(0)
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("delete_after_days")
+                        .description("Delete the poll entirely after this many days")
+                        .kind(CommandOptionType::Integer)
+                        .min_int_value(0)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("reset")
+                .description("Remove this server's policy and use the bot-wide default")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("preview")
+                .description("Dry run: list what the next retention pass would remove")
+                .kind(CommandOptionType::SubCommand)
+        })
+}
+
+pub async fn handle_retention_command(
+    database: &Database,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let guild_id = match command.guild_id {
+        Some(id) => id.to_string(),
+        None => {
+            send_error_response(ctx, command, "This command can only be used in a server").await?;
+            return Ok(());
+        }
+    };
+
+    if !Caller::new(&command.user, command.member.as_ref()).has_manage_guild() {
+        send_error_response(ctx, command, "You need the Manage Server permission to change data retention.").await?;
+        return Ok(());
+    }
+
+    let subcommand = match command.data.options.first() {
+        Some(option) => option,
+        None => {
+            send_error_response(ctx, command, "No subcommand provided").await?;
+            return Ok(());
+        }
+    };
+
+    let global = global_policy_from_env();
+    let guild_policy = database.get_retention_policy(&guild_id).await?;
+
+    let content = match subcommand.name.as_str() {
+        "show" => format_policy_overview(guild_policy.as_ref(), &global),
+        "set" => {
+            let mut policy = guild_policy.unwrap_or_default();
+            for option in &subcommand.options {
+                let days = option.value.as_ref().and_then(|v| v.as_i64()).map(|v| v as i32);
+                match option.name.as_str() {
+                    "anonymize_after_days" => policy.anonymize_after_days = days,
+                    "purge_ballots_after_days" => policy.purge_ballots_after_days = days,
+                    "delete_after_days" => policy.delete_after_days = days,
+                    _ => {}
+                }
+            }
+            database.set_retention_policy(&guild_id, &policy).await?;
+            format!("Retention policy updated.\n\n{}", format_policy_overview(Some(&policy), &global))
+        }
+        "reset" => {
+            database.clear_retention_policy(&guild_id).await?;
+            format!("This server now uses the bot-wide retention policy.\n\n{}", format_policy_overview(None, &global))
+        }
+        "preview" => {
+            let report = database.find_retention_candidates(&global, Some(&guild_id)).await?;
+            let text = format_retention_report(&report);
+            // Keep within Discord's 2000 character message limit
+            if text.chars().count() > 1900 {
+                format!("{}…\n(list truncated)", text.chars().take(1900).collect::<String>())
+            } else {
+                text
+            }
+        }
+        _ => {
+            send_error_response(ctx, command, "Unknown subcommand").await?;
+            return Ok(());
+        }
+    };
+
+    command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| message.ephemeral(true).content(content))
+        })
+        .await?;
+
+    Ok(())
+}
+
+fn format_policy_overview(guild: Option<&RetentionPolicy>, global: &RetentionPolicy) -> String {
+    let effective = guild.map_or_else(|| global.clone(), |policy| policy.merged_with(global));
+    let describe = |days: Option<i32>| match days {
+        None | Some(0) => "never".to_string(),
+        Some(1) => "1 day after close".to_string(),
+        Some(d) => format!("{} days after close", d),
+    };
+
+    format!(
+        "**Effective retention policy**{}\n• Anonymize voter IDs: {}\n• Delete ballots (keep results): {}\n• Delete poll: {}",
+        if guild.is_some() { "" } else { " (bot-wide default)" },
+        describe(effective.anonymize_after_days),
+        describe(effective.purge_ballots_after_days),
+        describe(effective.delete_after_days),
+    )
+}
@@ -0,0 +1,276 @@
+use crate::commands::poll::{find_guild_poll, get_poll_results, send_error_response};
+use crate::db::{BallotStats, Database};
+use crate::models::{Poll, PollOrigin, PollStatus, ResultsVisibility, VotingMethod};
+use crate::permissions::{can_manage_poll, Caller, PollAction};
+use chrono::{DateTime, Utc};
+use log::{error, warn};
+use serenity::builder::CreateEmbed;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::id::{GuildId, UserId};
+use serenity::prelude::*;
+
+// Stop counting eligible members of very large servers after this many member list pages
+const MAX_MEMBER_PAGES: usize = 20;
+// Histogram rows and the width of their bars, small enough for one embed field
+const MAX_BUCKETS: i64 = 16;
+const BAR_WIDTH: i64 = 12;
+// Bucket widths to choose from, in seconds: the first that covers the poll in MAX_BUCKETS
+const BUCKET_SECONDS: [i64; 12] = [60, 300, 600, 1800, 3600, 10800, 21600, 43200, 86400, 172800, 604800, 2592000];
+const COMMON_RANKINGS: i64 = 5;
+
+// `/poll stats`: turnout, when people voted and how they filled in their ballots, for organizers
+pub async fn handle_poll_stats(
+    database: &Database,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let poll_id = command
+        .data
+        .options
+        .first()
+        .and_then(|sub| sub.options.iter().find(|o| o.name == "poll_id"))
+        .and_then(|o| o.value.as_ref())
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let Some(poll_id) = poll_id else {
+        send_error_response(ctx, command, "No poll ID provided").await?;
+        return Ok(());
+    };
+
+    let guild_id = command.guild_id.ok_or("Missing guild ID")?.to_string();
+    let Some(poll) = find_guild_poll(database, ctx, command, &guild_id, &poll_id).await? else {
+        return Ok(());
+    };
+
+    let settings = database.get_guild_settings(&guild_id).await?;
+    let caller = Caller::new(&command.user, command.member.as_ref());
+    if !can_manage_poll(&caller, &poll, &settings) {
+        send_error_response(ctx, command, PollAction::Stats.refusal_message()).await?;
+        return Ok(());
+    }
+
+    if matches!(poll.status, PollStatus::Draft | PollStatus::Scheduled) {
+        send_error_response(ctx, command, "Voting on this poll hasn't opened yet.").await?;
+        return Ok(());
+    }
+
+    // Counting eligible members can take several requests on large servers
+    command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                .interaction_response_data(|message| message.ephemeral(true))
+        })
+        .await?;
+
+    let mut embed = CreateEmbed::default();
+    if let Err(e) = create_stats_embed(&mut embed, database, ctx, &poll).await {
+        error!("Failed to gather stats for poll {}: {}", poll.id, e);
+        command
+            .edit_original_interaction_response(&ctx.http, |response| {
+                response.content(format!("Failed to gather the poll's statistics: {}", e))
+            })
+            .await?;
+        return Ok(());
+    }
+    command
+        .edit_original_interaction_response(&ctx.http, |response| response.set_embed(embed))
+        .await?;
+    Ok(())
+}
+
+async fn create_stats_embed(
+    embed: &mut CreateEmbed,
+    database: &Database,
+    ctx: &Context,
+    poll: &Poll,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let stats = database.get_ballot_stats(&poll.id).await?;
+    let question: String = poll.question.chars().take(240).collect();
+    embed
+        .title(format!("Stats: {}", question))
+        .footer(|f| f.text(format!("Poll ID: {}", poll.id)))
+        .color((255, 165, 0));
+
+    // Imported ballots have no Discord members or voting times behind them
+    if poll.origin == PollOrigin::Discord {
+        embed.field("Turnout", turnout(ctx, poll, &stats).await, false);
+        embed.field("Ballots over time", histogram(database, poll, stats.ballots).await?, false);
+    }
+    embed.field("Ballots", ballot_patterns(poll, &stats), false);
+
+    if matches!(poll.voting_method, VotingMethod::Ranked) && stats.ballots > stats.blank {
+        // Exhausted ballots and popular rankings give away the standings
+        if poll.status.is_active() && poll.results_visibility != ResultsVisibility::Live {
+            embed.field("Rankings", "Exhausted ballots and the most common rankings are shown once the poll closes.", false);
+        } else {
+            embed.field("Rankings", rankings(database, poll, &stats).await?, false);
+        }
+    }
+    Ok(())
+}
+
+async fn turnout(ctx: &Context, poll: &Poll, stats: &BallotStats) -> String {
+    let voted = format!("**{}** member{} cast a ballot", stats.ballots, if stats.ballots == 1 { "" } else { "s" });
+    match count_eligible_members(ctx, poll).await {
+        Ok((eligible, complete)) => {
+            let share = if eligible > 0 && complete {
+                format!(" ({:.0}%)", stats.ballots as f64 * 100.0 / eligible as f64)
+            } else {
+                String::new()
+            };
+            let who = if poll.allowed_roles.is_some() { "members with the voter role" } else { "members" };
+            let at_least = if complete { "" } else { "at least " };
+            format!("{} out of {}{} eligible {}{}", voted, at_least, eligible, who, share)
+        }
+        Err(e) => {
+            warn!("Could not count eligible members for poll {}: {}", poll.id, e);
+            format!("{}. The number of eligible members is unknown: the bot needs the Server Members intent to count them.", voted)
+        }
+    }
+}
+
+// Members who may vote: everyone but bots, or only those with one of the poll's allowed roles.
+// Listing members needs the Server Members privileged intent. The flag is false when the count
+// stopped at MAX_MEMBER_PAGES.
+async fn count_eligible_members(
+    ctx: &Context,
+    poll: &Poll,
+) -> Result<(usize, bool), Box<dyn std::error::Error + Send + Sync>> {
+    let guild_id = poll.guild_id.parse::<GuildId>()?;
+    let mut eligible = 0;
+    let mut after: Option<UserId> = None;
+    for _ in 0..MAX_MEMBER_PAGES {
+        let members = guild_id.members(&ctx.http, Some(1000), after).await?;
+        let Some(last) = members.last() else { return Ok((eligible, true)) };
+        after = Some(last.user.id);
+
+        eligible += members
+            .iter()
+            .filter(|member| {
+                !member.user.bot
+                    && poll.allowed_roles.as_ref().is_none_or(|roles| {
+                        member.roles.iter().any(|role| roles.contains(&role.to_string()))
+                    })
+            })
+            .count();
+        if members.len() < 1000 {
+            return Ok((eligible, true));
+        }
+    }
+    Ok((eligible, false))
+}
+
+// When ballots were cast (or last changed), as a text bar chart from opening to close
+async fn histogram(
+    database: &Database,
+    poll: &Poll,
+    ballots: i64,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    if ballots == 0 {
+        return Ok("No ballots yet.".to_string());
+    }
+    let start = poll.starts_at.unwrap_or(poll.created_at);
+    let end = match poll.closed_at {
+        Some(closed_at) if !poll.status.is_active() => closed_at,
+        _ => Utc::now(),
+    };
+    let span = (end - start).num_seconds().max(1);
+    let width = BUCKET_SECONDS
+        .into_iter()
+        .find(|width| (span + width - 1) / width <= MAX_BUCKETS)
+        .unwrap_or(BUCKET_SECONDS[BUCKET_SECONDS.len() - 1]);
+    let count = ((span + width - 1) / width).clamp(1, MAX_BUCKETS);
+
+    // Late votes on a reopened poll or clock skew land in the nearest bucket
+    let mut buckets = vec![0i64; count as usize];
+    for (bucket, ballots) in database.get_ballot_time_histogram(&poll.id, start, width).await? {
+        buckets[bucket.clamp(0, count - 1) as usize] += ballots;
+    }
+    let busiest = buckets.iter().copied().max().unwrap_or(0).max(1);
+    let style = if width >= 86400 { "d" } else if span <= 86400 { "t" } else { "f" };
+
+    let mut text = format!("Each row covers {}:\n", duration(width));
+    for (i, ballots) in buckets.iter().enumerate() {
+        let at: DateTime<Utc> = start + chrono::Duration::seconds(i as i64 * width);
+        let bar = "█".repeat(((ballots * BAR_WIDTH + busiest - 1) / busiest) as usize);
+        text.push_str(&format!("<t:{}:{}> `{:<width$}` {}\n", at.timestamp(), style, bar, ballots, width = BAR_WIDTH as usize));
+    }
+    Ok(text)
+}
+
+fn duration(seconds: i64) -> String {
+    let (amount, unit) = match seconds {
+        s if s % 86400 == 0 => (s / 86400, "day"),
+        s if s % 3600 == 0 => (s / 3600, "hour"),
+        s => (s / 60, "minute"),
+    };
+    if amount == 1 { format!("1 {}", unit) } else { format!("{} {}s", amount, unit) }
+}
+
+fn ballot_patterns(poll: &Poll, stats: &BallotStats) -> String {
+    if stats.ballots == 0 {
+        return "No ballots are stored for this poll.".to_string();
+    }
+    let share = |count: i64| count as f64 * 100.0 / stats.ballots as f64;
+    let mut lines = vec![format!(
+        "**{}** left blank ({:.0}%): opened the ballot but marked nothing",
+        stats.blank,
+        share(stats.blank)
+    )];
+    let (marked, bullet) = match poll.voting_method {
+        VotingMethod::Star => ("options given stars", "gave stars to a single option"),
+        VotingMethod::Ranked => ("options ranked", "ranked a single option"),
+        VotingMethod::Approval => ("options approved", "approved a single option"),
+        // Every plurality ballot marks one option
+        VotingMethod::Plurality => return lines.join("\n"),
+    };
+    let options = poll.options.iter().filter(|option| !option.withdrawn).count();
+    lines.push(format!("**{:.1}** of {} {} on average", stats.average_marked, options, marked));
+    lines.push(format!("**{}** bullet votes ({:.0}%): {}", stats.bullet, share(stats.bullet), bullet));
+    lines.join("\n")
+}
+
+// Ranked polls: how many ballots ran out of choices before the last round, and the favourite
+// complete orderings
+async fn rankings(
+    database: &Database,
+    poll: &Poll,
+    stats: &BallotStats,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let results = get_poll_results(database, poll).await?;
+    let finalists: Vec<String> = results
+        .rounds
+        .last()
+        .map_or(&results.raw_results, |round| &round.counts)
+        .iter()
+        .map(|count| count.option_id.clone())
+        .collect();
+    let exhausted = database.count_exhausted_ballots(&poll.id, &finalists).await?;
+    let ranked = stats.ballots - stats.blank;
+    let mut text = format!(
+        "**{}** exhausted ballot{} ({:.0}%): every option they ranked was out before the final round\n",
+        exhausted,
+        if exhausted == 1 { "" } else { "s" },
+        exhausted as f64 * 100.0 / ranked as f64
+    );
+
+    let common = database.get_common_full_rankings(&poll.id, COMMON_RANKINGS).await?;
+    if common.is_empty() {
+        text.push_str("No ballot ranked every option.");
+        return Ok(text);
+    }
+    text.push_str("Most common complete rankings:\n");
+    for (ranking, ballots) in common {
+        let order = ranking
+            .iter()
+            .map(|id| poll.options.iter().find(|option| &option.id == id).map_or("?", |option| option.text.as_str()))
+            .collect::<Vec<_>>()
+            .join(" > ");
+        let line: String = format!("`{}×` {}", ballots, order).chars().take(160).collect();
+        text.push_str(&line);
+        text.push('\n');
+    }
+    Ok(text)
+}
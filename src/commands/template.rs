@@ -0,0 +1,395 @@
+use crate::commands::poll::{publish_poll, results_visibility_option, send_error_response};
+use crate::db::Database;
+use crate::models::{PollTemplate, Recurrence, ResultsVisibility, VotingMethod};
+use crate::permissions::{can_create_poll, is_poll_admin, Caller};
+use chrono::{NaiveTime, Utc};
+use chrono_tz::Tz;
+use serenity::builder::{CreateApplicationCommand, CreateApplicationCommandOption};
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::application::interaction::InteractionResponseType;
+use serenity::model::channel::ChannelType;
+use serenity::prelude::*;
+use log::{info, error};
+
+pub fn create_template_command(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("polltemplate")
+        .description("Save polls you run often and post them on a schedule")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("save")
+                .description("Save a poll template (poll admins only)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| template_name_option(sub_option))
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("question")
+                        .description("The poll question")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("options")
+                        .description("Comma-separated list of options")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("method")
+                        .description("Voting method to use (default: server setting, otherwise STAR)")
+                        .kind(CommandOptionType::String)
+                        .add_string_choice("STAR", "star")
+                        .add_string_choice("Plurality", "plurality")
+                        .add_string_choice("Ranked Choice", "ranked")
+                        .add_string_choice("Approval", "approval")
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("duration")
+                        .description("Duration in minutes (default: server setting, 0 for manual close)")
+                        .kind(CommandOptionType::Integer)
+                        .min_int_value(0)
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("allowed_role")
+                        .description("Restrict voting to members with this role")
+                        .kind(CommandOptionType::Role)
+                        .required(false)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("anonymous")
+                        .description("Hide voter IDs in exports (default: server setting)")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+                .create_sub_option(results_visibility_option)
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("channel")
+                        .description("Channel for scheduled polls (default: this channel)")
+                        .kind(CommandOptionType::Channel)
+                        .channel_types(&[ChannelType::Text, ChannelType::News])
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("post")
+                .description("Post a poll from a template in this channel")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| template_name_option(sub_option))
+        })
+        .create_option(|option| {
+            option
+                .name("list")
+                .description("List this server's poll templates and schedules")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("delete")
+                .description("Delete a template and its schedule (poll admins only)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| template_name_option(sub_option))
+        })
+        .create_option(|option| {
+            option
+                .name("schedule")
+                .description("Post a template automatically, e.g. every Friday at 18:00 (poll admins only)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| template_name_option(sub_option))
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("days")
+                        .description("e.g. fri, mon,wed,fri, daily, weekdays or weekends")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("time")
+                        .description("Local time as HH:MM, e.g. 18:00")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                })
+                .create_sub_option(|sub_option| {
+                    sub_option
+                        .name("timezone")
+                        .description("IANA timezone, e.g. Europe/Berlin (default: server timezone)")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("unschedule")
+                .description("Stop posting a template automatically (poll admins only)")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|sub_option| template_name_option(sub_option))
+        })
+}
+
+fn template_name_option(option: &mut CreateApplicationCommandOption) -> &mut CreateApplicationCommandOption {
+    option
+        .name("name")
+        .description("Template name")
+        .kind(CommandOptionType::String)
+        .max_length(50)
+        .required(true)
+}
+
+pub async fn handle_template_command(
+    database: &Database,
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let guild_id = match command.guild_id {
+        Some(id) => id.to_string(),
+        None => {
+            send_error_response(ctx, command, "This command can only be used in a server").await?;
+            return Ok(());
+        }
+    };
+
+    let subcommand = match command.data.options.first() {
+        Some(option) => option,
+        None => {
+            send_error_response(ctx, command, "No subcommand provided").await?;
+            return Ok(());
+        }
+    };
+
+    let settings = database.get_guild_settings(&guild_id).await?;
+    let caller = Caller::new(&command.user, command.member.as_ref());
+
+    // Saved templates post polls on their own, so managing them is for poll admins; anyone who
+    // may create polls can post and list them.
+    let allowed = match subcommand.name.as_str() {
+        "post" | "list" => can_create_poll(&caller, &settings),
+        _ => is_poll_admin(&caller, &settings),
+    };
+    if !allowed {
+        send_error_response(ctx, command, "You don't have permission to manage poll templates in this server.").await?;
+        return Ok(());
+    }
+
+    let string_option = |name: &str| {
+        subcommand
+            .options
+            .iter()
+            .find(|option| option.name == name)
+            .and_then(|option| option.value.as_ref())
+            .and_then(|value| value.as_str())
+            .map(|value| value.trim().to_string())
+    };
+    let name = string_option("name").unwrap_or_default();
+
+    let content = match subcommand.name.as_str() {
+        "save" => {
+            let options: Vec<String> = string_option("options")
+                .unwrap_or_default()
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+            if options.len() < 2 {
+                send_error_response(ctx, command, "You need at least 2 options for a poll").await?;
+                return Ok(());
+            }
+            let max_options = settings.max_options();
+            if options.len() > max_options {
+                send_error_response(ctx, command, &format!("Maximum {} options allowed", max_options)).await?;
+                return Ok(());
+            }
+
+            // Server defaults are resolved now, so later `/pollconfig` changes don't alter saved templates
+            let mut template = PollTemplate {
+                id: uuid::Uuid::new_v4().to_string(),
+                guild_id: guild_id.clone(),
+                name: name.clone(),
+                channel_id: command.channel_id.to_string(),
+                created_by: command.user.id.to_string(),
+                question: string_option("question").unwrap_or_default(),
+                options,
+                voting_method: settings.default_method.clone().unwrap_or(VotingMethod::Star),
+                duration_minutes: settings.default_duration_minutes,
+                allowed_roles: settings.default_voter_role.clone().map(|role| vec![role]),
+                anonymous: settings.anonymous_by_default,
+                results_visibility: ResultsVisibility::Hidden,
+                recurrence: None,
+                next_run_at: None,
+            };
+            for option in &subcommand.options {
+                let value = match option.value.as_ref() {
+                    Some(value) => value,
+                    None => continue,
+                };
+                match option.name.as_str() {
+                    "method" => {
+                        if let Some(method) = value.as_str().and_then(VotingMethod::parse) {
+                            template.voting_method = method;
+                        }
+                    }
+                    "duration" => template.duration_minutes = value.as_i64(),
+                    "allowed_role" => template.allowed_roles = value.as_str().map(|role| vec![role.to_string()]),
+                    "anonymous" => template.anonymous = value.as_bool().unwrap_or(false),
+                    "results" => {
+                        if let Some(visibility) = value.as_str().and_then(ResultsVisibility::parse) {
+                            template.results_visibility = visibility;
+                        }
+                    }
+                    "channel" => {
+                        if let Some(channel) = value.as_str() {
+                            template.channel_id = channel.to_string();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            database.save_template(&template).await?;
+            info!("Saved poll template '{}' in guild {}", name, guild_id);
+            format!("Saved template **{}**. Post it with `/polltemplate post` or set a schedule with `/polltemplate schedule`.", name)
+        }
+        "post" => {
+            let template = match database.get_template(&guild_id, &name).await? {
+                Some(template) => template,
+                None => {
+                    send_error_response(ctx, command, "Template not found").await?;
+                    return Ok(());
+                }
+            };
+            let mut poll = template.instantiate();
+            poll.channel_id = command.channel_id.to_string();
+            poll.creator_id = command.user.id.to_string();
+            match publish_poll(database, ctx, &mut poll).await {
+                Ok(()) => format!("Posted **{}** from template **{}**.", poll.question, name),
+                Err(e) => {
+                    error!("Failed to post template '{}' in guild {}: {}", name, guild_id, e);
+                    format!("Failed to post the poll: {}", e)
+                }
+            }
+        }
+        "list" => {
+            let templates = database.get_templates_by_guild(&guild_id).await?;
+            if templates.is_empty() {
+                "No poll templates yet. Save one with `/polltemplate save`.".to_string()
+            } else {
+                let lines = templates
+                    .iter()
+                    .map(|template| {
+                        let schedule = match (&template.recurrence, template.next_run_at) {
+                            (Some(recurrence), Some(next)) => {
+                                format!("{}, next <t:{}:R>", recurrence, next.timestamp())
+                            }
+                            _ => "not scheduled".to_string(),
+                        };
+                        format!(
+                            "• **{}**: {} ({}, {} options) in <#{}>, {}",
+                            template.name,
+                            template.question,
+                            template.voting_method,
+                            template.options.len(),
+                            template.channel_id,
+                            schedule
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                // Keep within Discord's 2000 character message limit
+                if lines.chars().count() > 1900 {
+                    format!("{}…\n(list truncated)", lines.chars().take(1900).collect::<String>())
+                } else {
+                    lines
+                }
+            }
+        }
+        "delete" => {
+            if database.delete_template(&guild_id, &name).await? {
+                format!("Deleted template **{}**.", name)
+            } else {
+                send_error_response(ctx, command, "Template not found").await?;
+                return Ok(());
+            }
+        }
+        "schedule" => {
+            let template = match database.get_template(&guild_id, &name).await? {
+                Some(template) => template,
+                None => {
+                    send_error_response(ctx, command, "Template not found").await?;
+                    return Ok(());
+                }
+            };
+            let days = match string_option("days").as_deref().and_then(Recurrence::parse_days) {
+                Some(days) => days,
+                None => {
+                    send_error_response(ctx, command, "Couldn't read the days. Use e.g. `fri`, `mon,wed,fri`, `daily`, `weekdays` or `weekends`.").await?;
+                    return Ok(());
+                }
+            };
+            let time = match string_option("time").and_then(|time| NaiveTime::parse_from_str(&time, "%H:%M").ok()) {
+                Some(time) => time,
+                None => {
+                    send_error_response(ctx, command, "Couldn't read the time. Use 24-hour `HH:MM`, e.g. `18:00`.").await?;
+                    return Ok(());
+                }
+            };
+            let timezone = match string_option("timezone") {
+                Some(name) => match name.parse::<Tz>() {
+                    Ok(tz) => tz,
+                    Err(_) => {
+                        let msg = format!("Unknown timezone `{}`. Use an IANA name such as `Europe/Berlin`.", name);
+                        send_error_response(ctx, command, &msg).await?;
+                        return Ok(());
+                    }
+                },
+                None => settings.timezone(),
+            };
+
+            let recurrence = Recurrence { days, time, timezone };
+            let next_run_at = recurrence.next_after(Utc::now());
+            database.set_template_schedule(&template.id, Some(&recurrence), next_run_at).await?;
+            info!("Scheduled poll template '{}' in guild {}: {}", name, guild_id, recurrence);
+            match next_run_at {
+                Some(next) => format!(
+                    "Template **{}** will be posted in <#{}> {}. First poll: <t:{}:f>.",
+                    name, template.channel_id, recurrence, next.timestamp()
+                ),
+                None => format!("Template **{}** is scheduled {}, but no upcoming time could be found.", name, recurrence),
+            }
+        }
+        "unschedule" => {
+            let template = match database.get_template(&guild_id, &name).await? {
+                Some(template) => template,
+                None => {
+                    send_error_response(ctx, command, "Template not found").await?;
+                    return Ok(());
+                }
+            };
+            database.set_template_schedule(&template.id, None, None).await?;
+            format!("Template **{}** will no longer be posted automatically.", name)
+        }
+        _ => {
+            send_error_response(ctx, command, "Unknown subcommand").await?;
+            return Ok(());
+        }
+    };
+
+    command
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| message.ephemeral(true).content(content))
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use super::Database;
+use crate::models::{PollEdit, PollOption};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Row, Transaction};
+
+impl Database {
+    pub(super) async fn init_edits_schema(pool: &PgPool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query("ALTER TABLE poll_options ADD COLUMN IF NOT EXISTS withdrawn BOOLEAN NOT NULL DEFAULT FALSE;")
+            .execute(pool)
+            .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS poll_edits (
+                id BIGSERIAL PRIMARY KEY,
+                poll_id TEXT NOT NULL REFERENCES polls(id) ON DELETE CASCADE,
+                actor_id TEXT NOT NULL,
+                action TEXT NOT NULL,
+                details TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS poll_edits_poll_id_idx ON poll_edits (poll_id);")
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    // Audit log for a poll, oldest first
+    pub async fn get_poll_edits(
+        &self,
+        poll_id: &str,
+    ) -> Result<Vec<PollEdit>, Box<dyn std::error::Error + Send + Sync>> {
+        let edits = sqlx::query(
+            r#"
+            SELECT actor_id, action, details, created_at
+            FROM poll_edits
+            WHERE poll_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(poll_id)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| PollEdit {
+            actor_id: row.get("actor_id"),
+            action: row.get("action"),
+            details: row.get("details"),
+            created_at: row.get("created_at"),
+        })
+        .collect();
+        Ok(edits)
+    }
+
+    // Change (or remove, with None) a poll's end time
+    pub async fn set_poll_end_time(
+        &self,
+        poll_id: &str,
+        ends_at: Option<DateTime<Utc>>,
+        actor_id: &str,
+        details: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("UPDATE polls SET ends_at = $2 WHERE id = $1")
+            .bind(poll_id)
+            .bind(ends_at)
+            .execute(&mut *tx)
+            .await?;
+        // Reminders that are in the future again after an extension should fire again
+        sqlx::query(
+            r#"
+            UPDATE poll_reminders
+            SET sent_at = NULL
+            WHERE poll_id = $1 AND $2::TIMESTAMPTZ - make_interval(mins => offset_minutes::INTEGER) > NOW()
+            "#,
+        )
+        .bind(poll_id)
+        .bind(ends_at)
+        .execute(&mut *tx)
+        .await?;
+        record_edit(&mut tx, poll_id, actor_id, "end_time", details).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn rename_poll_option(
+        &self,
+        poll_id: &str,
+        option_id: &str,
+        text: &str,
+        actor_id: &str,
+        details: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("UPDATE poll_options SET text = $3 WHERE id = $2 AND poll_id = $1")
+            .bind(poll_id)
+            .bind(option_id)
+            .bind(text)
+            .execute(&mut *tx)
+            .await?;
+        record_edit(&mut tx, poll_id, actor_id, "rename_option", details).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Append a late option after the existing ones. Ballots cast before it was added simply
+    // don't mention it (unrated for STAR, unranked for IRV, unapproved for approval).
+    pub async fn add_poll_option(
+        &self,
+        poll_id: &str,
+        option: &PollOption,
+        actor_id: &str,
+        details: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO poll_options (id, poll_id, text, position)
+            SELECT $1, $2, $3, COALESCE(MAX(position) + 1, 0)
+            FROM poll_options
+            WHERE poll_id = $2
+            "#,
+        )
+        .bind(&option.id)
+        .bind(poll_id)
+        .bind(&option.text)
+        .execute(&mut *tx)
+        .await?;
+        record_edit(&mut tx, poll_id, actor_id, "add_option", details).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Withdrawn options keep their row and votes; the tabulators skip them
+    pub async fn withdraw_poll_option(
+        &self,
+        poll_id: &str,
+        option_id: &str,
+        actor_id: &str,
+        details: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("UPDATE poll_options SET withdrawn = TRUE WHERE id = $2 AND poll_id = $1")
+            .bind(poll_id)
+            .bind(option_id)
+            .execute(&mut *tx)
+            .await?;
+        record_edit(&mut tx, poll_id, actor_id, "withdraw_option", details).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+pub(super) async fn record_edit(
+    tx: &mut Transaction<'_, Postgres>,
+    poll_id: &str,
+    actor_id: &str,
+    action: &str,
+    details: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    sqlx::query("INSERT INTO poll_edits (poll_id, actor_id, action, details) VALUES ($1, $2, $3, $4)")
+        .bind(poll_id)
+        .bind(actor_id)
+        .bind(action)
+        .bind(details)
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
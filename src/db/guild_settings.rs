@@ -0,0 +1,108 @@
+use super::Database;
+use crate::models::{GuildSettings, VotingMethod};
+use sqlx::{PgPool, Row};
+
+impl Database {
+    pub(super) async fn init_guild_settings_schema(pool: &PgPool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS guild_settings (
+                guild_id TEXT PRIMARY KEY,
+                default_method TEXT,
+                default_duration_minutes BIGINT,
+                creator_role TEXT,
+                default_voter_role TEXT,
+                announcement_channel TEXT,
+                anonymous_by_default BOOLEAN NOT NULL DEFAULT FALSE,
+                max_options INTEGER,
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query("ALTER TABLE guild_settings ADD COLUMN IF NOT EXISTS admin_role TEXT, ADD COLUMN IF NOT EXISTS timezone TEXT;")
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    // Get a guild's settings, or the built-in defaults if it never configured any
+    pub async fn get_guild_settings(
+        &self,
+        guild_id: &str,
+    ) -> Result<GuildSettings, Box<dyn std::error::Error + Send + Sync>> {
+        let row = sqlx::query(
+            r#"
+            SELECT default_method, default_duration_minutes, creator_role, admin_role, default_voter_role,
+                   announcement_channel, anonymous_by_default, max_options, timezone
+            FROM guild_settings
+            WHERE guild_id = $1
+            "#,
+        )
+        .bind(guild_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let settings = match row {
+            Some(row) => GuildSettings {
+                guild_id: guild_id.to_string(),
+                default_method: row
+                    .get::<Option<String>, _>("default_method")
+                    .and_then(|method| VotingMethod::parse(&method)),
+                default_duration_minutes: row.get("default_duration_minutes"),
+                creator_role: row.get("creator_role"),
+                admin_role: row.get("admin_role"),
+                default_voter_role: row.get("default_voter_role"),
+                announcement_channel: row.get("announcement_channel"),
+                anonymous_by_default: row.get("anonymous_by_default"),
+                max_options: row.get("max_options"),
+                timezone: row.get("timezone"),
+            },
+            None => GuildSettings {
+                guild_id: guild_id.to_string(),
+                ..Default::default()
+            },
+        };
+        Ok(settings)
+    }
+
+    // Create or replace a guild's settings
+    pub async fn save_guild_settings(
+        &self,
+        settings: &GuildSettings,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO guild_settings (guild_id, default_method, default_duration_minutes, creator_role, default_voter_role,
+                                        announcement_channel, anonymous_by_default, max_options, admin_role, timezone, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, NOW())
+            ON CONFLICT (guild_id)
+            DO UPDATE SET default_method = EXCLUDED.default_method,
+                          default_duration_minutes = EXCLUDED.default_duration_minutes,
+                          creator_role = EXCLUDED.creator_role,
+                          default_voter_role = EXCLUDED.default_voter_role,
+                          announcement_channel = EXCLUDED.announcement_channel,
+                          anonymous_by_default = EXCLUDED.anonymous_by_default,
+                          max_options = EXCLUDED.max_options,
+                          admin_role = EXCLUDED.admin_role,
+                          timezone = EXCLUDED.timezone,
+                          updated_at = NOW()
+            "#,
+        )
+        .bind(&settings.guild_id)
+        .bind(settings.default_method.as_ref().map(|method| method.as_str()))
+        .bind(settings.default_duration_minutes)
+        .bind(&settings.creator_role)
+        .bind(&settings.default_voter_role)
+        .bind(&settings.announcement_channel)
+        .bind(settings.anonymous_by_default)
+        .bind(settings.max_options)
+        .bind(&settings.admin_role)
+        .bind(&settings.timezone)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
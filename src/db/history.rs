@@ -0,0 +1,187 @@
+use super::Database;
+use crate::models::VotingMethod;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::Row;
+
+// The polls `/poll history` covers: those run in Discord in one server that have opened, created
+// in [$2, $3) where either bound may be NULL. Every query below starts from it.
+const HISTORY_POLLS: &str = r#"
+    history_polls AS (
+        SELECT id, creator_id, question, voting_method, status, anonymous, created_at
+        FROM polls
+        WHERE guild_id = $1
+          AND origin = 'discord'
+          AND status NOT IN ('draft', 'scheduled')
+          AND ($2::TIMESTAMPTZ IS NULL OR created_at >= $2)
+          AND ($3::TIMESTAMPTZ IS NULL OR created_at < $3)
+    )"#;
+
+// One calendar month of a server's polls, in the server's timezone
+#[derive(Debug, Clone)]
+pub struct MonthActivity {
+    pub month: NaiveDate, // First day of the month
+    pub polls: i64,
+    pub ballots: i64,
+    pub voters: i64, // Distinct members who cast a ballot in any of the month's polls
+}
+
+// A poll with its ballot count, for the history CSV. Ballots are only ever counted.
+#[derive(Debug, Clone)]
+pub struct PollActivity {
+    pub poll_id: String,
+    pub created_at: DateTime<Utc>,
+    pub question: String,
+    pub voting_method: VotingMethod,
+    pub status: String,
+    pub creator_id: String,
+    pub anonymous: bool,
+    pub ballots: i64,
+}
+
+impl Database {
+    pub async fn get_monthly_poll_activity(
+        &self,
+        guild_id: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        timezone: &str,
+    ) -> Result<Vec<MonthActivity>, Box<dyn std::error::Error + Send + Sync>> {
+        let sql = format!(
+            r#"
+            WITH {}
+            SELECT DATE_TRUNC('month', p.created_at AT TIME ZONE $4)::DATE AS month,
+                   COUNT(DISTINCT p.id) AS polls,
+                   COUNT(DISTINCT (v.poll_id, v.user_id)) FILTER (WHERE v.user_id IS NOT NULL) AS ballots,
+                   COUNT(DISTINCT v.user_id) AS voters
+            FROM history_polls p
+            LEFT JOIN votes v ON v.poll_id = p.id
+            GROUP BY month
+            ORDER BY month
+            "#,
+            HISTORY_POLLS
+        );
+        let months = sqlx::query(&sql)
+            .bind(guild_id)
+            .bind(from)
+            .bind(to)
+            .bind(timezone)
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|row| MonthActivity {
+                month: row.get("month"),
+                polls: row.get("polls"),
+                ballots: row.get("ballots"),
+                voters: row.get("voters"),
+            })
+            .collect();
+        Ok(months)
+    }
+
+    // Distinct members who cast a ballot in any poll of the period
+    pub async fn count_history_voters(
+        &self,
+        guild_id: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<i64, Box<dyn std::error::Error + Send + Sync>> {
+        let sql = format!(
+            "WITH {} SELECT COUNT(DISTINCT v.user_id) AS voters FROM history_polls p JOIN votes v ON v.poll_id = p.id",
+            HISTORY_POLLS
+        );
+        let row = sqlx::query(&sql).bind(guild_id).bind(from).bind(to).fetch_one(&self.pool).await?;
+        Ok(row.get("voters"))
+    }
+
+    // Members who created the most polls, most first
+    pub async fn get_top_poll_creators(
+        &self,
+        guild_id: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+        limit: i64,
+    ) -> Result<Vec<(String, i64)>, Box<dyn std::error::Error + Send + Sync>> {
+        let sql = format!(
+            r#"
+            WITH {}
+            SELECT creator_id, COUNT(*) AS polls
+            FROM history_polls
+            GROUP BY creator_id
+            ORDER BY polls DESC, MIN(created_at)
+            LIMIT $4
+            "#,
+            HISTORY_POLLS
+        );
+        let creators = sqlx::query(&sql)
+            .bind(guild_id)
+            .bind(from)
+            .bind(to)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|row| (row.get::<String, _>("creator_id"), row.get::<i64, _>("polls")))
+            .collect();
+        Ok(creators)
+    }
+
+    // How many polls used each voting method, most used first
+    pub async fn get_voting_method_usage(
+        &self,
+        guild_id: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<(VotingMethod, i64)>, Box<dyn std::error::Error + Send + Sync>> {
+        let sql = format!(
+            r#"
+            WITH {}
+            SELECT voting_method, COUNT(*) AS polls
+            FROM history_polls
+            GROUP BY voting_method
+            ORDER BY polls DESC, voting_method
+            "#,
+            HISTORY_POLLS
+        );
+        let mut usage = Vec::new();
+        for row in sqlx::query(&sql).bind(guild_id).bind(from).bind(to).fetch_all(&self.pool).await? {
+            let method = row.get::<String, _>("voting_method");
+            let method = VotingMethod::parse(&method).ok_or_else(|| format!("Unknown voting method: {}", method))?;
+            usage.push((method, row.get::<i64, _>("polls")));
+        }
+        Ok(usage)
+    }
+
+    // Every poll of the period with its ballot count, oldest first
+    pub async fn get_poll_activity(
+        &self,
+        guild_id: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<Vec<PollActivity>, Box<dyn std::error::Error + Send + Sync>> {
+        let sql = format!(
+            r#"
+            WITH {}
+            SELECT p.id, p.created_at, p.question, p.voting_method, p.status, p.creator_id, p.anonymous,
+                   (SELECT COUNT(DISTINCT v.user_id) FROM votes v WHERE v.poll_id = p.id) AS ballots
+            FROM history_polls p
+            ORDER BY p.created_at, p.id
+            "#,
+            HISTORY_POLLS
+        );
+        let mut polls = Vec::new();
+        for row in sqlx::query(&sql).bind(guild_id).bind(from).bind(to).fetch_all(&self.pool).await? {
+            let method = row.get::<String, _>("voting_method");
+            polls.push(PollActivity {
+                poll_id: row.get("id"),
+                created_at: row.get("created_at"),
+                question: row.get("question"),
+                voting_method: VotingMethod::parse(&method).ok_or_else(|| format!("Unknown voting method: {}", method))?,
+                status: row.get("status"),
+                creator_id: row.get("creator_id"),
+                anonymous: row.get("anonymous"),
+                ballots: row.get("ballots"),
+            });
+        }
+        Ok(polls)
+    }
+}
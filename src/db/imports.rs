@@ -0,0 +1,77 @@
+use super::{outbox, Database};
+use crate::import::ImportedPoll;
+use crate::models::{CloseReason, OutboxAction};
+use crate::voting::{calculate_poll_results, PollResults};
+
+impl Database {
+    // Store an imported poll, already closed, with its ballots and tallied results. The
+    // announcement is queued in the same transaction, so an import is either announced in full
+    // or not stored.
+    pub async fn create_imported_poll(
+        &self,
+        imported: &ImportedPoll,
+    ) -> Result<PollResults, Box<dyn std::error::Error + Send + Sync>> {
+        let (poll, votes) = (&imported.poll, &imported.votes);
+        let results = calculate_poll_results(poll, votes);
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO polls (id, guild_id, channel_id, creator_id, question, voting_method, created_at, ends_at, status, message_id, allowed_roles, anonymous,
+                               starts_at, results_visibility, origin, closed_at, closed_by, close_reason, results_snapshot)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, NULL, $8, NULL, NULL, $9, NULL, $10, $11, $12, $13, $14, $15)
+            "#,
+        )
+        .bind(&poll.id)
+        .bind(&poll.guild_id)
+        .bind(&poll.channel_id)
+        .bind(&poll.creator_id)
+        .bind(&poll.question)
+        .bind(poll.voting_method.as_str())
+        .bind(poll.created_at)
+        .bind(poll.status.as_str())
+        .bind(poll.anonymous)
+        .bind(poll.results_visibility.as_str())
+        .bind(poll.origin.as_str())
+        .bind(poll.closed_at)
+        .bind(&poll.closed_by)
+        .bind(CloseReason::Manual.as_str())
+        .bind(sqlx::types::Json(&results))
+        .execute(&mut *tx)
+        .await?;
+
+        for (i, option) in poll.options.iter().enumerate() {
+            sqlx::query("INSERT INTO poll_options (id, poll_id, text, position, withdrawn) VALUES ($1, $2, $3, $4, $5)")
+                .bind(&option.id)
+                .bind(&poll.id)
+                .bind(&option.text)
+                .bind(i as i32)
+                .bind(option.withdrawn)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        // Binding each column as an array inserts tens of thousands of votes in one round trip
+        let user_ids: Vec<&str> = votes.iter().map(|vote| vote.user_id.as_str()).collect();
+        let option_ids: Vec<&str> = votes.iter().map(|vote| vote.option_id.as_str()).collect();
+        let ratings: Vec<i32> = votes.iter().map(|vote| vote.rating).collect();
+        let timestamps: Vec<_> = votes.iter().map(|vote| vote.timestamp).collect();
+        sqlx::query(
+            r#"
+            INSERT INTO votes (user_id, poll_id, option_id, rating, timestamp)
+            SELECT user_id, $1, option_id, rating, timestamp
+            FROM UNNEST($2::TEXT[], $3::TEXT[], $4::INT[], $5::TIMESTAMPTZ[]) AS v(user_id, option_id, rating, timestamp)
+            "#,
+        )
+        .bind(&poll.id)
+        .bind(&user_ids)
+        .bind(&option_ids)
+        .bind(&ratings)
+        .bind(&timestamps)
+        .execute(&mut *tx)
+        .await?;
+
+        outbox::enqueue(&mut tx, &poll.id, OutboxAction::PostResults).await?;
+        tx.commit().await?;
+        Ok(results)
+    }
+}
@@ -0,0 +1,57 @@
+use super::Database;
+use sqlx::{PgConnection, PgPool, Row};
+use tokio::sync::Mutex;
+
+// Leadership held through a session-level Postgres advisory lock. The lock lives as long as
+// the dedicated connection holding it, so a crashed leader releases it automatically.
+pub struct LeaderLock {
+    pool: PgPool,
+    key: i64,
+    connection: Mutex<Option<PgConnection>>,
+}
+
+impl Database {
+    pub fn leader_lock(&self, key: i64) -> LeaderLock {
+        LeaderLock {
+            pool: self.pool.clone(),
+            key,
+            connection: Mutex::new(None),
+        }
+    }
+}
+
+impl LeaderLock {
+    // Whether this process holds the lock, trying to take it if nobody does
+    pub async fn is_leader(&self) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut connection = self.connection.lock().await;
+
+        if let Some(held) = connection.as_mut() {
+            // Make sure the session (and with it the lock) is still alive
+            if sqlx::query("SELECT 1").execute(&mut *held).await.is_ok() {
+                return Ok(true);
+            }
+            // The held connection is detached from the pool, so dropping it just closes it
+            *connection = None;
+        }
+
+        let mut candidate = self.pool.acquire().await?;
+        let acquired = match sqlx::query("SELECT pg_try_advisory_lock($1) AS acquired")
+            .bind(self.key)
+            .fetch_one(&mut *candidate)
+            .await
+        {
+            Ok(row) => row.get::<bool, _>("acquired"),
+            Err(e) => {
+                // Don't hand a broken connection back to the pool
+                drop(candidate.detach());
+                return Err(e.into());
+            }
+        };
+        if acquired {
+            // Take the connection out of the pool for as long as we lead; the pool opens
+            // another in its place
+            *connection = Some(candidate.detach());
+        }
+        Ok(acquired)
+    }
+}
@@ -0,0 +1,222 @@
+use super::edits::record_edit;
+use super::{outbox, Database};
+use crate::models::{OutboxAction, PollStatus};
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+
+impl Database {
+    pub(super) async fn init_lifecycle_schema(pool: &PgPool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query("ALTER TABLE polls ADD COLUMN IF NOT EXISTS status TEXT;")
+            .execute(pool)
+            .await?;
+
+        // Databases from before the lifecycle states tracked `is_active` and `is_pending`
+        sqlx::query(
+            r#"
+            DO $$
+            BEGIN
+                IF EXISTS (SELECT 1 FROM information_schema.columns
+                           WHERE table_name = 'polls' AND column_name = 'is_active') THEN
+                    ALTER TABLE polls ADD COLUMN IF NOT EXISTS is_pending BOOLEAN NOT NULL DEFAULT FALSE;
+                    UPDATE polls
+                    SET status = CASE WHEN NOT is_active THEN 'closed'
+                                      WHEN is_pending THEN 'scheduled'
+                                      ELSE 'open' END
+                    WHERE status IS NULL;
+                    ALTER TABLE polls DROP COLUMN is_active, DROP COLUMN is_pending;
+                END IF;
+            END $$;
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query("ALTER TABLE polls ALTER COLUMN status SET NOT NULL;")
+            .execute(pool)
+            .await?;
+
+        // The states and transitions come from `PollStatus`, so the database enforces exactly
+        // what the model allows, whichever code path writes the row
+        let statuses = PollStatus::ALL
+            .iter()
+            .map(|status| format!("'{}'", status.as_str()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let transitions = PollStatus::ALL
+            .iter()
+            .flat_map(|from| {
+                PollStatus::ALL
+                    .iter()
+                    .filter(|to| from.can_transition_to(**to))
+                    .map(move |to| format!("('{}', '{}')", from.as_str(), to.as_str()))
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        sqlx::query("ALTER TABLE polls DROP CONSTRAINT IF EXISTS polls_status_check;")
+            .execute(pool)
+            .await?;
+        sqlx::query(&format!(
+            "ALTER TABLE polls ADD CONSTRAINT polls_status_check CHECK (status IN ({}));",
+            statuses
+        ))
+        .execute(pool)
+        .await?;
+
+        sqlx::query(&format!(
+            r#"
+            CREATE OR REPLACE FUNCTION polls_check_status_transition() RETURNS trigger AS $$
+            BEGIN
+                IF NEW.status IS DISTINCT FROM OLD.status
+                   AND (OLD.status, NEW.status) NOT IN (VALUES {}) THEN
+                    RAISE EXCEPTION 'Illegal poll status transition from % to %', OLD.status, NEW.status;
+                END IF;
+                RETURN NEW;
+            END;
+            $$ LANGUAGE plpgsql;
+            "#,
+            transitions
+        ))
+        .execute(pool)
+        .await?;
+
+        sqlx::query("DROP TRIGGER IF EXISTS polls_status_transition ON polls;")
+            .execute(pool)
+            .await?;
+        sqlx::query(
+            r#"
+            CREATE TRIGGER polls_status_transition
+            BEFORE UPDATE OF status ON polls
+            FOR EACH ROW EXECUTE FUNCTION polls_check_status_transition();
+            "#,
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    // Freeze voting on an open poll. Returns false if it wasn't open.
+    pub async fn pause_poll(
+        &self,
+        poll_id: &str,
+        actor_id: &str,
+        details: &str,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        if !transition(&mut tx, poll_id, &[PollStatus::Open], PollStatus::Paused).await? {
+            return Ok(false);
+        }
+        record_edit(&mut tx, poll_id, actor_id, "pause", details).await?;
+        outbox::enqueue(&mut tx, poll_id, OutboxAction::EditPollMessage).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    // Accept votes on a paused poll again. Returns false if it wasn't paused.
+    pub async fn resume_poll(
+        &self,
+        poll_id: &str,
+        actor_id: &str,
+        details: &str,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        if !transition(&mut tx, poll_id, &[PollStatus::Paused], PollStatus::Open).await? {
+            return Ok(false);
+        }
+        record_edit(&mut tx, poll_id, actor_id, "resume", details).await?;
+        outbox::enqueue(&mut tx, poll_id, OutboxAction::EditPollMessage).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    // Open a closed poll again with a new end time (None = manual close). Its close details and
+    // results snapshot are cleared, since the results can still change; closing it again
+    // announces the new results. Returns false if it wasn't closed (archived polls can't be reopened).
+    pub async fn reopen_poll(
+        &self,
+        poll_id: &str,
+        ends_at: Option<DateTime<Utc>>,
+        actor_id: &str,
+        details: &str,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        if !transition(&mut tx, poll_id, &[PollStatus::Closed], PollStatus::Open).await? {
+            return Ok(false);
+        }
+        sqlx::query(
+            r#"
+            UPDATE polls
+            SET ends_at = $2, closed_at = NULL, closed_by = NULL, close_reason = NULL, results_snapshot = NULL
+            WHERE id = $1
+            "#,
+        )
+        .bind(poll_id)
+        .bind(ends_at)
+        .execute(&mut *tx)
+        .await?;
+        // An announcement of the old results that hasn't gone out yet would now be wrong
+        sqlx::query("DELETE FROM discord_outbox WHERE poll_id = $1 AND kind = $2")
+            .bind(poll_id)
+            .bind(OutboxAction::PostResults.kind())
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            UPDATE poll_reminders
+            SET sent_at = NULL
+            WHERE poll_id = $1 AND $2::TIMESTAMPTZ - make_interval(mins => offset_minutes::INTEGER) > NOW()
+            "#,
+        )
+        .bind(poll_id)
+        .bind(ends_at)
+        .execute(&mut *tx)
+        .await?;
+        record_edit(&mut tx, poll_id, actor_id, "reopen", details).await?;
+        outbox::enqueue(&mut tx, poll_id, OutboxAction::EditPollMessage).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    // Drafts are normally published within seconds; older ones were never posted (the bot
+    // crashed or Discord refused the message) and nobody can see them
+    pub async fn delete_stale_drafts(
+        &self,
+        older_than: DateTime<Utc>,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let result = sqlx::query("DELETE FROM polls WHERE status = 'draft' AND created_at < $1")
+            .bind(older_than)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+// Move a poll from one of `from` to `to`. Returns false if it was in some other state, so
+// callers can use it as a claim. Illegal transitions are refused before touching the row.
+pub(super) async fn transition(
+    tx: &mut Transaction<'_, Postgres>,
+    poll_id: &str,
+    from: &[PollStatus],
+    to: PollStatus,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(illegal) = from.iter().find(|status| !status.can_transition_to(to)) {
+        return Err(format!("Illegal poll status transition from {} to {}", illegal.as_str(), to.as_str()).into());
+    }
+    let result = sqlx::query("UPDATE polls SET status = $2 WHERE id = $1 AND status = ANY($3)")
+        .bind(poll_id)
+        .bind(to.as_str())
+        .bind(status_strs(from))
+        .execute(&mut **tx)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub(super) fn status_strs(statuses: &[PollStatus]) -> Vec<&'static str> {
+    statuses.iter().map(|status| status.as_str()).collect()
+}
+
+pub(super) fn status_from_row(row: &PgRow) -> Result<PollStatus, Box<dyn std::error::Error + Send + Sync>> {
+    let status: String = row.get("status");
+    PollStatus::parse(&status).ok_or_else(|| format!("Unknown poll status: {}", status).into())
+}
@@ -1,7 +1,30 @@
+mod edits;
+mod guild_settings;
+mod history;
+mod imports;
+mod leader;
+mod lifecycle;
+mod outbox;
+mod privacy;
+mod reminders;
+mod retention;
+mod search;
+mod stats;
+mod templates;
+
+pub use history::{MonthActivity, PollActivity};
+pub use leader::LeaderLock;
+pub use outbox::OutboxEntry;
+pub use reminders::DueReminder;
+pub use search::PollIdMatch;
+pub use retention::RetentionReport;
+pub use stats::BallotStats;
+
 use sqlx::{Row, PgPool, postgres::{PgPoolOptions}};
 use chrono::{DateTime, Utc};
 use std::env;
-use crate::models::{Poll, VotingMethod};
+use crate::models::{CloseReason, OutboxAction, Poll, PollOrigin, PollStatus, ResultsVisibility, VotingMethod};
+use crate::voting::PollResults;
 #[cfg(feature = "embedded-postgres")]
 use postgresql_embedded::{PostgreSQL};
 
@@ -77,7 +100,7 @@ impl Database {
                 voting_method TEXT NOT NULL,
                 created_at TIMESTAMPTZ NOT NULL,
                 ends_at TIMESTAMPTZ,
-                is_active BOOLEAN NOT NULL DEFAULT TRUE,
+                status TEXT NOT NULL,
                 message_id TEXT,
                 allowed_roles TEXT[]
             );
@@ -114,6 +137,32 @@ impl Database {
         .execute(pool)
         .await?;
 
+        // Columns added after the initial schema; ADD COLUMN IF NOT EXISTS keeps existing databases working
+        sqlx::query(
+            r#"
+            ALTER TABLE polls
+                ADD COLUMN IF NOT EXISTS closed_at TIMESTAMPTZ,
+                ADD COLUMN IF NOT EXISTS closed_by TEXT,
+                ADD COLUMN IF NOT EXISTS close_reason TEXT,
+                ADD COLUMN IF NOT EXISTS results_snapshot JSONB,
+                ADD COLUMN IF NOT EXISTS anonymous BOOLEAN NOT NULL DEFAULT FALSE,
+                ADD COLUMN IF NOT EXISTS starts_at TIMESTAMPTZ,
+                ADD COLUMN IF NOT EXISTS results_visibility TEXT NOT NULL DEFAULT 'hidden',
+                ADD COLUMN IF NOT EXISTS origin TEXT NOT NULL DEFAULT 'discord';
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        // Must come before anything that queries poll status
+        Self::init_lifecycle_schema(pool).await?;
+        Self::init_retention_schema(pool).await?;
+        Self::init_guild_settings_schema(pool).await?;
+        Self::init_edits_schema(pool).await?;
+        Self::init_templates_schema(pool).await?;
+        Self::init_reminders_schema(pool).await?;
+        Self::init_outbox_schema(pool).await?;
+
         Ok(())
     }
     
@@ -124,8 +173,9 @@ impl Database {
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         sqlx::query(
             r#"
-            INSERT INTO polls (id, guild_id, channel_id, creator_id, question, voting_method, created_at, ends_at, is_active, message_id, allowed_roles)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, NULL, $10)
+            INSERT INTO polls (id, guild_id, channel_id, creator_id, question, voting_method, created_at, ends_at, status, message_id, allowed_roles, anonymous,
+                               starts_at, results_visibility)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, NULL, $10, $11, $12, $13)
             "#,
         )
         .bind(&poll.id)
@@ -133,16 +183,14 @@ impl Database {
         .bind(&poll.channel_id)
         .bind(&poll.creator_id)
         .bind(&poll.question)
-        .bind(match poll.voting_method {
-            crate::models::VotingMethod::Star => "star",
-            crate::models::VotingMethod::Plurality => "plurality",
-            crate::models::VotingMethod::Ranked => "ranked",
-            crate::models::VotingMethod::Approval => "approval",
-        })
+        .bind(poll.voting_method.as_str())
         .bind(poll.created_at)
         .bind(poll.ends_at)
-        .bind(poll.is_active)
+        .bind(poll.status.as_str())
         .bind(&poll.allowed_roles)
+        .bind(poll.anonymous)
+        .bind(poll.starts_at)
+        .bind(poll.results_visibility.as_str())
         .execute(&self.pool)
         .await?;
 
@@ -165,23 +213,35 @@ impl Database {
         Ok(())
     }
 
-    // Update the message ID for a poll
-    pub async fn update_poll_message_id(
+    // Move a new poll out of draft once its message is posted, storing the message ID if known
+    pub async fn mark_poll_published(
         &self,
         poll_id: &str,
-        message_id: &str,
+        status: PollStatus,
+        message_id: Option<&str>,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        sqlx::query(
-            r#"
-            UPDATE polls
-            SET message_id = $1
-            WHERE id = $2
-            "#,
-        )
-        .bind(message_id)
-        .bind(poll_id)
-        .execute(&self.pool)
-        .await?;
+        let mut tx = self.pool.begin().await?;
+        if !lifecycle::transition(&mut tx, poll_id, &[PollStatus::Draft], status).await? {
+            return Err(format!("Poll {} is not a draft", poll_id).into());
+        }
+        sqlx::query("UPDATE polls SET message_id = $2 WHERE id = $1")
+            .bind(poll_id)
+            .bind(message_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Forget a poll message that was deleted, so nothing tries to edit it again
+    pub async fn clear_poll_message_id(
+        &self,
+        poll_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query("UPDATE polls SET message_id = NULL WHERE id = $1")
+            .bind(poll_id)
+            .execute(&self.pool)
+            .await?;
         Ok(())
     }
     
@@ -193,7 +253,8 @@ impl Database {
         // Get the poll
         let poll_row = sqlx::query(
             r#"
-            SELECT id, guild_id, channel_id, creator_id, question, voting_method, created_at, ends_at, is_active, message_id, allowed_roles 
+            SELECT id, guild_id, channel_id, creator_id, question, voting_method, created_at, ends_at, status, message_id, allowed_roles,
+                   anonymous, closed_at, closed_by, close_reason, starts_at, results_visibility, origin
             FROM polls 
             WHERE id = $1
             "#,
@@ -211,22 +272,17 @@ impl Database {
         let voting_method_str = poll_row.get::<String, _>("voting_method");
         let created_at = poll_row.get::<DateTime<Utc>, _>("created_at");
         let ends_at: Option<DateTime<Utc>> = poll_row.try_get("ends_at").ok();
-        let is_active = poll_row.get::<bool, _>("is_active");
+        let status = lifecycle::status_from_row(&poll_row)?;
         let message_id: Option<String> = poll_row.get("message_id");
         
         // Parse voting method
-        let voting_method = match voting_method_str.as_str() {
-            "star" => crate::models::VotingMethod::Star,
-            "plurality" => crate::models::VotingMethod::Plurality,
-            "ranked" => crate::models::VotingMethod::Ranked,
-            "approval" => crate::models::VotingMethod::Approval,
-            _ => return Err(format!("Unknown voting method: {}", voting_method_str).into()),
-        };
+        let voting_method = VotingMethod::parse(&voting_method_str)
+            .ok_or_else(|| format!("Unknown voting method: {}", voting_method_str))?;
         
         // Get options
         let options = sqlx::query(
             r#"
-            SELECT id, text, position
+            SELECT id, text, position, withdrawn
             FROM poll_options
             WHERE poll_id = $1
             ORDER BY position
@@ -239,6 +295,7 @@ impl Database {
         .map(|row| crate::models::PollOption {
             id: row.get::<String, _>("id"),
             text: row.get::<String, _>("text"),
+            withdrawn: row.get::<bool, _>("withdrawn"),
         })
         .collect();
         
@@ -253,59 +310,170 @@ impl Database {
             voting_method,
             created_at,
             ends_at,
-            is_active,
+            starts_at: poll_row.get::<Option<DateTime<Utc>>, _>("starts_at"),
+            status,
             message_id,
             allowed_roles: poll_row.try_get::<Option<Vec<String>>, _>("allowed_roles").unwrap_or(None),
+            anonymous: poll_row.get::<bool, _>("anonymous"),
+            results_visibility: ResultsVisibility::parse(&poll_row.get::<String, _>("results_visibility"))
+                .unwrap_or_default(),
+            origin: PollOrigin::parse(&poll_row.get::<String, _>("origin")).unwrap_or_default(),
+            closed_at: poll_row.get::<Option<DateTime<Utc>>, _>("closed_at"),
+            closed_by: poll_row.get::<Option<String>, _>("closed_by"),
+            close_reason: poll_row
+                .get::<Option<String>, _>("close_reason")
+                .and_then(|reason| CloseReason::parse(&reason)),
+            edits: self.get_poll_edits(poll_id).await?,
         };
         
         Ok(poll)
     }
     
-    // End a poll (set is_active = false)
+    // Close a poll and record who closed it and why. This is the claim
+    // on closing: it returns false if the poll was already closed, by this process or another,
+    // and only the caller that got true may announce the results.
     pub async fn end_poll(
         &self,
         poll_id: &str,
+        closed_by: &str,
+        reason: CloseReason,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        let closed = sqlx::query(
+            r#"
+            UPDATE polls
+            SET status = 'closed', closed_at = NOW(), closed_by = $2, close_reason = $3
+            WHERE id = $1 AND status = ANY($4)
+            RETURNING id
+            "#,
+        )
+        .bind(poll_id)
+        .bind(closed_by)
+        .bind(reason.as_str())
+        .bind(lifecycle::status_strs(&PollStatus::sources_of(PollStatus::Closed)))
+        .fetch_optional(&mut *tx)
+        .await?;
+        if closed.is_none() {
+            return Ok(false);
+        }
+
+        // Queued with the close itself, so the announcement can't be lost to a Discord error or a restart
+        outbox::enqueue(&mut tx, poll_id, OutboxAction::EditPollMessage).await?;
+        outbox::enqueue(&mut tx, poll_id, OutboxAction::PostResults).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    // Store a snapshot unless the poll already has one. Returns false if it did, in which case
+    // that one was (or will be) announced and should be used instead.
+    pub async fn save_results_snapshot_if_missing(
+        &self,
+        poll_id: &str,
+        results: &PollResults,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let result = sqlx::query("UPDATE polls SET results_snapshot = $1 WHERE id = $2 AND results_snapshot IS NULL")
+            .bind(sqlx::types::Json(results))
+            .bind(poll_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // Store the results exactly as they were announced when the poll closed
+    pub async fn save_results_snapshot(
+        &self,
+        poll_id: &str,
+        results: &PollResults,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         sqlx::query(
             r#"
             UPDATE polls
-            SET is_active = FALSE
-            WHERE id = $1 AND is_active = TRUE
+            SET results_snapshot = $1
+            WHERE id = $2
             "#,
         )
+        .bind(sqlx::types::Json(results))
         .bind(poll_id)
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
-    // Get polls that have passed their end time and are still active
-    pub async fn get_expired_polls(
+    // Get the results stored when the poll closed (None for polls closed before snapshots existed)
+    pub async fn get_results_snapshot(
         &self,
-        now: DateTime<Utc>,
-    ) -> Result<Vec<(String, String, Option<String>)>, Box<dyn std::error::Error + Send + Sync>> {
-        let polls = sqlx::query(
+        poll_id: &str,
+    ) -> Result<Option<PollResults>, Box<dyn std::error::Error + Send + Sync>> {
+        let snapshot = sqlx::query(
             r#"
-            SELECT id, channel_id, message_id
+            SELECT results_snapshot
             FROM polls
-            WHERE ends_at IS NOT NULL AND ends_at < $1 AND is_active = TRUE
+            WHERE id = $1
             "#,
         )
-        .bind(now)
-        .fetch_all(&self.pool)
+        .bind(poll_id)
+        .fetch_optional(&self.pool)
         .await?
-        .into_iter()
-        .map(|row| {
-            (
+        .and_then(|row| row.get::<Option<sqlx::types::Json<PollResults>>, _>("results_snapshot"))
+        .map(|json| json.0);
+        Ok(snapshot)
+    }
+
+    // Start and end times of every poll the in-memory scheduler has a timer for: scheduled ones,
+    // and open or paused ones with an end time. (poll_id, status, starts_at, ends_at)
+    #[allow(clippy::type_complexity)]
+    pub async fn get_scheduled_polls(
+        &self,
+    ) -> Result<Vec<(String, PollStatus, Option<DateTime<Utc>>, Option<DateTime<Utc>>)>, Box<dyn std::error::Error + Send + Sync>> {
+        self.get_poll_times("status = 'scheduled' OR (status IN ('open', 'paused') AND ends_at IS NOT NULL)").await
+    }
+
+    // Start and end times of every scheduled, open or paused poll, including those without an
+    // end time, for startup reconciliation
+    #[allow(clippy::type_complexity)]
+    pub async fn get_active_poll_times(
+        &self,
+    ) -> Result<Vec<(String, PollStatus, Option<DateTime<Utc>>, Option<DateTime<Utc>>)>, Box<dyn std::error::Error + Send + Sync>> {
+        self.get_poll_times("status IN ('scheduled', 'open', 'paused')").await
+    }
+
+    #[allow(clippy::type_complexity)]
+    async fn get_poll_times(
+        &self,
+        condition: &str,
+    ) -> Result<Vec<(String, PollStatus, Option<DateTime<Utc>>, Option<DateTime<Utc>>)>, Box<dyn std::error::Error + Send + Sync>> {
+        let rows = sqlx::query(&format!("SELECT id, status, starts_at, ends_at FROM polls WHERE {}", condition))
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut polls = Vec::with_capacity(rows.len());
+        for row in rows {
+            polls.push((
                 row.get::<String, _>("id"),
-                row.get::<String, _>("channel_id"),
-                row.get::<Option<String>, _>("message_id"),
-            )
-        })
-        .collect();
+                lifecycle::status_from_row(&row)?,
+                row.get::<Option<DateTime<Utc>>, _>("starts_at"),
+                row.get::<Option<DateTime<Utc>>, _>("ends_at"),
+            ));
+        }
         Ok(polls)
     }
 
+    // Open voting on a scheduled poll. Returns false if it was already open (or ended meanwhile).
+    pub async fn open_poll(
+        &self,
+        poll_id: &str,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        if !lifecycle::transition(&mut tx, poll_id, &[PollStatus::Scheduled], PollStatus::Open).await? {
+            return Ok(false);
+        }
+
+        // Adds the vote button to the preview message
+        outbox::enqueue(&mut tx, poll_id, OutboxAction::EditPollMessage).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
     // Get active polls for a specific guild
     pub async fn get_active_polls_by_guild(
         &self,
@@ -313,9 +481,9 @@ impl Database {
     ) -> Result<Vec<Poll>, Box<dyn std::error::Error + Send + Sync>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, question, ends_at
+            SELECT id, creator_id, question, ends_at, starts_at, status
             FROM polls
-            WHERE guild_id = $1 AND is_active = TRUE
+            WHERE guild_id = $1 AND status IN ('scheduled', 'open', 'paused')
             ORDER BY created_at DESC
             "#,
         )
@@ -323,22 +491,31 @@ impl Database {
         .fetch_all(&self.pool)
         .await?;
 
-        let partial_polls = rows.into_iter().map(|row| {
-            Poll {
+        let mut partial_polls = Vec::with_capacity(rows.len());
+        for row in rows {
+            partial_polls.push(Poll {
                 id: row.get("id"),
                 question: row.get("question"),
                 ends_at: row.try_get::<Option<DateTime<Utc>>, _>("ends_at").ok().flatten(),
                 guild_id: guild_id.to_string(),
                 channel_id: String::new(),
-                creator_id: String::new(),
+                creator_id: row.get("creator_id"),
                 options: Vec::new(),
                 voting_method: VotingMethod::Plurality,
                 created_at: Utc::now(),
-                is_active: true,
+                starts_at: row.get::<Option<DateTime<Utc>>, _>("starts_at"),
+                status: lifecycle::status_from_row(&row)?,
                 message_id: None,
                 allowed_roles: None,
-            }
-        }).collect();
+                anonymous: false,
+                results_visibility: ResultsVisibility::Hidden,
+                origin: PollOrigin::Discord,
+                closed_at: None,
+                closed_by: None,
+                close_reason: None,
+                edits: Vec::new(),
+            });
+        }
 
         Ok(partial_polls)
     }
@@ -351,10 +528,10 @@ impl Database {
     ) -> Result<Vec<Poll>, Box<dyn std::error::Error + Send + Sync>> {
         let rows = sqlx::query(
             r#"
-            SELECT id, question, ends_at
+            SELECT id, question, ends_at, closed_at, closed_by, close_reason, status
             FROM polls
-            WHERE guild_id = $1 AND is_active = FALSE
-            ORDER BY ends_at DESC
+            WHERE guild_id = $1 AND status IN ('closed', 'archived')
+            ORDER BY COALESCE(closed_at, ends_at, created_at) DESC
             LIMIT $2
             "#,
         )
@@ -363,8 +540,9 @@ impl Database {
         .fetch_all(&self.pool)
         .await?;
 
-        let partial_polls = rows.into_iter().map(|row| {
-            Poll {
+        let mut partial_polls = Vec::with_capacity(rows.len());
+        for row in rows {
+            partial_polls.push(Poll {
                 id: row.get("id"),
                 question: row.get("question"),
                 ends_at: row.try_get::<Option<DateTime<Utc>>, _>("ends_at").ok().flatten(),
@@ -374,11 +552,21 @@ impl Database {
                 options: Vec::new(),
                 voting_method: VotingMethod::Plurality,
                 created_at: Utc::now(),
-                is_active: false,
+                starts_at: None,
+                status: lifecycle::status_from_row(&row)?,
                 message_id: None,
                 allowed_roles: None,
-            }
-        }).collect();
+                anonymous: false,
+                results_visibility: ResultsVisibility::Hidden,
+                origin: PollOrigin::Discord,
+                closed_at: row.get::<Option<DateTime<Utc>>, _>("closed_at"),
+                closed_by: row.get::<Option<String>, _>("closed_by"),
+                close_reason: row
+                    .get::<Option<String>, _>("close_reason")
+                    .and_then(|reason| CloseReason::parse(&reason)),
+                edits: Vec::new(),
+            });
+        }
         Ok(partial_polls)
     }
 
@@ -443,26 +631,33 @@ impl Database {
         &self,
         vote: &crate::models::Vote,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        // First verify the poll and option exist
-        let poll_exists = sqlx::query("SELECT 1 FROM polls WHERE id = $1")
+        // First verify the poll is accepting votes and the option exists
+        let status = sqlx::query("SELECT status FROM polls WHERE id = $1")
             .bind(&vote.poll_id)
             .fetch_optional(&self.pool)
             .await?
-            .is_some();
+            .map(|row| lifecycle::status_from_row(&row))
+            .transpose()?;
 
-        if !poll_exists {
-            return Err("Poll not found".into());
+        match status {
+            None => return Err("Poll not found".into()),
+            Some(status) if !status.accepts_votes() => {
+                return Err(format!("This poll isn't accepting votes ({})", status.to_string().to_lowercase()).into());
+            }
+            Some(_) => {}
         }
 
-        let option_exists = sqlx::query("SELECT 1 FROM poll_options WHERE id = $1 AND poll_id = $2")
+        let option_withdrawn = sqlx::query("SELECT withdrawn FROM poll_options WHERE id = $1 AND poll_id = $2")
             .bind(&vote.option_id)
             .bind(&vote.poll_id)
             .fetch_optional(&self.pool)
             .await?
-            .is_some();
+            .map(|row| row.get::<bool, _>("withdrawn"));
 
-        if !option_exists {
-            return Err("Poll option not found".into());
+        match option_withdrawn {
+            None => return Err("Poll option not found".into()),
+            Some(true) => return Err("That option has been withdrawn from the poll".into()),
+            Some(false) => {}
         }
 
         sqlx::query(
@@ -0,0 +1,217 @@
+use super::Database;
+use crate::models::OutboxAction;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Postgres, Row, Transaction};
+
+// A queued Discord side effect. `dead_at` is set once it has failed too often to retry.
+#[derive(Debug, Clone)]
+pub struct OutboxEntry {
+    pub id: i64,
+    pub poll_id: String,
+    pub question: String,
+    pub action: OutboxAction,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub dead_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Database {
+    pub(super) async fn init_outbox_schema(pool: &PgPool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS discord_outbox (
+                id BIGSERIAL PRIMARY KEY,
+                poll_id TEXT NOT NULL REFERENCES polls(id) ON DELETE CASCADE,
+                kind TEXT NOT NULL,
+                notify TEXT,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                locked_until TIMESTAMPTZ,
+                last_error TEXT,
+                dead_at TIMESTAMPTZ,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS discord_outbox_due_idx ON discord_outbox (next_attempt_at) WHERE dead_at IS NULL;",
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    // Queue an action on its own. State changes that cause Discord side effects (closing,
+    // opening, reminders) queue theirs in the same transaction instead.
+    pub async fn enqueue_outbox_action(
+        &self,
+        poll_id: &str,
+        action: OutboxAction,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        enqueue(&mut tx, poll_id, action).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Lease up to `limit` due actions to this process, counting the attempt. Another process
+    // can only pick an action up again once the lease runs out.
+    pub async fn claim_outbox_actions(
+        &self,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> Result<Vec<OutboxEntry>, Box<dyn std::error::Error + Send + Sync>> {
+        let rows = sqlx::query(
+            r#"
+            UPDATE discord_outbox o
+            SET attempts = o.attempts + 1,
+                locked_until = NOW() + make_interval(secs => $2::DOUBLE PRECISION)
+            FROM polls p
+            WHERE p.id = o.poll_id AND o.id IN (
+                SELECT id FROM discord_outbox
+                WHERE dead_at IS NULL
+                  AND next_attempt_at <= NOW()
+                  AND (locked_until IS NULL OR locked_until < NOW())
+                ORDER BY next_attempt_at, id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING o.id, o.poll_id, p.question, o.kind, o.notify, o.attempts, o.last_error,
+                      o.next_attempt_at, o.dead_at, o.created_at
+            "#,
+        )
+        .bind(limit)
+        .bind(lease_seconds as f64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut entries: Vec<OutboxEntry> = rows.iter().map(entry_from_row).collect::<Result<_, _>>()?;
+        // RETURNING doesn't keep the subquery's order; deliver oldest first
+        entries.sort_by_key(|entry| (entry.next_attempt_at, entry.id));
+        Ok(entries)
+    }
+
+    // Delivered: nothing left to keep
+    pub async fn complete_outbox_action(&self, id: i64) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query("DELETE FROM discord_outbox WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // Record a failed attempt and retry at `retry_at`, or dead-letter the action with None
+    pub async fn fail_outbox_action(
+        &self,
+        id: i64,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            UPDATE discord_outbox
+            SET last_error = $2,
+                locked_until = NULL,
+                next_attempt_at = COALESCE($3, next_attempt_at),
+                dead_at = CASE WHEN $3::TIMESTAMPTZ IS NULL THEN NOW() ELSE NULL END
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(error)
+        .bind(retry_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // Queued actions for a server's polls: dead-lettered ones, or those still being retried
+    pub async fn get_outbox_actions(
+        &self,
+        guild_id: &str,
+        dead: bool,
+        limit: i64,
+    ) -> Result<Vec<OutboxEntry>, Box<dyn std::error::Error + Send + Sync>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT o.id, o.poll_id, p.question, o.kind, o.notify, o.attempts, o.last_error,
+                   o.next_attempt_at, o.dead_at, o.created_at
+            FROM discord_outbox o
+            JOIN polls p ON p.id = o.poll_id
+            WHERE p.guild_id = $1 AND (o.dead_at IS NOT NULL) = $2
+            ORDER BY o.created_at DESC, o.id DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(guild_id)
+        .bind(dead)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(entry_from_row).collect()
+    }
+
+    // Queue dead-lettered actions again with a fresh set of attempts: one action by ID, or
+    // every one in the server with None. Returns how many were requeued.
+    pub async fn replay_outbox_actions(
+        &self,
+        guild_id: &str,
+        id: Option<i64>,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let result = sqlx::query(
+            r#"
+            UPDATE discord_outbox o
+            SET dead_at = NULL, attempts = 0, next_attempt_at = NOW(), locked_until = NULL
+            FROM polls p
+            WHERE p.id = o.poll_id
+              AND p.guild_id = $1
+              AND o.dead_at IS NOT NULL
+              AND ($2::BIGINT IS NULL OR o.id = $2)
+            "#,
+        )
+        .bind(guild_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+pub(super) async fn enqueue(
+    tx: &mut Transaction<'_, Postgres>,
+    poll_id: &str,
+    action: OutboxAction,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    sqlx::query("INSERT INTO discord_outbox (poll_id, kind, notify) VALUES ($1, $2, $3)")
+        .bind(poll_id)
+        .bind(action.kind())
+        .bind(action.notify().map(|notify| notify.as_str()))
+        .execute(&mut **tx)
+        .await?;
+    Ok(())
+}
+
+fn entry_from_row(row: &PgRow) -> Result<OutboxEntry, Box<dyn std::error::Error + Send + Sync>> {
+    let kind: String = row.get("kind");
+    let notify: Option<String> = row.get("notify");
+    let action = OutboxAction::parse(&kind, notify.as_deref())
+        .ok_or_else(|| format!("Unknown outbox action: {}", kind))?;
+
+    Ok(OutboxEntry {
+        id: row.get("id"),
+        poll_id: row.get("poll_id"),
+        question: row.get("question"),
+        action,
+        attempts: row.get("attempts"),
+        last_error: row.get("last_error"),
+        next_attempt_at: row.get("next_attempt_at"),
+        dead_at: row.get("dead_at"),
+        created_at: row.get("created_at"),
+    })
+}
@@ -0,0 +1,178 @@
+use super::retention::new_pseudonym;
+use super::{lifecycle, Database};
+use crate::models::{ActiveBallotPolicy, Poll, PollStatus};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::Row;
+
+// Everything stored about one member, for privacy requests
+#[derive(Debug, Serialize)]
+pub struct UserDataExport {
+    pub user_id: String,
+    pub generated_at: DateTime<Utc>,
+    pub polls_created: Vec<Poll>,
+    pub ballots: Vec<BallotExport>,
+}
+
+// One member's ballot in one poll
+#[derive(Debug, Serialize)]
+pub struct BallotExport {
+    pub poll_id: String,
+    pub guild_id: String,
+    pub question: String,
+    pub poll_active: bool,
+    pub votes: Vec<BallotEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BallotEntry {
+    pub option_id: String,
+    pub option_text: String,
+    pub rating: i32,
+    pub timestamp: DateTime<Utc>,
+}
+
+// Outcome of forgetting a member
+#[derive(Debug, Default)]
+pub struct ForgetReport {
+    pub polls_pseudonymized: u64, // Ballots kept under a pseudonym
+    pub polls_dropped: u64,       // Ballots deleted from active polls
+    pub polls_archived: u64,      // Closed polls archived so they can't be reopened
+    pub vote_rows: u64,
+}
+
+impl Database {
+    // Collect every poll a member created and every ballot they cast, across all guilds
+    pub async fn export_user_data(
+        &self,
+        user_id: &str,
+    ) -> Result<UserDataExport, Box<dyn std::error::Error + Send + Sync>> {
+        let created_ids: Vec<String> = sqlx::query("SELECT id FROM polls WHERE creator_id = $1 ORDER BY created_at")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|row| row.get("id"))
+            .collect();
+
+        let mut polls_created = Vec::with_capacity(created_ids.len());
+        for poll_id in &created_ids {
+            polls_created.push(self.get_poll(poll_id).await?);
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT v.poll_id, p.guild_id, p.question, p.status IN ('scheduled', 'open', 'paused') AS is_active, v.option_id, o.text AS option_text, v.rating, v.timestamp
+            FROM votes v
+            JOIN polls p ON p.id = v.poll_id
+            JOIN poll_options o ON o.id = v.option_id
+            WHERE v.user_id = $1
+            ORDER BY p.created_at, v.poll_id, o.position
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut ballots: Vec<BallotExport> = Vec::new();
+        for row in rows {
+            let poll_id: String = row.get("poll_id");
+            let entry = BallotEntry {
+                option_id: row.get("option_id"),
+                option_text: row.get("option_text"),
+                rating: row.get("rating"),
+                timestamp: row.get("timestamp"),
+            };
+            match ballots.last_mut() {
+                Some(ballot) if ballot.poll_id == poll_id => ballot.votes.push(entry),
+                _ => ballots.push(BallotExport {
+                    poll_id,
+                    guild_id: row.get("guild_id"),
+                    question: row.get("question"),
+                    poll_active: row.get("is_active"),
+                    votes: vec![entry],
+                }),
+            }
+        }
+
+        Ok(UserDataExport {
+            user_id: user_id.to_string(),
+            generated_at: Utc::now(),
+            polls_created,
+            ballots,
+        })
+    }
+
+    // Remove a member's identity from every ballot they cast. Ballots in closed polls are kept
+    // under a per-poll pseudonym so published results still add up, and those polls are archived:
+    // reopened, they would let the member vote again next to their pseudonymous ballot. Ballots
+    // in active polls are deleted or pseudonymized as `active_policy` says; a pseudonymized one
+    // still counts, but the bot no longer recognizes it, so the member could vote a second time.
+    pub async fn forget_user_ballots(
+        &self,
+        user_id: &str,
+        active_policy: ActiveBallotPolicy,
+    ) -> Result<ForgetReport, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        let mut report = ForgetReport::default();
+
+        if active_policy == ActiveBallotPolicy::Drop {
+            let dropped = sqlx::query(
+                r#"
+                DELETE FROM votes v
+                USING polls p
+                WHERE v.poll_id = p.id AND v.user_id = $1 AND p.status IN ('scheduled', 'open', 'paused')
+                RETURNING v.poll_id
+                "#,
+            )
+            .bind(user_id)
+            .fetch_all(&mut *tx)
+            .await?;
+            report.vote_rows += dropped.len() as u64;
+            let mut poll_ids: Vec<String> = dropped.into_iter().map(|row| row.get("poll_id")).collect();
+            poll_ids.sort();
+            poll_ids.dedup();
+            report.polls_dropped = poll_ids.len() as u64;
+        }
+
+        let voted: Vec<(String, String)> = sqlx::query_as(
+            r#"
+            SELECT DISTINCT v.poll_id, p.status
+            FROM votes v
+            JOIN polls p ON p.id = v.poll_id
+            WHERE v.user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(&mut *tx)
+        .await?;
+        let poll_ids: Vec<String> = voted.iter().map(|(poll_id, _)| poll_id.clone()).collect();
+        let pseudonyms: Vec<String> = voted.iter().map(|_| new_pseudonym()).collect();
+        report.vote_rows += sqlx::query(
+            r#"
+            UPDATE votes v
+            SET user_id = pseudonyms.pseudonym
+            FROM UNNEST($2::TEXT[], $3::TEXT[]) AS pseudonyms(poll_id, pseudonym)
+            WHERE v.user_id = $1 AND v.poll_id = pseudonyms.poll_id
+            "#,
+        )
+        .bind(user_id)
+        .bind(&poll_ids)
+        .bind(&pseudonyms)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        report.polls_pseudonymized = voted.len() as u64;
+
+        for (poll_id, status) in &voted {
+            if status == PollStatus::Closed.as_str()
+                && lifecycle::transition(&mut tx, poll_id, &[PollStatus::Closed], PollStatus::Archived).await?
+            {
+                report.polls_archived += 1;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(report)
+    }
+}
@@ -0,0 +1,126 @@
+use super::{outbox, Database};
+use crate::models::{OutboxAction, ReminderNotify};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+
+// A reminder whose time has come, for a poll that is still open
+#[derive(Debug, Clone)]
+pub struct DueReminder {
+    pub poll_id: String,
+    pub offset_minutes: i64,
+    pub notify: ReminderNotify,
+}
+
+impl Database {
+    pub(super) async fn init_reminders_schema(pool: &PgPool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS poll_reminders (
+                poll_id TEXT NOT NULL REFERENCES polls(id) ON DELETE CASCADE,
+                offset_minutes BIGINT NOT NULL,
+                notify TEXT NOT NULL,
+                sent_at TIMESTAMPTZ,
+                PRIMARY KEY (poll_id, offset_minutes)
+            );
+            "#,
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    // Remind `offset_minutes` before the poll's end time, whatever it is when the time comes
+    pub async fn add_poll_reminders(
+        &self,
+        poll_id: &str,
+        offsets_minutes: &[i64],
+        notify: ReminderNotify,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO poll_reminders (poll_id, offset_minutes, notify)
+            SELECT $1, offset_minutes, $3 FROM UNNEST($2::BIGINT[]) AS offset_minutes
+            ON CONFLICT (poll_id, offset_minutes) DO NOTHING
+            "#,
+        )
+        .bind(poll_id)
+        .bind(offsets_minutes)
+        .bind(notify.as_str())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_due_reminders(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<DueReminder>, Box<dyn std::error::Error + Send + Sync>> {
+        let reminders = sqlx::query(
+            r#"
+            SELECT r.poll_id, r.offset_minutes, r.notify
+            FROM poll_reminders r
+            JOIN polls p ON p.id = r.poll_id
+            WHERE r.sent_at IS NULL
+              AND p.status = 'open'
+              AND p.ends_at > $1
+              AND p.ends_at - make_interval(mins => r.offset_minutes::INTEGER) <= $1
+            ORDER BY r.poll_id, r.offset_minutes
+            "#,
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| DueReminder {
+            poll_id: row.get("poll_id"),
+            offset_minutes: row.get("offset_minutes"),
+            notify: ReminderNotify::parse(&row.get::<String, _>("notify")).unwrap_or(ReminderNotify::Channel),
+        })
+        .collect();
+        Ok(reminders)
+    }
+
+    // Mark reminders as sent and queue one delivery for them. Returns false if they had already
+    // been claimed, so a reminder is posted at most once.
+    pub async fn claim_reminders(
+        &self,
+        poll_id: &str,
+        offsets_minutes: &[i64],
+        notify: ReminderNotify,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(
+            r#"
+            UPDATE poll_reminders
+            SET sent_at = NOW()
+            WHERE poll_id = $1 AND offset_minutes = ANY($2) AND sent_at IS NULL
+            "#,
+        )
+        .bind(poll_id)
+        .bind(offsets_minutes)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        outbox::enqueue(&mut tx, poll_id, OutboxAction::SendReminder(notify)).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    // Everyone with a ballot row in the poll, whatever they rated
+    pub async fn get_poll_voter_ids(
+        &self,
+        poll_id: &str,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let voters = sqlx::query("SELECT DISTINCT user_id FROM votes WHERE poll_id = $1")
+            .bind(poll_id)
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|row| row.get::<String, _>("user_id"))
+            .collect();
+        Ok(voters)
+    }
+}
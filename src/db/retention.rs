@@ -0,0 +1,272 @@
+use super::{lifecycle, Database};
+use crate::models::{PollStatus, RetentionPolicy, ANONYMOUS_VOTER_PREFIX};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+
+// A random stand-in for a voter ID, generated here rather than in SQL so any PostgreSQL version works
+pub(super) fn new_pseudonym() -> String {
+    format!("{}{}", ANONYMOUS_VOTER_PREFIX, uuid::Uuid::new_v4())
+}
+
+// A closed poll that a retention rule applies to
+#[derive(Debug, Clone)]
+pub struct RetentionCandidate {
+    pub poll_id: String,
+    pub guild_id: String,
+    pub question: String,
+    pub closed_at: DateTime<Utc>,
+    pub ballot_count: i64,
+}
+
+// What a retention pass would do (dry run) or did. A poll appears in at most one list,
+// the most destructive one that applies.
+#[derive(Debug, Clone, Default)]
+pub struct RetentionReport {
+    pub anonymize: Vec<RetentionCandidate>,
+    pub purge_ballots: Vec<RetentionCandidate>,
+    pub delete: Vec<RetentionCandidate>,
+}
+
+impl RetentionReport {
+    pub fn is_empty(&self) -> bool {
+        self.anonymize.is_empty() && self.purge_ballots.is_empty() && self.delete.is_empty()
+    }
+}
+
+impl Database {
+    pub(super) async fn init_retention_schema(pool: &PgPool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS retention_policies (
+                guild_id TEXT PRIMARY KEY,
+                anonymize_after_days INTEGER,
+                purge_ballots_after_days INTEGER,
+                delete_after_days INTEGER,
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query(
+            r#"
+            ALTER TABLE polls
+                ADD COLUMN IF NOT EXISTS voters_anonymized_at TIMESTAMPTZ,
+                ADD COLUMN IF NOT EXISTS ballots_purged_at TIMESTAMPTZ;
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Get the retention policy configured for a guild (None if it only uses the global policy)
+    pub async fn get_retention_policy(
+        &self,
+        guild_id: &str,
+    ) -> Result<Option<RetentionPolicy>, Box<dyn std::error::Error + Send + Sync>> {
+        let policy = sqlx::query(
+            r#"
+            SELECT anonymize_after_days, purge_ballots_after_days, delete_after_days
+            FROM retention_policies
+            WHERE guild_id = $1
+            "#,
+        )
+        .bind(guild_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .map(|row| RetentionPolicy {
+            anonymize_after_days: row.get("anonymize_after_days"),
+            purge_ballots_after_days: row.get("purge_ballots_after_days"),
+            delete_after_days: row.get("delete_after_days"),
+        });
+        Ok(policy)
+    }
+
+    // Create or replace the retention policy for a guild
+    pub async fn set_retention_policy(
+        &self,
+        guild_id: &str,
+        policy: &RetentionPolicy,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO retention_policies (guild_id, anonymize_after_days, purge_ballots_after_days, delete_after_days, updated_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (guild_id)
+            DO UPDATE SET anonymize_after_days = EXCLUDED.anonymize_after_days,
+                          purge_ballots_after_days = EXCLUDED.purge_ballots_after_days,
+                          delete_after_days = EXCLUDED.delete_after_days,
+                          updated_at = NOW()
+            "#,
+        )
+        .bind(guild_id)
+        .bind(policy.anonymize_after_days)
+        .bind(policy.purge_ballots_after_days)
+        .bind(policy.delete_after_days)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // Remove a guild's policy so the global policy applies again
+    pub async fn clear_retention_policy(
+        &self,
+        guild_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query("DELETE FROM retention_policies WHERE guild_id = $1")
+            .bind(guild_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // Work out which closed polls each retention rule applies to, without changing anything.
+    // Guild policies override the global one column by column; `guild_id` limits the report to one guild.
+    pub async fn find_retention_candidates(
+        &self,
+        global: &RetentionPolicy,
+        guild_id: Option<&str>,
+    ) -> Result<RetentionReport, Box<dyn std::error::Error + Send + Sync>> {
+        let delete = self
+            .retention_candidates("delete_after_days", "TRUE", global.delete_after_days, guild_id)
+            .await?;
+        let purge_ballots = self
+            .retention_candidates("purge_ballots_after_days", "p.ballots_purged_at IS NULL", global.purge_ballots_after_days, guild_id)
+            .await?
+            .into_iter()
+            .filter(|c| !delete.iter().any(|d| d.poll_id == c.poll_id))
+            .collect::<Vec<_>>();
+        let anonymize = self
+            .retention_candidates(
+                "anonymize_after_days",
+                "p.voters_anonymized_at IS NULL AND p.ballots_purged_at IS NULL",
+                global.anonymize_after_days,
+                guild_id,
+            )
+            .await?
+            .into_iter()
+            .filter(|c| {
+                !delete.iter().any(|d| d.poll_id == c.poll_id)
+                    && !purge_ballots.iter().any(|p| p.poll_id == c.poll_id)
+            })
+            .collect();
+
+        Ok(RetentionReport { anonymize, purge_ballots, delete })
+    }
+
+    // `days_column` and `extra_condition` are fixed strings from find_retention_candidates, never user input
+    async fn retention_candidates(
+        &self,
+        days_column: &str,
+        extra_condition: &str,
+        global_days: Option<i32>,
+        guild_id: Option<&str>,
+    ) -> Result<Vec<RetentionCandidate>, Box<dyn std::error::Error + Send + Sync>> {
+        let query = format!(
+            r#"
+            SELECT p.id, p.guild_id, p.question,
+                   COALESCE(p.closed_at, p.ends_at, p.created_at) AS closed_time,
+                   (SELECT COUNT(DISTINCT v.user_id) FROM votes v WHERE v.poll_id = p.id) AS ballot_count
+            FROM polls p
+            LEFT JOIN retention_policies rp ON rp.guild_id = p.guild_id
+            WHERE p.status IN ('closed', 'archived')
+              AND ($2::TEXT IS NULL OR p.guild_id = $2)
+              AND COALESCE(rp.{days_column}, $1) > 0
+              AND COALESCE(p.closed_at, p.ends_at, p.created_at) < NOW() - make_interval(days => COALESCE(rp.{days_column}, $1))
+              AND {extra_condition}
+            ORDER BY closed_time
+            "#
+        );
+        let candidates = sqlx::query(&query)
+            .bind(global_days)
+            .bind(guild_id)
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(|row| RetentionCandidate {
+                poll_id: row.get("id"),
+                guild_id: row.get("guild_id"),
+                question: row.get("question"),
+                closed_at: row.get("closed_time"),
+                ballot_count: row.get("ballot_count"),
+            })
+            .collect();
+        Ok(candidates)
+    }
+
+    // Replace every real voter ID on a poll with a random pseudonym. Each voter keeps one
+    // pseudonym per poll, so ballots still group together for tabulation and export.
+    pub async fn anonymize_poll_voters(
+        &self,
+        poll_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        let voters: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT user_id FROM votes WHERE poll_id = $1 AND user_id NOT LIKE $2 || '%'",
+        )
+        .bind(poll_id)
+        .bind(ANONYMOUS_VOTER_PREFIX)
+        .fetch_all(&mut *tx)
+        .await?;
+        let pseudonyms: Vec<String> = voters.iter().map(|_| new_pseudonym()).collect();
+        let updated = sqlx::query(
+            r#"
+            UPDATE votes v
+            SET user_id = pseudonyms.pseudonym
+            FROM UNNEST($2::TEXT[], $3::TEXT[]) AS pseudonyms(user_id, pseudonym)
+            WHERE v.poll_id = $1 AND v.user_id = pseudonyms.user_id
+            "#,
+        )
+        .bind(poll_id)
+        .bind(&voters)
+        .bind(&pseudonyms)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        sqlx::query("UPDATE polls SET voters_anonymized_at = NOW() WHERE id = $1")
+            .bind(poll_id)
+            .execute(&mut *tx)
+            .await?;
+        // Reopening would mix pseudonymous and real ballots
+        lifecycle::transition(&mut tx, poll_id, &[PollStatus::Closed], PollStatus::Archived).await?;
+        tx.commit().await?;
+        Ok(updated)
+    }
+
+    // Delete every ballot on a poll; the poll row and its results snapshot are kept
+    pub async fn purge_poll_ballots(
+        &self,
+        poll_id: &str,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let mut tx = self.pool.begin().await?;
+        let deleted = sqlx::query("DELETE FROM votes WHERE poll_id = $1")
+            .bind(poll_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        sqlx::query("UPDATE polls SET ballots_purged_at = NOW(), voters_anonymized_at = COALESCE(voters_anonymized_at, NOW()) WHERE id = $1")
+            .bind(poll_id)
+            .execute(&mut *tx)
+            .await?;
+        lifecycle::transition(&mut tx, poll_id, &[PollStatus::Closed], PollStatus::Archived).await?;
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    // Permanently delete a poll; options and votes go with it through ON DELETE CASCADE
+    pub async fn delete_poll(
+        &self,
+        poll_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query("DELETE FROM polls WHERE id = $1")
+            .bind(poll_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
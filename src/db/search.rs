@@ -0,0 +1,116 @@
+use super::{lifecycle, Database};
+use crate::models::{Poll, PollOrigin, PollStatus, ResultsVisibility, VotingMethod};
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+
+// Short poll IDs need a few characters before they can single out one poll
+const MIN_SHORT_ID_CHARS: usize = 4;
+
+// What a poll ID typed into a command refers to
+pub enum PollIdMatch {
+    Id(String), // A full ID, or the one poll a short ID starts
+    Ambiguous,  // A short ID that starts several polls' IDs
+}
+
+// `%`, `_` and `\` match literally in LIKE patterns built from what members type
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl Database {
+    // Partial polls of a server in one of `statuses` whose question contains `query` or whose ID
+    // starts with it, newest first. With `creator_id` only that member's polls are returned.
+    pub async fn search_polls_by_guild(
+        &self,
+        guild_id: &str,
+        query: &str,
+        statuses: &[PollStatus],
+        creator_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Poll>, Box<dyn std::error::Error + Send + Sync>> {
+        let statuses: Vec<&str> = statuses.iter().map(|status| status.as_str()).collect();
+        let query = escape_like(query.trim());
+        let rows = sqlx::query(
+            r#"
+            SELECT id, creator_id, question, ends_at, starts_at, status, results_visibility, origin
+            FROM polls
+            WHERE guild_id = $1
+              AND status = ANY($2)
+              AND ($3::TEXT IS NULL OR creator_id = $3)
+              AND (question ILIKE '%' || $4 || '%' OR id LIKE LOWER($4) || '%')
+            ORDER BY created_at DESC
+            LIMIT $5
+            "#,
+        )
+        .bind(guild_id)
+        .bind(&statuses)
+        .bind(creator_id)
+        .bind(&query)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut partial_polls = Vec::with_capacity(rows.len());
+        for row in rows {
+            partial_polls.push(Poll {
+                id: row.get("id"),
+                question: row.get("question"),
+                ends_at: row.get::<Option<DateTime<Utc>>, _>("ends_at"),
+                guild_id: guild_id.to_string(),
+                channel_id: String::new(),
+                creator_id: row.get("creator_id"),
+                options: Vec::new(),
+                voting_method: VotingMethod::Plurality,
+                created_at: Utc::now(),
+                starts_at: row.get::<Option<DateTime<Utc>>, _>("starts_at"),
+                status: lifecycle::status_from_row(&row)?,
+                message_id: None,
+                allowed_roles: None,
+                anonymous: false,
+                results_visibility: ResultsVisibility::parse(&row.get::<String, _>("results_visibility"))
+                    .unwrap_or_default(),
+                origin: PollOrigin::parse(&row.get::<String, _>("origin")).unwrap_or_default(),
+                closed_at: None,
+                closed_by: None,
+                close_reason: None,
+                edits: Vec::new(),
+            });
+        }
+
+        Ok(partial_polls)
+    }
+
+    // The full ID of the one poll in the server whose ID starts with `poll_id`, so members can type
+    // the short ID shown in autocomplete suggestions. A prefix of several polls' IDs is ambiguous;
+    // anything else comes back unchanged.
+    pub async fn resolve_poll_id(
+        &self,
+        guild_id: &str,
+        poll_id: &str,
+    ) -> Result<PollIdMatch, Box<dyn std::error::Error + Send + Sync>> {
+        let poll_id = poll_id.trim();
+        if poll_id.chars().count() < MIN_SHORT_ID_CHARS {
+            return Ok(PollIdMatch::Id(poll_id.to_string()));
+        }
+        let ids: Vec<String> = sqlx::query_scalar(
+            "SELECT id FROM polls WHERE guild_id = $1 AND id LIKE LOWER($2) || '%' ORDER BY id LIMIT 2",
+        )
+        .bind(guild_id)
+        .bind(escape_like(poll_id))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(match ids.as_slice() {
+            [id] => PollIdMatch::Id(id.clone()),
+            [_, _] => PollIdMatch::Ambiguous,
+            _ => PollIdMatch::Id(poll_id.to_string()),
+        })
+    }
+}
@@ -0,0 +1,134 @@
+use super::Database;
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+
+// How far voters got with their ballots. A ballot is every vote row a member has in the poll;
+// an option counts as marked when it was given a positive rating (stars, a rank, an approval
+// or the plurality choice). Withdrawn options are left out, as they are in the tally.
+#[derive(Debug, Clone, Default)]
+pub struct BallotStats {
+    pub ballots: i64,
+    pub blank: i64,          // Opened the ballot but left every option unmarked
+    pub bullet: i64,         // Marked exactly one option
+    pub average_marked: f64, // Options marked per ballot that isn't blank
+}
+
+impl Database {
+    pub async fn get_ballot_stats(
+        &self,
+        poll_id: &str,
+    ) -> Result<BallotStats, Box<dyn std::error::Error + Send + Sync>> {
+        let row = sqlx::query(
+            r#"
+            WITH ballots AS (
+                SELECT v.user_id, COUNT(*) FILTER (WHERE v.rating > 0) AS marked
+                FROM votes v
+                JOIN poll_options o ON o.id = v.option_id
+                WHERE v.poll_id = $1 AND NOT o.withdrawn
+                GROUP BY v.user_id
+            )
+            SELECT COUNT(*) AS ballots,
+                   COUNT(*) FILTER (WHERE marked = 0) AS blank,
+                   COUNT(*) FILTER (WHERE marked = 1) AS bullet,
+                   COALESCE(AVG(marked) FILTER (WHERE marked > 0), 0)::FLOAT8 AS average_marked
+            FROM ballots
+            "#,
+        )
+        .bind(poll_id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(BallotStats {
+            ballots: row.get("ballots"),
+            blank: row.get("blank"),
+            bullet: row.get("bullet"),
+            average_marked: row.get("average_marked"),
+        })
+    }
+
+    // Ballots by when they were last changed, as (bucket, count) pairs, where bucket `n` covers
+    // `bucket_seconds` starting `n` buckets after `start`. Empty buckets are left out.
+    pub async fn get_ballot_time_histogram(
+        &self,
+        poll_id: &str,
+        start: DateTime<Utc>,
+        bucket_seconds: i64,
+    ) -> Result<Vec<(i64, i64)>, Box<dyn std::error::Error + Send + Sync>> {
+        let buckets = sqlx::query(
+            r#"
+            SELECT FLOOR(EXTRACT(EPOCH FROM cast_at - $2) / $3)::BIGINT AS bucket, COUNT(*) AS ballots
+            FROM (SELECT MAX(timestamp) AS cast_at FROM votes WHERE poll_id = $1 GROUP BY user_id) b
+            GROUP BY bucket
+            ORDER BY bucket
+            "#,
+        )
+        .bind(poll_id)
+        .bind(start)
+        .bind(bucket_seconds as f64)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.get::<i64, _>("bucket"), row.get::<i64, _>("ballots")))
+        .collect();
+        Ok(buckets)
+    }
+
+    // Ranked ballots that rank none of `finalists`, so they had no say in the last round
+    pub async fn count_exhausted_ballots(
+        &self,
+        poll_id: &str,
+        finalists: &[String],
+    ) -> Result<i64, Box<dyn std::error::Error + Send + Sync>> {
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS exhausted
+            FROM (
+                SELECT v.user_id
+                FROM votes v
+                JOIN poll_options o ON o.id = v.option_id
+                WHERE v.poll_id = $1 AND v.rating > 0 AND NOT o.withdrawn
+                GROUP BY v.user_id
+                HAVING NOT BOOL_OR(v.option_id = ANY($2))
+            ) exhausted
+            "#,
+        )
+        .bind(poll_id)
+        .bind(finalists)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row.get("exhausted"))
+    }
+
+    // The rankings that order every option on the ballot, most common first, as option IDs from
+    // first choice to last with the number of ballots that cast each
+    pub async fn get_common_full_rankings(
+        &self,
+        poll_id: &str,
+        limit: i64,
+    ) -> Result<Vec<(Vec<String>, i64)>, Box<dyn std::error::Error + Send + Sync>> {
+        let rankings = sqlx::query(
+            r#"
+            WITH rankings AS (
+                SELECT v.user_id, ARRAY_AGG(v.option_id ORDER BY v.rating) AS ranking
+                FROM votes v
+                JOIN poll_options o ON o.id = v.option_id
+                WHERE v.poll_id = $1 AND v.rating > 0 AND NOT o.withdrawn
+                GROUP BY v.user_id
+            )
+            SELECT ranking, COUNT(*) AS ballots
+            FROM rankings
+            WHERE CARDINALITY(ranking) = (SELECT COUNT(*) FROM poll_options WHERE poll_id = $1 AND NOT withdrawn)
+            GROUP BY ranking
+            ORDER BY ballots DESC, ranking
+            LIMIT $2
+            "#,
+        )
+        .bind(poll_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| (row.get::<Vec<String>, _>("ranking"), row.get::<i64, _>("ballots")))
+        .collect();
+        Ok(rankings)
+    }
+}
@@ -0,0 +1,218 @@
+use super::Database;
+use crate::models::{PollTemplate, Recurrence, ResultsVisibility, VotingMethod};
+use chrono::{DateTime, NaiveTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+
+const TEMPLATE_COLUMNS: &str = "id, guild_id, name, channel_id, created_by, question, options, voting_method, duration_minutes, \
+                                allowed_roles, anonymous, results_visibility, recurrence_days, recurrence_time, recurrence_timezone, next_run_at";
+
+impl Database {
+    pub(super) async fn init_templates_schema(pool: &PgPool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS poll_templates (
+                id TEXT PRIMARY KEY,
+                guild_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                channel_id TEXT NOT NULL,
+                created_by TEXT NOT NULL,
+                question TEXT NOT NULL,
+                options TEXT[] NOT NULL,
+                voting_method TEXT NOT NULL,
+                duration_minutes BIGINT,
+                allowed_roles TEXT[],
+                anonymous BOOLEAN NOT NULL DEFAULT FALSE,
+                recurrence_days TEXT,
+                recurrence_time TEXT,
+                recurrence_timezone TEXT,
+                next_run_at TIMESTAMPTZ,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                UNIQUE (guild_id, name)
+            );
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        sqlx::query("ALTER TABLE poll_templates ADD COLUMN IF NOT EXISTS results_visibility TEXT NOT NULL DEFAULT 'hidden';")
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    // Create a template, or replace the poll settings of an existing one with the same name.
+    // An existing schedule is kept.
+    pub async fn save_template(
+        &self,
+        template: &PollTemplate,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            INSERT INTO poll_templates (id, guild_id, name, channel_id, created_by, question, options, voting_method,
+                                        duration_minutes, allowed_roles, anonymous, results_visibility)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+            ON CONFLICT (guild_id, name)
+            DO UPDATE SET channel_id = EXCLUDED.channel_id,
+                          created_by = EXCLUDED.created_by,
+                          question = EXCLUDED.question,
+                          options = EXCLUDED.options,
+                          voting_method = EXCLUDED.voting_method,
+                          duration_minutes = EXCLUDED.duration_minutes,
+                          allowed_roles = EXCLUDED.allowed_roles,
+                          anonymous = EXCLUDED.anonymous,
+                          results_visibility = EXCLUDED.results_visibility
+            "#,
+        )
+        .bind(&template.id)
+        .bind(&template.guild_id)
+        .bind(&template.name)
+        .bind(&template.channel_id)
+        .bind(&template.created_by)
+        .bind(&template.question)
+        .bind(&template.options)
+        .bind(template.voting_method.as_str())
+        .bind(template.duration_minutes)
+        .bind(&template.allowed_roles)
+        .bind(template.anonymous)
+        .bind(template.results_visibility.as_str())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_template(
+        &self,
+        guild_id: &str,
+        name: &str,
+    ) -> Result<Option<PollTemplate>, Box<dyn std::error::Error + Send + Sync>> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM poll_templates WHERE guild_id = $1 AND name = $2",
+            TEMPLATE_COLUMNS
+        ))
+        .bind(guild_id)
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+        row.as_ref().map(template_from_row).transpose()
+    }
+
+    pub async fn get_templates_by_guild(
+        &self,
+        guild_id: &str,
+    ) -> Result<Vec<PollTemplate>, Box<dyn std::error::Error + Send + Sync>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM poll_templates WHERE guild_id = $1 ORDER BY name",
+            TEMPLATE_COLUMNS
+        ))
+        .bind(guild_id)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(template_from_row).collect()
+    }
+
+    // Returns false if there was no template with that name
+    pub async fn delete_template(
+        &self,
+        guild_id: &str,
+        name: &str,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let result = sqlx::query("DELETE FROM poll_templates WHERE guild_id = $1 AND name = $2")
+            .bind(guild_id)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    // Set or (with None) remove a template's recurring schedule
+    pub async fn set_template_schedule(
+        &self,
+        template_id: &str,
+        recurrence: Option<&Recurrence>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        sqlx::query(
+            r#"
+            UPDATE poll_templates
+            SET recurrence_days = $2, recurrence_time = $3, recurrence_timezone = $4, next_run_at = $5
+            WHERE id = $1
+            "#,
+        )
+        .bind(template_id)
+        .bind(recurrence.map(|r| r.days_str()))
+        .bind(recurrence.map(|r| r.time.format("%H:%M").to_string()))
+        .bind(recurrence.map(|r| r.timezone.name().to_string()))
+        .bind(next_run_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // Scheduled templates whose next run time has passed
+    pub async fn get_due_templates(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<PollTemplate>, Box<dyn std::error::Error + Send + Sync>> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM poll_templates WHERE next_run_at IS NOT NULL AND next_run_at <= $1",
+            TEMPLATE_COLUMNS
+        ))
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await?;
+        rows.iter().map(template_from_row).collect()
+    }
+
+    // Move a template's schedule on from `expected` to `next`. Returns false if another run
+    // already moved it, so each occurrence posts at most one poll.
+    pub async fn advance_template_schedule(
+        &self,
+        template_id: &str,
+        expected: DateTime<Utc>,
+        next: Option<DateTime<Utc>>,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let result = sqlx::query("UPDATE poll_templates SET next_run_at = $3 WHERE id = $1 AND next_run_at = $2")
+            .bind(template_id)
+            .bind(expected)
+            .bind(next)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn template_from_row(row: &PgRow) -> Result<PollTemplate, Box<dyn std::error::Error + Send + Sync>> {
+    let method: String = row.get("voting_method");
+    let voting_method = VotingMethod::parse(&method).ok_or_else(|| format!("Unknown voting method: {}", method))?;
+
+    // A schedule is only usable if all three parts parse
+    let recurrence = match (
+        row.get::<Option<String>, _>("recurrence_days"),
+        row.get::<Option<String>, _>("recurrence_time"),
+        row.get::<Option<String>, _>("recurrence_timezone"),
+    ) {
+        (Some(days), Some(time), Some(timezone)) => Recurrence::parse_days(&days)
+            .zip(NaiveTime::parse_from_str(&time, "%H:%M").ok())
+            .zip(timezone.parse().ok())
+            .map(|((days, time), timezone)| Recurrence { days, time, timezone }),
+        _ => None,
+    };
+
+    Ok(PollTemplate {
+        id: row.get("id"),
+        guild_id: row.get("guild_id"),
+        name: row.get("name"),
+        channel_id: row.get("channel_id"),
+        created_by: row.get("created_by"),
+        question: row.get("question"),
+        options: row.get("options"),
+        voting_method,
+        duration_minutes: row.get("duration_minutes"),
+        allowed_roles: row.get("allowed_roles"),
+        anonymous: row.get("anonymous"),
+        results_visibility: ResultsVisibility::parse(&row.get::<String, _>("results_visibility")).unwrap_or_default(),
+        recurrence,
+        next_run_at: row.get("next_run_at"),
+    })
+}
@@ -0,0 +1,61 @@
+use super::{aggregate, votable_options, Ballot};
+use crate::models::{Poll, VotingMethod};
+
+// Aggregated Ballot Information Format. Options get short tokens (`O1`, `O2`, ... in poll
+// order) defined with their full text in the header. Ranked and plurality ballots are written as
+// rankings (`3:O2>O1`); STAR and approval ballots as rated orders with ties (`2:O1/5>O2/3=O3/3`).
+// Withdrawn options are left out, as they are in the tally. The header also carries the
+// tie-breaking seed the results were announced with.
+pub fn election(poll: &Poll, ballots: &[Ballot], seed: u64) -> String {
+    let options = votable_options(poll);
+    let token = |option_id: &str| {
+        options
+            .iter()
+            .position(|option| option.id == option_id)
+            .map(|i| format!("O{}", i + 1))
+    };
+
+    let lines: Vec<String> = match poll.voting_method {
+        VotingMethod::Ranked | VotingMethod::Plurality => ballots
+            .iter()
+            .map(|ballot| ballot.ranking().into_iter().filter_map(token).collect::<Vec<_>>().join(">"))
+            .filter(|ranking| !ranking.is_empty())
+            .collect(),
+        VotingMethod::Star | VotingMethod::Approval => ballots
+            .iter()
+            .map(|ballot| {
+                ballot
+                    .rating_groups(&options)
+                    .into_iter()
+                    .map(|(rating, group)| {
+                        group
+                            .iter()
+                            .filter_map(|option| token(&option.id))
+                            .map(|token| format!("{}/{}", token, rating))
+                            .collect::<Vec<_>>()
+                            .join("=")
+                    })
+                    .collect::<Vec<_>>()
+                    .join(">")
+            })
+            .collect(),
+    };
+
+    let mut text = format!(
+        "{{version: \"0.1\"}}\n{{title: \"{}\"}}\n{{voting_method: \"{}\"}}\n{{tie_break_seed: {}}}\n",
+        escape(&poll.question),
+        poll.voting_method,
+        seed
+    );
+    for (i, option) in options.iter().enumerate() {
+        text.push_str(&format!("=O{}:[{}]\n", i + 1, option.text.replace(['[', ']'], "").replace(['\n', '\r'], " ")));
+    }
+    for (count, line) in aggregate(lines) {
+        text.push_str(&format!("{}:{}\n", count, line));
+    }
+    text
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace(['\n', '\r'], " ")
+}
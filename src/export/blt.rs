@@ -0,0 +1,46 @@
+use super::{aggregate, Ballot};
+use crate::import::blt::SEED_COMMENT;
+use crate::models::Poll;
+
+// OpenSTV's BLT format for a single-winner election: candidate and seat counts, withdrawn
+// candidates as negative numbers, one line per distinct ranking with its count, then the
+// candidate names and the title. Candidates are numbered in poll order, withdrawn ones included,
+// so the numbers match the poll embed. The tie-breaking seed follows as a comment, after the
+// title where other tools stop reading.
+pub fn election(poll: &Poll, ballots: &[Ballot], seed: u64) -> String {
+    let number = |option_id: &str| poll.options.iter().position(|option| option.id == option_id).map(|i| i + 1);
+
+    let mut text = format!("{} 1\n", poll.options.len());
+    let withdrawn: Vec<String> = poll
+        .options
+        .iter()
+        .enumerate()
+        .filter(|(_, option)| option.withdrawn)
+        .map(|(i, _)| format!("-{}", i + 1))
+        .collect();
+    if !withdrawn.is_empty() {
+        text.push_str(&format!("{}\n", withdrawn.join(" ")));
+    }
+
+    let rankings = ballots
+        .iter()
+        .map(|ballot| ballot.ranking().into_iter().filter_map(number).collect::<Vec<_>>())
+        .filter(|ranking| !ranking.is_empty());
+    for (count, ranking) in aggregate(rankings) {
+        let ranking: Vec<String> = ranking.iter().map(|number| number.to_string()).collect();
+        text.push_str(&format!("{} {} 0\n", count, ranking.join(" ")));
+    }
+    text.push_str("0\n");
+
+    for option in &poll.options {
+        text.push_str(&format!("{}\n", quote(&option.text)));
+    }
+    text.push_str(&format!("{}\n", quote(&poll.question)));
+    text.push_str(&format!("{} {}\n", SEED_COMMENT, seed));
+    text
+}
+
+// BLT strings can't contain double quotes or line breaks
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "'").replace(['\n', '\r'], " "))
+}
@@ -0,0 +1,127 @@
+use super::Ballot;
+use crate::db::PollActivity;
+use crate::models::{Poll, PollOption, VotingMethod};
+
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S UTC";
+
+// Long form: one row per vote, including the zero ratings of options a member didn't pick.
+// Withdrawn options are marked in the text, as in the wide form's headers.
+pub fn votes(poll: &Poll, ballots: &[Ballot]) -> String {
+    let rating_header = match poll.voting_method {
+        VotingMethod::Star => "Stars",
+        VotingMethod::Plurality => "Selected",
+        VotingMethod::Ranked => "Rank",
+        VotingMethod::Approval => "Approved",
+    };
+
+    let mut rows = vec![row(&["User ID", "Option Number", "Option ID", "Option Text", rating_header, "Timestamp"])];
+    for ballot in ballots {
+        for vote in &ballot.votes {
+            let (number, text) = match poll.options.iter().position(|option| option.id == vote.option_id) {
+                Some(i) => ((i + 1).to_string(), option_label(&poll.options[i])),
+                None => (String::new(), "Unknown Option".to_string()),
+            };
+            rows.push(row(&[
+                &ballot.voter,
+                &number,
+                &vote.option_id,
+                &text,
+                &vote.rating.to_string(),
+                &vote.timestamp.format(TIMESTAMP_FORMAT).to_string(),
+            ]));
+        }
+    }
+    rows.concat()
+}
+
+// Wide form: one row per ballot. STAR and approval polls get a column per option, plurality
+// polls the chosen option, and ranked polls a column per preference.
+pub fn ballots(poll: &Poll, ballots: &[Ballot]) -> String {
+    let option_text = |option_id: &str| {
+        poll.options
+            .iter()
+            .find(|option| option.id == option_id)
+            .map(option_label)
+            .unwrap_or_else(|| "Unknown Option".to_string())
+    };
+
+    let mut header = vec!["Voter".to_string(), "Submitted".to_string()];
+    match poll.voting_method {
+        VotingMethod::Star | VotingMethod::Approval => {
+            header.extend(poll.options.iter().map(option_label));
+        }
+        VotingMethod::Plurality => header.push("Choice".to_string()),
+        VotingMethod::Ranked => header.extend((1..=poll.options.len()).map(|rank| format!("Rank {}", rank))),
+    }
+    let mut rows = vec![row(&header.iter().map(String::as_str).collect::<Vec<_>>())];
+
+    for ballot in ballots {
+        let mut fields = vec![ballot.voter.clone(), ballot.submitted_at.format(TIMESTAMP_FORMAT).to_string()];
+        match poll.voting_method {
+            VotingMethod::Star | VotingMethod::Approval => {
+                fields.extend(
+                    poll.options
+                        .iter()
+                        .map(|option| ballot.rating(&option.id).map(|rating| rating.to_string()).unwrap_or_default()),
+                );
+            }
+            VotingMethod::Plurality => {
+                let choice = ballot.votes.iter().find(|vote| vote.rating > 0).map(|vote| option_text(&vote.option_id));
+                fields.push(choice.unwrap_or_default());
+            }
+            VotingMethod::Ranked => {
+                let ranking = ballot.ranking();
+                fields.extend((0..poll.options.len()).map(|i| ranking.get(i).map(|id| option_text(id)).unwrap_or_default()));
+            }
+        }
+        rows.push(row(&fields.iter().map(String::as_str).collect::<Vec<_>>()));
+    }
+    rows.concat()
+}
+
+// `/poll history`: one row per poll with its ballot count, never the ballots themselves
+pub fn history(polls: &[PollActivity]) -> String {
+    let mut rows = vec![row(&["Poll ID", "Created", "Question", "Method", "Status", "Creator ID", "Anonymous", "Ballots"])];
+    for poll in polls {
+        rows.push(row(&[
+            &poll.poll_id,
+            &poll.created_at.format(TIMESTAMP_FORMAT).to_string(),
+            &poll.question,
+            poll.voting_method.as_str(),
+            &poll.status,
+            &poll.creator_id,
+            if poll.anonymous { "yes" } else { "no" },
+            &poll.ballots.to_string(),
+        ]));
+    }
+    rows.concat()
+}
+
+fn option_label(option: &PollOption) -> String {
+    if option.withdrawn {
+        format!("{} (withdrawn)", option.text)
+    } else {
+        option.text.clone()
+    }
+}
+
+fn row(fields: &[&str]) -> String {
+    let mut line = fields.iter().map(|field| escape(field)).collect::<Vec<_>>().join(",");
+    line.push('\n');
+    line
+}
+
+// Quote fields that contain separators, quotes or line breaks; formula-like text is prefixed so
+// spreadsheets don't evaluate option text as a formula
+fn escape(field: &str) -> String {
+    let field = if field.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", field)
+    } else {
+        field.to_string()
+    };
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
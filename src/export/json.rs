@@ -0,0 +1,58 @@
+use super::Ballot;
+use crate::models::Poll;
+use crate::voting::PollResults;
+use serde_json::{json, Value};
+
+// Everything needed to audit or re-tally the poll elsewhere: metadata, the results announced at
+// close, the edit log and every ballot keyed by option ID
+pub fn poll(poll: &Poll, ballots: &[Ballot], results: &PollResults) -> Value {
+    let options: Vec<Value> = poll
+        .options
+        .iter()
+        .enumerate()
+        .map(|(i, option)| {
+            json!({
+                "number": i + 1,
+                "id": option.id,
+                "text": option.text,
+                "withdrawn": option.withdrawn,
+            })
+        })
+        .collect();
+
+    let ballots: Vec<Value> = ballots
+        .iter()
+        .map(|ballot| {
+            let ratings: serde_json::Map<String, Value> = ballot
+                .votes
+                .iter()
+                .map(|vote| (vote.option_id.clone(), json!(vote.rating)))
+                .collect();
+            json!({
+                "voter": ballot.voter,
+                "submitted_at": ballot.submitted_at,
+                "ratings": ratings,
+            })
+        })
+        .collect();
+
+    json!({
+        "poll": {
+            "id": poll.id,
+            "question": poll.question,
+            "voting_method": poll.voting_method.as_str(),
+            "status": poll.status.as_str(),
+            "anonymous": poll.anonymous,
+            "created_at": poll.created_at,
+            "starts_at": poll.starts_at,
+            "ends_at": poll.ends_at,
+            "closed_at": poll.closed_at,
+            "closed_by": poll.closed_by,
+            "close_reason": poll.close_reason.map(|reason| reason.as_str()),
+            "options": options,
+            "edits": poll.edits,
+        },
+        "results": results,
+        "ballots": ballots,
+    })
+}
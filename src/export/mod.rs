@@ -0,0 +1,317 @@
+pub mod abif;
+pub mod blt;
+pub mod csv;
+pub mod json;
+pub mod preflib;
+
+use crate::models::{Poll, PollOption, Vote, VotingMethod};
+use crate::voting::PollResults;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+
+// File formats `/poll export` can produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    CsvLong, // One row per vote
+    CsvWide, // One row per ballot, columns depend on the voting method
+    Json,    // Poll metadata, results snapshot and ballots
+    // Election interchange formats for external tabulators. They aggregate identical ballots
+    // and carry no voter IDs, so they can be published for auditing.
+    Blt,     // OpenSTV and most STV/IRV tools; rankings only
+    PrefLib, // `.soc`, `.soi` or `.toc`, whichever fits the ballots
+    Abif,    // Aggregated Ballot Information Format, rankings and ratings
+}
+
+// A rendered export, ready to attach
+pub struct ExportFile {
+    pub file_name: String,
+    pub data: Vec<u8>,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 6] = [
+        ExportFormat::CsvLong,
+        ExportFormat::CsvWide,
+        ExportFormat::Json,
+        ExportFormat::Blt,
+        ExportFormat::PrefLib,
+        ExportFormat::Abif,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportFormat::CsvLong => "csv_long",
+            ExportFormat::CsvWide => "csv_wide",
+            ExportFormat::Json => "json",
+            ExportFormat::Blt => "blt",
+            ExportFormat::PrefLib => "preflib",
+            ExportFormat::Abif => "abif",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        ExportFormat::ALL.into_iter().find(|format| format.as_str() == value)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::CsvLong => "CSV, one row per vote",
+            ExportFormat::CsvWide => "CSV, one row per ballot",
+            ExportFormat::Json => "JSON with results",
+            ExportFormat::Blt => "BLT, anonymized rankings",
+            ExportFormat::PrefLib => "PrefLib, anonymized ballots",
+            ExportFormat::Abif => "ABIF, anonymized ballots",
+        }
+    }
+
+    // BLT has no way to express ratings or ties
+    pub fn supports(&self, method: &VotingMethod) -> bool {
+        match self {
+            ExportFormat::Blt => matches!(method, VotingMethod::Ranked | VotingMethod::Plurality),
+            _ => true,
+        }
+    }
+
+    pub fn render(
+        &self,
+        poll: &Poll,
+        votes: &[Vote],
+        results: &PollResults,
+    ) -> Result<ExportFile, Box<dyn std::error::Error + Send + Sync>> {
+        let ballots = Ballot::collect(poll, votes);
+        let file = |suffix: &str, extension: &str, data: Vec<u8>| ExportFile {
+            file_name: format!("poll-{}-{}.{}", poll.id, suffix, extension),
+            data,
+        };
+        Ok(match self {
+            ExportFormat::CsvLong => file("votes", "csv", csv::votes(poll, &ballots).into_bytes()),
+            ExportFormat::CsvWide => file("ballots", "csv", csv::ballots(poll, &ballots).into_bytes()),
+            ExportFormat::Json => file("poll", "json", serde_json::to_vec_pretty(&json::poll(poll, &ballots, results))?),
+            ExportFormat::Blt => file("ballots", "blt", blt::election(poll, &ballots, results.seed).into_bytes()),
+            ExportFormat::PrefLib => {
+                let (data_type, data) = preflib::election(poll, &ballots);
+                file("ballots", data_type, data.into_bytes())
+            }
+            ExportFormat::Abif => file("ballots", "abif", abif::election(poll, &ballots, results.seed).into_bytes()),
+        })
+    }
+}
+
+// Counts of identical ballots, most common first (ties in key order), so interchange files are
+// stable between exports
+pub fn aggregate<K: Ord>(keys: impl IntoIterator<Item = K>) -> Vec<(usize, K)> {
+    let mut counts: BTreeMap<K, usize> = BTreeMap::new();
+    for key in keys {
+        *counts.entry(key).or_default() += 1;
+    }
+    let mut counts: Vec<(usize, K)> = counts.into_iter().map(|(key, count)| (count, key)).collect();
+    counts.sort_by_key(|(count, _)| std::cmp::Reverse(*count));
+    counts
+}
+
+// Options still on the ballot, numbered from 1 in poll order, as the tally sees them
+pub fn votable_options(poll: &Poll) -> Vec<&PollOption> {
+    poll.options.iter().filter(|option| !option.withdrawn).collect()
+}
+
+// One member's votes on a poll, in option order
+pub struct Ballot<'a> {
+    pub voter: String, // User ID, or "Voter N" on anonymous polls
+    pub submitted_at: DateTime<Utc>, // Latest change to any of its votes
+    pub votes: Vec<&'a Vote>,
+}
+
+impl<'a> Ballot<'a> {
+    // Ballots in order of each member's first vote, so anonymous labels are stable between exports
+    pub fn collect(poll: &Poll, votes: &'a [Vote]) -> Vec<Ballot<'a>> {
+        let position: HashMap<&str, usize> =
+            poll.options.iter().enumerate().map(|(i, option)| (option.id.as_str(), i)).collect();
+
+        let mut by_voter: HashMap<&str, Vec<&'a Vote>> = HashMap::new();
+        for vote in votes {
+            by_voter.entry(vote.user_id.as_str()).or_default().push(vote);
+        }
+
+        let mut ballots: Vec<(DateTime<Utc>, &str, Vec<&'a Vote>)> = by_voter
+            .into_iter()
+            .map(|(user_id, mut votes)| {
+                votes.sort_by_key(|vote| position.get(vote.option_id.as_str()).copied().unwrap_or(usize::MAX));
+                let first_vote = votes.iter().map(|vote| vote.timestamp).min().unwrap_or_default();
+                (first_vote, user_id, votes)
+            })
+            .collect();
+        ballots.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+
+        ballots
+            .into_iter()
+            .enumerate()
+            .map(|(i, (_, user_id, votes))| Ballot {
+                voter: if poll.anonymous { format!("Voter {}", i + 1) } else { user_id.to_string() },
+                submitted_at: votes.iter().map(|vote| vote.timestamp).max().unwrap_or_default(),
+                votes,
+            })
+            .collect()
+    }
+
+    pub fn rating(&self, option_id: &str) -> Option<i32> {
+        self.votes.iter().find(|vote| vote.option_id == option_id).map(|vote| vote.rating)
+    }
+
+    // Option IDs in preference order, for ranked polls (and the pick of a plurality ballot);
+    // unranked options (rank 0) are left out
+    pub fn ranking(&self) -> Vec<&'a str> {
+        let mut ranked: Vec<&&'a Vote> = self.votes.iter().filter(|vote| vote.rating > 0).collect();
+        ranked.sort_by_key(|vote| vote.rating);
+        ranked.into_iter().map(|vote| vote.option_id.as_str()).collect()
+    }
+
+    // Groups of equally rated options, best first, with their rating; options the member
+    // didn't rate count as 0, as they do in the tally
+    pub fn rating_groups<'o>(&self, options: &[&'o PollOption]) -> Vec<(i32, Vec<&'o PollOption>)> {
+        let mut groups: BTreeMap<std::cmp::Reverse<i32>, Vec<&'o PollOption>> = BTreeMap::new();
+        for option in options {
+            let rating = self.rating(&option.id).unwrap_or(0);
+            groups.entry(std::cmp::Reverse(rating)).or_default().push(option);
+        }
+        groups.into_iter().map(|(rating, options)| (rating.0, options)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::ImportFormat;
+    use crate::voting::{calculate_poll_results, tally};
+    use chrono::Duration;
+
+    // A poll and its votes; each ballot is a rating (or rank) per option, in option order
+    fn poll_with_ballots(method: VotingMethod, options: &[&str], ballots: &[&[i32]]) -> (Poll, Vec<Vote>) {
+        let poll = Poll::new(
+            "guild".to_string(),
+            "channel".to_string(),
+            "creator".to_string(),
+            "Where should we eat?".to_string(),
+            options.iter().map(|option| option.to_string()).collect(),
+            method,
+            Some(0),
+            None,
+        );
+        let start = Utc::now();
+        let poll_id = &poll.id;
+        let votes = ballots
+            .iter()
+            .enumerate()
+            .flat_map(|(i, ratings)| {
+                poll.options.iter().zip(ratings.iter()).map(move |(option, rating)| Vote {
+                    user_id: format!("user-{}", i + 1),
+                    poll_id: poll_id.clone(),
+                    option_id: option.id.clone(),
+                    rating: *rating,
+                    timestamp: start + Duration::seconds(i as i64),
+                })
+            })
+            .collect();
+        (poll, votes)
+    }
+
+    // Label, (option text, score) per count, eliminated option texts and notes
+    type RoundOutcome = (String, Vec<(String, f64)>, Vec<String>, Vec<String>);
+
+    // What a recount has to reproduce, by option text since imports get new option IDs
+    fn outcome(results: &PollResults) -> (String, String, Vec<RoundOutcome>) {
+        let rounds = results
+            .rounds
+            .iter()
+            .map(|round| {
+                let text = |id: &String| round.counts.iter().find(|count| &count.option_id == id).map(|count| count.option_text.clone());
+                (
+                    round.label.clone(),
+                    round.counts.iter().map(|count| (count.option_text.clone(), count.score)).collect(),
+                    round.eliminated.iter().filter_map(text).collect(),
+                    round.notes.clone(),
+                )
+            })
+            .collect();
+        (results.winner.clone(), results.summary.clone(), rounds)
+    }
+
+    // Export in every format the tabulator reads, recount each file as `trusty-vote-tally` does,
+    // and compare with the results the bot announces
+    fn assert_recounts_match(poll: &Poll, votes: &[Vote]) {
+        let expected = calculate_poll_results(poll, votes);
+        for format in ExportFormat::ALL.into_iter().filter(|format| format.supports(&poll.voting_method)) {
+            let import_format = match format {
+                ExportFormat::CsvLong | ExportFormat::CsvWide => ImportFormat::Csv,
+                ExportFormat::Json => ImportFormat::Json,
+                ExportFormat::Blt => ImportFormat::Blt,
+                ExportFormat::Abif => ImportFormat::Abif,
+                ExportFormat::PrefLib => continue,
+            };
+            let file = format.render(poll, votes, &expected).expect("export renders");
+            let text = String::from_utf8(file.data).expect("export is UTF-8");
+            let election = import_format.read(&text).unwrap_or_else(|e| panic!("{}: {}", format.as_str(), e));
+
+            // CSV has nowhere to put the seed; the tabulator is given it with `--seed`
+            let seed = match format {
+                ExportFormat::CsvLong | ExportFormat::CsvWide => expected.seed,
+                _ => election.seed.unwrap_or_else(|| panic!("{} export has no seed", format.as_str())),
+            };
+            let imported = election
+                .into_poll(String::new(), String::new(), String::new(), None, Some(poll.voting_method.clone()))
+                .unwrap_or_else(|e| panic!("{}: {}", format.as_str(), e));
+            let recount = tally(&imported.poll, &imported.votes, seed);
+            assert_eq!(outcome(&recount), outcome(&expected), "{} recount differs", format.as_str());
+        }
+    }
+
+    #[test]
+    fn ranked_recounts_match_with_blank_ballots_and_a_withdrawn_option() {
+        let (mut poll, votes) = poll_with_ballots(
+            VotingMethod::Ranked,
+            &["Tacos", "Pizza", "Sushi", "Curry"],
+            &[&[1, 2, 0, 0], &[2, 1, 0, 0], &[0, 0, 1, 2], &[0, 0, 2, 1], &[0, 0, 0, 0], &[0, 0, 0, 0], &[0, 3, 1, 2]],
+        );
+        poll.options[3].withdrawn = true;
+        assert_recounts_match(&poll, &votes);
+    }
+
+    #[test]
+    fn ranked_recounts_match_when_every_candidate_ties() {
+        let (poll, votes) = poll_with_ballots(
+            VotingMethod::Ranked,
+            &["Tacos", "Pizza", "Sushi"],
+            &[&[1, 2, 3], &[3, 1, 2], &[2, 3, 1]],
+        );
+        assert_recounts_match(&poll, &votes);
+    }
+
+    #[test]
+    fn star_recounts_match_with_tied_scores() {
+        let (poll, votes) = poll_with_ballots(
+            VotingMethod::Star,
+            &["Tacos", "Pizza", "Sushi"],
+            &[&[5, 0, 3], &[0, 5, 3], &[0, 0, 0], &[4, 4, 1]],
+        );
+        assert_recounts_match(&poll, &votes);
+    }
+
+    #[test]
+    fn approval_recounts_match_with_tied_counts() {
+        let (poll, votes) = poll_with_ballots(
+            VotingMethod::Approval,
+            &["Tacos", "Pizza", "Sushi"],
+            &[&[1, 1, 0], &[1, 1, 1], &[0, 0, 0]],
+        );
+        assert_recounts_match(&poll, &votes);
+    }
+
+    #[test]
+    fn plurality_recounts_match_with_tied_counts() {
+        let (poll, votes) = poll_with_ballots(
+            VotingMethod::Plurality,
+            &["Tacos", "Pizza", "Sushi"],
+            &[&[1, 0, 0], &[0, 1, 0], &[0, 0, 1], &[0, 0, 0]],
+        );
+        assert_recounts_match(&poll, &votes);
+    }
+}
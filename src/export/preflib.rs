@@ -0,0 +1,98 @@
+use super::{aggregate, votable_options, Ballot};
+use crate::models::{Poll, VotingMethod};
+use chrono::Utc;
+
+// PrefLib's data format. Returns the data type, which is also the file extension:
+// - `soc` (strict complete orders) for ranked polls where every ballot ranks every option
+// - `soi` (strict incomplete orders) for other ranked polls and for plurality picks
+// - `toc` (orders with ties) for STAR and approval ballots, grouping options with equal ratings
+// Withdrawn options are left out, as they are in the tally.
+pub fn election(poll: &Poll, ballots: &[Ballot]) -> (&'static str, String) {
+    let options = votable_options(poll);
+    let number = |option_id: &str| options.iter().position(|option| option.id == option_id).map(|i| i + 1);
+
+    let (data_type, orders): (&'static str, Vec<(usize, String)>) = match poll.voting_method {
+        VotingMethod::Ranked | VotingMethod::Plurality => {
+            let rankings: Vec<Vec<usize>> = ballots
+                .iter()
+                .map(|ballot| ballot.ranking().into_iter().filter_map(number).collect::<Vec<_>>())
+                .filter(|ranking| !ranking.is_empty())
+                .collect();
+            let complete = matches!(poll.voting_method, VotingMethod::Ranked)
+                && rankings.iter().all(|ranking| ranking.len() == options.len());
+            let orders = aggregate(rankings)
+                .into_iter()
+                .map(|(count, ranking)| {
+                    let ranking: Vec<String> = ranking.iter().map(|number| number.to_string()).collect();
+                    (count, ranking.join(","))
+                })
+                .collect();
+            (if complete { "soc" } else { "soi" }, orders)
+        }
+        VotingMethod::Star | VotingMethod::Approval => {
+            let orders = ballots.iter().map(|ballot| {
+                ballot
+                    .rating_groups(&options)
+                    .into_iter()
+                    .map(|(_, group)| {
+                        let mut numbers: Vec<usize> = group.iter().filter_map(|option| number(&option.id)).collect();
+                        numbers.sort_unstable();
+                        numbers
+                    })
+                    .collect::<Vec<_>>()
+            });
+            let orders = aggregate(orders)
+                .into_iter()
+                .map(|(count, groups)| {
+                    let groups: Vec<String> = groups
+                        .iter()
+                        .map(|group| {
+                            let numbers: Vec<String> = group.iter().map(|number| number.to_string()).collect();
+                            if numbers.len() == 1 { numbers.concat() } else { format!("{{{}}}", numbers.join(",")) }
+                        })
+                        .collect();
+                    (count, groups.join(","))
+                })
+                .collect();
+            ("toc", orders)
+        }
+    };
+
+    let voters: usize = orders.iter().map(|(count, _)| count).sum();
+    let date = poll.closed_at.unwrap_or_else(Utc::now).format("%Y-%m-%d");
+    let mut text = format!(
+        "# FILE NAME: poll-{}-ballots.{}\n\
+         # TITLE: {}\n\
+         # DESCRIPTION: {} poll exported from Discord\n\
+         # DATA TYPE: {}\n\
+         # MODIFICATION TYPE: original\n\
+         # RELATES TO: \n\
+         # RELATED FILES: \n\
+         # PUBLICATION DATE: {}\n\
+         # MODIFICATION DATE: {}\n\
+         # NUMBER ALTERNATIVES: {}\n\
+         # NUMBER VOTERS: {}\n\
+         # NUMBER UNIQUE ORDERS: {}\n",
+        poll.id,
+        data_type,
+        single_line(&poll.question),
+        poll.voting_method,
+        data_type,
+        date,
+        date,
+        options.len(),
+        voters,
+        orders.len()
+    );
+    for (i, option) in options.iter().enumerate() {
+        text.push_str(&format!("# ALTERNATIVE NAME {}: {}\n", i + 1, single_line(&option.text)));
+    }
+    for (count, order) in orders {
+        text.push_str(&format!("{}: {}\n", count, order));
+    }
+    (data_type, text)
+}
+
+fn single_line(value: &str) -> String {
+    value.replace(['\n', '\r'], " ")
+}
@@ -1,7 +1,8 @@
-mod vote;
+pub mod vote;
 
 use crate::db::Database;
-use crate::models::Poll;
+use crate::models::{CloseReason, Poll, PollStatus};
+use crate::permissions::{can_manage_poll, Caller, PollAction};
 use serenity::model::application::interaction::{Interaction, InteractionResponseType};
 use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
 use serenity::model::application::interaction::message_component::MessageComponentInteraction;
@@ -18,6 +19,11 @@ pub async fn handle_command(
     info!("Received command: {}", command.data.name);
     match command.data.name.as_str() {
         "poll" => crate::commands::poll::handle_poll_command(database, ctx, command).await?,
+        "pollconfig" => crate::commands::config::handle_config_command(database, ctx, command).await?,
+        "privacy" => crate::commands::privacy::handle_privacy_command(database, ctx, command).await?,
+        "pollretention" => crate::commands::retention::handle_retention_command(database, ctx, command).await?,
+        "polltemplate" => crate::commands::template::handle_template_command(database, ctx, command).await?,
+        "polldeliveries" => crate::commands::deliveries::handle_deliveries_command(database, ctx, command).await?,
         _ => {
             command.create_interaction_response(&ctx.http, |response| {
                 response
@@ -70,16 +76,39 @@ pub async fn handle_component(
             // We need to fetch the poll to get channel_id and message_id
             match database.get_poll(poll_id).await {
                 Ok(poll) => {
+                    // The menu only lists polls the member may end, but re-check in case their roles changed
+                    let settings = database.get_guild_settings(&poll.guild_id).await?;
+                    let caller = Caller::new(&component.user, component.member.as_ref());
+                    if !can_manage_poll(&caller, &poll, &settings) {
+                        component.create_interaction_response(&ctx.http, |response| {
+                            response.kind(InteractionResponseType::UpdateMessage)
+                                .interaction_response_data(|msg| msg.content(PollAction::End.refusal_message()).components(|c| c))
+                        }).await?;
+                        return Ok(());
+                    }
+
                     component.create_interaction_response(&ctx.http, |response| {
                         response.kind(InteractionResponseType::DeferredUpdateMessage)
                     }).await?;
                     
-                    match crate::commands::poll::end_poll_logic(database, ctx, poll_id, &poll.channel_id, poll.message_id).await {
-                        Ok(_) => {
+                    let closed_by = component.user.id.to_string();
+                    match crate::commands::poll::end_poll_logic(
+                        database,
+                        ctx,
+                        poll_id,
+                        &closed_by,
+                        CloseReason::Manual,
+                    ).await {
+                        Ok(true) => {
                             component.edit_original_interaction_response(&ctx.http, |response| {
                                 response.content(format!("Poll '{}' ended successfully.", poll.question)).components(|c| c)
                             }).await?;
                         }
+                        Ok(false) => {
+                            component.edit_original_interaction_response(&ctx.http, |response| {
+                                response.content(format!("Poll '{}' had already ended.", poll.question)).components(|c| c)
+                            }).await?;
+                        }
                         Err(e) => {
                             error!("Error ending poll {} via selection: {}", poll_id, e);
                             component.edit_original_interaction_response(&ctx.http, |response| {
@@ -102,8 +131,7 @@ pub async fn handle_component(
         if let Some(poll_id) = component.data.values.get(0) {
             match database.get_poll(poll_id).await {
                 Ok(poll) => {
-                    let votes = database.get_poll_votes(poll_id).await?;
-                    let results = crate::commands::poll::calculate_poll_results(&poll, &votes);
+                    let results = crate::commands::poll::get_poll_results(database, &poll).await?;
                     
                     component.create_interaction_response(&ctx.http, |response| {
                         response.kind(InteractionResponseType::UpdateMessage)
@@ -111,15 +139,7 @@ pub async fn handle_component(
                                 message
                                     .content("") // Clear the "Select a poll..." text
                                     .embed(|e| crate::commands::poll::create_results_embed(e, &poll, &results))
-                                    .components(|c| {
-                                        c.create_action_row(|row| {
-                                            row.create_button(|btn| {
-                                                btn.custom_id(format!("shareResults_{}", poll.id))
-                                                   .label("Share Results")
-                                                   .style(ButtonStyle::Primary)
-                                            })
-                                        })
-                                    })
+                                    .components(|c| crate::commands::results::create_results_components(c, &poll, &results, true))
                             })
                     }).await?;
                 }
@@ -157,7 +177,8 @@ pub async fn handle_component(
     // Fetch the poll from database
     let poll: Option<Poll> = if let Some(ref poll_id) = poll_id_opt {
         match database.get_poll(poll_id).await {
-            Ok(p) => Some(p),
+            // Withdrawn options never show up in the voting UI
+            Ok(p) => Some(p.with_votable_options()),
             Err(e) => {
                 error!(
                     "Failed to fetch poll ID '{}' for component interaction '{}': {}",
@@ -191,13 +212,22 @@ pub async fn handle_component(
         return Ok(());
     };
 
-    // If poll is found but inactive, disallow all interactions except sharing results/votes
+    // Only open polls take votes; sharing or reading results works in any state
     if let Some(ref p) = poll {
-        if !p.is_active && !custom_id.starts_with("shareResults_") && !custom_id.starts_with("shareVote_") {
+        let reads_results = ["shareResults_", "shareVote_", "showBreakdown_", "breakdownPage_"]
+            .iter()
+            .any(|prefix| custom_id.starts_with(prefix));
+        if !p.status.accepts_votes() && !reads_results {
+            let msg = match (p.status, p.starts_at) {
+                (PollStatus::Scheduled, Some(time)) => format!("Voting on this poll opens <t:{}:R>.", time.timestamp()),
+                (PollStatus::Draft | PollStatus::Scheduled, _) => "Voting on this poll hasn't opened yet.".to_string(),
+                (PollStatus::Paused, _) => "Voting on this poll is paused by the moderators. Try again later.".to_string(),
+                _ => "This poll has ended.".to_string(),
+            };
             component.create_interaction_response(&ctx.http, |response| {
                 response
                     .kind(InteractionResponseType::ChannelMessageWithSource)
-                    .interaction_response_data(|message| message.content("This poll has ended.").ephemeral(true))
+                    .interaction_response_data(|message| message.content(msg).ephemeral(true))
             }).await?;
             return Ok(());
         }
@@ -224,7 +254,8 @@ pub async fn handle_component(
     }
 
     // Route to the appropriate handler based on the custom_id
-    if custom_id == "vote_button" || custom_id == "voteButton" {
+    // `openVote_` buttons on `/poll myvotes` open the same voting UI as the poll message's button
+    if custom_id == "vote_button" || custom_id == "voteButton" || custom_id.starts_with("openVote_") {
         if let Some(p) = poll {
             vote::handle_vote_button(database, ctx, component, &p).await?;
         }
@@ -325,13 +356,13 @@ pub async fn handle_component(
         }).await?;
     } else if custom_id.starts_with("shareResults_") {
         if let Some(p) = poll {
-            let votes = database.get_poll_votes(&p.id).await?;
-            let results = crate::commands::poll::calculate_poll_results(&p, &votes);
+            let results = crate::commands::poll::get_poll_results(database, &p).await?;
             
             // Send public message
             component.channel_id.send_message(&ctx.http, |m| {
                 m.content(format!("Results for poll '{}' shared by <@{}>:", p.question, component.user.id))
                  .embed(|e| crate::commands::poll::create_results_embed(e, &p, &results))
+                 .components(|c| crate::commands::results::create_results_components(c, &p, &results, false))
             }).await?;
 
             // Update ephemeral message to disable button
@@ -351,6 +382,10 @@ pub async fn handle_component(
                     })
             }).await?;
         }
+    } else if custom_id.starts_with("showBreakdown_") || custom_id.starts_with("breakdownPage_") {
+        if let Some(p) = poll {
+            handle_breakdown_page(database, ctx, component, &p).await?;
+        }
     } else if custom_id.starts_with("shareVote_") {
         if let Some(p) = poll {
             let user_votes = database.get_user_poll_votes(&p.id, &component.user.id.to_string()).await?;
@@ -399,6 +434,48 @@ pub async fn handle_component(
     Ok(())
 }
 
+// "Show full breakdown" opens the first page privately; the page buttons flip through it in place
+async fn handle_breakdown_page(
+    database: &Database,
+    ctx: &Context,
+    component: &MessageComponentInteraction,
+    poll: &Poll,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let user_id = component.user.id.to_string();
+    if poll.status.is_active() && !crate::commands::poll::can_see_results_so_far(database, poll, &user_id).await? {
+        component.create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| {
+                    message.content("Results so far aren't available to you yet.").ephemeral(true)
+                })
+        }).await?;
+        return Ok(());
+    }
+
+    let results = crate::commands::poll::get_poll_results(database, poll).await?;
+    let pages = crate::commands::results::page_count(&results);
+    let custom_id = &component.data.custom_id;
+    let (kind, page) = if custom_id.starts_with("showBreakdown_") {
+        (InteractionResponseType::ChannelMessageWithSource, 0)
+    } else {
+        let page = custom_id.split('_').next_back().and_then(|s| s.parse::<usize>().ok()).unwrap_or(0);
+        (InteractionResponseType::UpdateMessage, page)
+    };
+    // Results so far can shrink between clicks
+    let page = page.min(pages - 1);
+
+    component.create_interaction_response(&ctx.http, |response| {
+        response.kind(kind).interaction_response_data(|message| {
+            message
+                .ephemeral(true)
+                .embed(|e| crate::commands::results::create_breakdown_embed(e, poll, &results, page))
+                .components(|c| crate::commands::results::create_breakdown_components(c, poll, page, pages))
+        })
+    }).await?;
+    Ok(())
+}
+
 pub async fn handle_interaction(
     database: &Database,
     ctx: &Context,
@@ -411,6 +488,9 @@ pub async fn handle_interaction(
         Interaction::MessageComponent(component) => {
             handle_component(database, ctx, &component).await
         }
+        Interaction::Autocomplete(autocomplete) => {
+            crate::commands::autocomplete::handle_autocomplete(database, ctx, &autocomplete).await
+        }
         _ => {
             warn!("Unhandled interaction type: {:?}", interaction.kind());
             Ok(())
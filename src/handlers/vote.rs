@@ -1,3 +1,4 @@
+use crate::commands::poll::truncate;
 use crate::db::Database;
 use serenity::model::application::interaction::{
     message_component::MessageComponentInteraction, InteractionResponseType,
@@ -6,7 +7,8 @@ use serenity::model::application::component::{ActionRowComponent, ButtonStyle};
 use serenity::prelude::*;
 use chrono::Utc;
 use log::{info, warn};
-use crate::models::Poll;
+use crate::models::{Poll, ResultsVisibility};
+use crate::tasks::live_results;
 
 pub async fn handle_vote_button(
     database: &Database,
@@ -67,16 +69,8 @@ pub async fn handle_vote_button(
                                 .components(|c| {
                                     for option in options_to_show {
                                         let rating = option_ratings.get(&option.id).copied().unwrap_or(0);
-                                        let truncated_name = if option.text.len() > 80 {
-                                            format!("{}...", &option.text[..77])
-                                        } else {
-                                            option.text.clone()
-                                        };
-                                        let short_name = if option.text.len() > 20 {
-                                            format!("{}...", &option.text[..17])
-                                        } else {
-                                            option.text.clone()
-                                        };
+                                        let truncated_name = truncate(&option.text, 80);
+                                        let short_name = truncate(&option.text, 20);
                                         
                                         c.create_action_row(|row| {
                                             row.create_select_menu(|menu| {
@@ -361,7 +355,8 @@ pub async fn handle_star_vote(
     };
 
     database.save_vote(&vote).await?;
-    let poll = database.get_poll(poll_id).await?;
+    let poll = database.get_poll(poll_id).await?.with_votable_options();
+    live_results::vote_changed(ctx, &poll).await;
 
     // Find which page this option is on
     let options_per_page = 4;
@@ -394,11 +389,7 @@ pub async fn handle_star_vote(
                        .components(|c| {
                             for option in options_to_show {
                                 let rating = option_ratings.get(&option.id).copied().unwrap_or(0);
-                                let truncated_name = if option.text.len() > 30 {
-                                    format!("{}...", &option.text[..27])
-                                } else {
-                                    option.text.clone()
-                                };
+                                let truncated_name = truncate(&option.text, 30);
                                 c.create_action_row(|row| {
                                     row.create_select_menu(|menu| {
                                         menu
@@ -501,6 +492,7 @@ pub async fn handle_plurality_vote(
         };
         database.save_vote(&vote).await?;
     }
+    live_results::vote_changed(ctx, poll).await;
 
     let existing_votes = database.get_user_poll_votes(poll_id, &user_id).await?;
     let mut option_ratings = std::collections::HashMap::<String, i32>::new();
@@ -581,6 +573,7 @@ pub async fn handle_approval_vote_toggle(
         timestamp: Utc::now(),
     };
     database.save_vote(&vote).await?;
+    live_results::vote_changed(ctx, poll).await;
 
     component
         .create_interaction_response(&ctx.http, |response| {
@@ -706,8 +699,17 @@ pub async fn handle_done_voting(
     let user_votes = database.get_user_poll_votes(poll_id, &user_id).await?;
 
     let vote_details = format_user_vote(poll, &user_votes);
-    let vote_summary = format!("**{}**\n{} Voting\n\nYour vote has been recorded:\n{}", 
+    let mut vote_summary = format!("**{}**\n{} Voting\n\nYour vote has been recorded:\n{}", 
         poll.question, poll.voting_method, vote_details);
+
+    // Polls that show results to voters include the standings with the confirmation
+    if poll.results_visibility != ResultsVisibility::Hidden {
+        let standings = crate::commands::poll::results_so_far(database, poll).await?;
+        let with_standings = format!("{}\n\n**Results so far:**\n{}", vote_summary, standings);
+        if with_standings.chars().count() <= 2000 {
+            vote_summary = with_standings;
+        }
+    }
     
     component
         .create_interaction_response(&ctx.http, |response| {
@@ -761,10 +763,15 @@ pub async fn handle_rank_action(
     let existing_votes = database.get_user_poll_votes(&poll.id, &user_id).await?;
     let mut rankings = std::collections::HashMap::new();
 
-    for vote in &existing_votes {
-        if vote.rating > 0 {
-            rankings.insert(vote.option_id.clone(), vote.rating);
-        }
+    // `poll` only holds votable options, so rankings of withdrawn ones are dropped here and
+    // the remaining ranks closed up to 1..n
+    let mut ranked: Vec<_> = existing_votes
+        .iter()
+        .filter(|vote| vote.rating > 0 && poll.options.iter().any(|o| o.id == vote.option_id))
+        .collect();
+    ranked.sort_by_key(|vote| vote.rating);
+    for (i, vote) in ranked.into_iter().enumerate() {
+        rankings.insert(vote.option_id.clone(), i as i32 + 1);
     }
 
     let current_rank = rankings.get(option_id).cloned().unwrap_or(0);
@@ -837,6 +844,7 @@ pub async fn handle_rank_action(
         };
         database.save_vote(&v).await?;
     }
+    live_results::vote_changed(ctx, poll).await;
 
     handle_vote_button(database, ctx, component, poll).await
 }
@@ -1,17 +1,26 @@
+mod charts;
+mod cli;
 mod commands;
 mod db;
+mod export;
 mod handlers;
-mod models;
-mod voting;
-mod tasks; 
+mod permissions;
+mod tasks;
+
+use trusty_vote_core::{import, models, voting};
 
 use db::Database;
+use tasks::leader::Leadership;
+use tasks::live_results::{LiveResults, LiveResultsKey};
+use tasks::outbox::{OutboxWorker, OutboxWorkerKey};
+use tasks::scheduler::{PollScheduler, PollSchedulerKey};
 use serenity::async_trait;
 use serenity::model::application::command::Command;
 use serenity::model::application::interaction::Interaction;
 use serenity::model::gateway::Ready;
 use serenity::prelude::*;
 use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use log::{info, error}; 
 
@@ -22,6 +31,9 @@ use log::{info, error};
 
 struct Bot {
     database: Arc<Database>,
+    leadership: Arc<Leadership>,
+    reconciled: AtomicBool, // Startup reconciliation runs once, not on every reconnect
+    tasks_started: AtomicBool, // Likewise the periodic task loops, which never exit
 }
 
 #[async_trait]
@@ -42,7 +54,13 @@ impl EventHandler for Bot {
 
         // Register slash commands globally or for specific guilds
         let commands = Command::set_global_application_commands(&ctx.http, |commands_builder| {
-            commands_builder.create_application_command(|command| commands::poll::create_poll_command(command))
+            commands_builder
+                .create_application_command(|command| commands::poll::create_poll_command(command))
+                .create_application_command(|command| commands::config::create_config_command(command))
+                .create_application_command(|command| commands::retention::create_retention_command(command))
+                .create_application_command(|command| commands::privacy::create_privacy_command(command))
+                .create_application_command(|command| commands::template::create_template_command(command))
+                .create_application_command(|command| commands::deliveries::create_deliveries_command(command))
             // Add other commands here
         })
         .await;
@@ -53,13 +71,58 @@ impl EventHandler for Bot {
             info!("Successfully registered global slash commands.");
         }
 
-        // --- Start Background Task for Ending Polls ---
-        let db_clone = Arc::clone(&self.database);
-        let ctx_clone = ctx.clone();
-        tokio::spawn(async move {
-            tasks::poll_ender::check_expired_polls_task(db_clone, ctx_clone).await;
-        });
-        // --- End Background Task ---
+        // --- Start Background Tasks ---
+        if !self.reconciled.swap(true, Ordering::SeqCst) {
+            let db_clone = Arc::clone(&self.database);
+            let ctx_clone = ctx.clone();
+            let leadership = Arc::clone(&self.leadership);
+            tokio::spawn(async move {
+                if leadership.is_leader().await {
+                    tasks::reconcile::reconcile_polls_on_startup(&db_clone, &ctx_clone).await;
+                }
+            });
+        }
+
+        let scheduler = ctx.data.read().await.get::<PollSchedulerKey>().cloned();
+        if let Some(scheduler) = scheduler {
+            let db_clone = Arc::clone(&self.database);
+            let ctx_clone = ctx.clone();
+            tokio::spawn(async move {
+                scheduler.run(db_clone, ctx_clone).await;
+            });
+        }
+
+        let outbox_worker = ctx.data.read().await.get::<OutboxWorkerKey>().cloned();
+        if let Some(outbox_worker) = outbox_worker {
+            let db_clone = Arc::clone(&self.database);
+            let ctx_clone = ctx.clone();
+            tokio::spawn(async move {
+                outbox_worker.run(db_clone, ctx_clone).await;
+            });
+        }
+
+        if !self.tasks_started.swap(true, Ordering::SeqCst) {
+            let db_clone = Arc::clone(&self.database);
+            let ctx_clone = ctx.clone();
+            let leadership = Arc::clone(&self.leadership);
+            tokio::spawn(async move {
+                tasks::recurring::check_recurring_polls_task(db_clone, ctx_clone, leadership).await;
+            });
+
+            let db_clone = Arc::clone(&self.database);
+            let ctx_clone = ctx.clone();
+            let leadership = Arc::clone(&self.leadership);
+            tokio::spawn(async move {
+                tasks::reminders::check_poll_reminders_task(db_clone, ctx_clone, leadership).await;
+            });
+
+            let db_clone = Arc::clone(&self.database);
+            let leadership = Arc::clone(&self.leadership);
+            tokio::spawn(async move {
+                tasks::retention::run_retention_task(db_clone, leadership).await;
+            });
+        }
+        // --- End Background Tasks ---
     }
 }
 
@@ -69,6 +132,16 @@ async fn main() {
     dotenvy::dotenv().ok();
     env_logger::init();
 
+    // `trusty-vote import <file> ...` imports a ballot file and exits instead of starting the bot
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(String::as_str) == Some("import") {
+        if let Err(e) = cli::import(&args[2..]).await {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Load token from environment variable
     let token = env::var("DISCORD_TOKEN").expect("Expected a token in the environment");
 
@@ -81,14 +154,21 @@ async fn main() {
         }
     };
 
-    // Define intents
+    let leadership = Arc::new(Leadership::from_env(&database));
+    let live_results = Arc::new(LiveResults::new(Arc::clone(&database)));
+
+    // Define intents. Listing members for DM reminders and turnout only needs the Server Members
+    // toggle in the developer portal, not GUILD_MEMBERS here.
     let intents = GatewayIntents::GUILDS
         | GatewayIntents::GUILD_MESSAGES
         | GatewayIntents::GUILD_INTEGRATIONS; // Add necessary intents
 
     // Build client
     let mut client = Client::builder(&token, intents)
-        .event_handler(Bot { database })
+        .event_handler(Bot { database, leadership, reconciled: AtomicBool::new(false), tasks_started: AtomicBool::new(false) })
+        .type_map_insert::<PollSchedulerKey>(Arc::new(PollScheduler::default()))
+        .type_map_insert::<OutboxWorkerKey>(Arc::new(OutboxWorker::default()))
+        .type_map_insert::<LiveResultsKey>(live_results)
         .await
         .expect("Err creating client");
 
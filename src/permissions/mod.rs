@@ -0,0 +1,81 @@
+use crate::models::{GuildSettings, Poll};
+use serenity::model::guild::Member;
+use serenity::model::permissions::Permissions;
+use serenity::model::user::User;
+
+// Actions on an existing poll that only its creator or a poll admin may take. Every command and
+// component path that performs one checks `can_manage_poll` first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollAction {
+    End,
+    Edit,
+    Export,
+    Pause, // Also covers resuming
+    Reopen,
+    Stats,
+}
+
+impl PollAction {
+    // Message shown to members who aren't allowed to perform the action
+    pub fn refusal_message(&self) -> &'static str {
+        match self {
+            PollAction::End => "Only the poll's creator or a server manager can end this poll.",
+            PollAction::Edit => "Only the poll's creator or a server manager can edit this poll.",
+            PollAction::Export => "Only the poll's creator or a server manager can export this poll's ballots.",
+            PollAction::Pause => "Only the poll's creator or a server manager can pause or resume this poll.",
+            PollAction::Reopen => "Only the poll's creator or a server manager can reopen this poll.",
+            PollAction::Stats => "Only the poll's creator or a server manager can see this poll's voting statistics.",
+        }
+    }
+}
+
+// The member invoking a command or component, as far as authorization is concerned
+#[derive(Debug, Clone)]
+pub struct Caller {
+    pub user_id: String,
+    pub guild_id: Option<String>,
+    pub roles: Vec<String>,
+    pub permissions: Permissions,
+}
+
+impl Caller {
+    // Interaction members carry their resolved channel permissions; outside a guild there are none
+    pub fn new(user: &User, member: Option<&Member>) -> Self {
+        Self {
+            user_id: user.id.to_string(),
+            guild_id: member.map(|m| m.guild_id.to_string()),
+            roles: member.map_or_else(Vec::new, |m| m.roles.iter().map(|r| r.to_string()).collect()),
+            permissions: member.and_then(|m| m.permissions).unwrap_or_else(Permissions::empty),
+        }
+    }
+
+    pub fn has_manage_guild(&self) -> bool {
+        self.permissions.manage_guild() || self.permissions.administrator()
+    }
+
+    pub fn has_role(&self, role_id: &str) -> bool {
+        self.roles.iter().any(|r| r == role_id)
+    }
+}
+
+// Manage Server, or the admin role configured with `/pollconfig`
+pub fn is_poll_admin(caller: &Caller, settings: &GuildSettings) -> bool {
+    caller.has_manage_guild()
+        || settings.admin_role.as_deref().is_some_and(|role| caller.has_role(role))
+}
+
+pub fn can_manage_poll(caller: &Caller, poll: &Poll, settings: &GuildSettings) -> bool {
+    // A poll ID from another server never grants anything here
+    if caller.guild_id.as_deref() != Some(poll.guild_id.as_str()) {
+        return false;
+    }
+    poll.creator_id == caller.user_id || is_poll_admin(caller, settings)
+}
+
+// Creator role from `/pollconfig`; poll admins may always create polls
+pub fn can_create_poll(caller: &Caller, settings: &GuildSettings) -> bool {
+    match settings.creator_role.as_deref() {
+        Some(role) => caller.has_role(role) || is_poll_admin(caller, settings),
+        None => true,
+    }
+}
@@ -0,0 +1,53 @@
+use crate::db::{Database, LeaderLock};
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use log::{info, error};
+
+// Advisory lock key shared by every process of the bot ("trusty" in ASCII)
+const LEADER_LOCK_KEY: i64 = 0x7472_7573_7479;
+
+// With BACKGROUND_JOBS_LEADER_ELECTION=true, only the process holding a Postgres advisory lock
+// runs retention, recurring polls and reminders; the others stand by and take over if it goes
+// away. The poll scheduler runs everywhere regardless, since closing a poll is claimed per poll.
+pub struct Leadership {
+    lock: Option<LeaderLock>,
+    leading: AtomicBool,
+}
+
+impl Leadership {
+    pub fn from_env(database: &Database) -> Self {
+        let enabled = env::var("BACKGROUND_JOBS_LEADER_ELECTION")
+            .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        if enabled {
+            info!("Leader election enabled for background jobs");
+        }
+        Self {
+            lock: enabled.then(|| database.leader_lock(LEADER_LOCK_KEY)),
+            leading: AtomicBool::new(false),
+        }
+    }
+
+    // Whether background jobs should run in this process right now
+    pub async fn is_leader(&self) -> bool {
+        let lock = match &self.lock {
+            Some(lock) => lock,
+            None => return true,
+        };
+        let leading = match lock.is_leader().await {
+            Ok(leading) => leading,
+            Err(e) => {
+                error!("Leader election check failed, standing by: {}", e);
+                false
+            }
+        };
+        if self.leading.swap(leading, Ordering::SeqCst) != leading {
+            if leading {
+                info!("This process is now the leader for background jobs");
+            } else {
+                info!("This process is no longer the leader for background jobs");
+            }
+        }
+        leading
+    }
+}
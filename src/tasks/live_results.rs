@@ -0,0 +1,114 @@
+use crate::commands::poll::{is_missing_message_error, refresh_poll_message};
+use crate::db::Database;
+use crate::models::{Poll, ResultsVisibility};
+use serenity::prelude::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration as StdDuration;
+use log::{debug, warn, error};
+use tokio::time::Instant;
+
+// Votes arriving this soon after one another are rendered together
+const DEBOUNCE_MILLIS: u64 = 2000;
+// At most one edit per poll in this window, well inside Discord's message edit rate limits
+const MIN_EDIT_INTERVAL_SECONDS: u64 = 10;
+
+#[derive(Default)]
+struct RenderState {
+    last_render: Option<Instant>,
+    pending: bool, // A render is already scheduled and will pick up any newer votes
+}
+
+// Re-renders the running tally on live-results poll messages after votes change. Each process
+// debounces the votes it receives; a render reads the current tally, so a late one is never stale.
+pub struct LiveResults {
+    database: Arc<Database>,
+    polls: Mutex<HashMap<String, RenderState>>,
+}
+
+pub struct LiveResultsKey;
+
+impl TypeMapKey for LiveResultsKey {
+    type Value = Arc<LiveResults>;
+}
+
+// Call after a vote on the poll was saved; a no-op unless it shows live results
+pub async fn vote_changed(ctx: &Context, poll: &Poll) {
+    if poll.results_visibility != ResultsVisibility::Live {
+        return;
+    }
+    let live_results = ctx.data.read().await.get::<LiveResultsKey>().cloned();
+    if let Some(live_results) = live_results {
+        live_results.schedule(ctx, &poll.id);
+    }
+}
+
+impl LiveResults {
+    pub fn new(database: Arc<Database>) -> Self {
+        Self {
+            database,
+            polls: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn schedule(self: Arc<Self>, ctx: &Context, poll_id: &str) {
+        let now = Instant::now();
+        let min_interval = StdDuration::from_secs(MIN_EDIT_INTERVAL_SECONDS);
+        let render_at = {
+            let mut polls = self.polls.lock().expect("live results lock poisoned");
+            // Forget polls that haven't been rendered recently
+            polls.retain(|_, state| state.pending || state.last_render.is_some_and(|at| now - at < min_interval));
+
+            let state = polls.entry(poll_id.to_string()).or_default();
+            if state.pending {
+                return;
+            }
+            state.pending = true;
+            let debounced = now + StdDuration::from_millis(DEBOUNCE_MILLIS);
+            match state.last_render {
+                Some(last_render) => debounced.max(last_render + min_interval),
+                None => debounced,
+            }
+        };
+
+        let ctx = ctx.clone();
+        let poll_id = poll_id.to_string();
+        tokio::spawn(async move {
+            tokio::time::sleep_until(render_at).await;
+            self.render(&ctx, &poll_id).await;
+        });
+    }
+
+    async fn render(&self, ctx: &Context, poll_id: &str) {
+        // Votes from here on schedule the next render
+        {
+            let mut polls = self.polls.lock().expect("live results lock poisoned");
+            let state = polls.entry(poll_id.to_string()).or_default();
+            state.pending = false;
+            state.last_render = Some(Instant::now());
+        }
+
+        let poll = match self.database.get_poll(poll_id).await {
+            Ok(poll) => poll,
+            Err(e) => {
+                error!("Failed to load poll {} for its live results: {}", poll_id, e);
+                return;
+            }
+        };
+        if !poll.shows_live_results() || poll.message_id.is_none() {
+            return;
+        }
+
+        // Best effort: the next vote, and closing the poll, render the tally again
+        match refresh_poll_message(&self.database, ctx, &poll).await {
+            Ok(()) => debug!("Updated live results for poll {}", poll.id),
+            Err(e) if is_missing_message_error(e.as_ref()) => {
+                warn!("Message for poll {} no longer exists, forgetting it", poll.id);
+                if let Err(e) = self.database.clear_poll_message_id(&poll.id).await {
+                    error!("Failed to clear message ID for poll {}: {}", poll.id, e);
+                }
+            }
+            Err(e) => warn!("Failed to update live results for poll {}: {}", poll.id, e),
+        }
+    }
+}
@@ -1 +1,8 @@
-pub mod poll_ender;
+pub mod leader;
+pub mod live_results;
+pub mod outbox;
+pub mod reconcile;
+pub mod recurring;
+pub mod reminders;
+pub mod retention;
+pub mod scheduler;
@@ -0,0 +1,127 @@
+use crate::commands::poll::{is_missing_message_error, post_poll_results, refresh_poll_message};
+use crate::db::{Database, OutboxEntry};
+use crate::models::OutboxAction;
+use crate::tasks::reminders::send_reminder;
+use serenity::prelude::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use chrono::{Duration, Utc};
+use log::{info, warn, error};
+use tokio::sync::Notify;
+
+// Also look for due retries this often, and for actions queued by another process
+const POLL_INTERVAL_SECONDS: u64 = 15;
+const BATCH_SIZE: i64 = 20;
+// An action claimed by a process that died is retried once its lease runs out
+const LEASE_SECONDS: i64 = 300;
+// Backoff doubles from the base up to the cap; after MAX_ATTEMPTS (about an hour in) the
+// action is dead-lettered until an admin replays it
+pub const MAX_ATTEMPTS: i32 = 8;
+const BASE_BACKOFF_SECONDS: i64 = 30;
+const MAX_BACKOFF_SECONDS: i64 = 3600;
+
+// Delivers the Discord side effects queued in the outbox. Safe to run in every process:
+// actions are leased to one process at a time.
+#[derive(Default)]
+pub struct OutboxWorker {
+    wake: Notify,
+    running: AtomicBool,
+}
+
+pub struct OutboxWorkerKey;
+
+impl TypeMapKey for OutboxWorkerKey {
+    type Value = Arc<OutboxWorker>;
+}
+
+// Deliver newly queued actions now rather than at the next poll; a no-op if the worker isn't installed
+pub async fn wake(ctx: &Context) {
+    let worker = ctx.data.read().await.get::<OutboxWorkerKey>().cloned();
+    if let Some(worker) = worker {
+        worker.wake.notify_one();
+    }
+}
+
+impl OutboxWorker {
+    // Calling it again (Discord can send `ready` more than once) does nothing
+    pub async fn run(self: Arc<Self>, database: Arc<Database>, ctx: Context) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        info!("Starting outbox worker");
+
+        loop {
+            let entries = match database.claim_outbox_actions(BATCH_SIZE, LEASE_SECONDS).await {
+                Ok(entries) => entries,
+                Err(e) => {
+                    error!("Failed to claim outbox actions: {}", e);
+                    Vec::new()
+                }
+            };
+            let batch_was_full = entries.len() as i64 == BATCH_SIZE;
+
+            for entry in entries {
+                process(&database, &ctx, &entry).await;
+            }
+
+            if batch_was_full {
+                continue;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(StdDuration::from_secs(POLL_INTERVAL_SECONDS)) => {}
+                _ = self.wake.notified() => {}
+            }
+        }
+    }
+}
+
+async fn process(database: &Database, ctx: &Context, entry: &OutboxEntry) {
+    let result = match entry.action {
+        OutboxAction::EditPollMessage => match database.get_poll(&entry.poll_id).await {
+            Ok(poll) => match refresh_poll_message(database, ctx, &poll).await {
+                // Retrying can't bring a deleted message back
+                Err(e) if is_missing_message_error(e.as_ref()) => {
+                    warn!("Message for poll {} no longer exists, forgetting it", poll.id);
+                    database.clear_poll_message_id(&poll.id).await
+                }
+                result => result,
+            },
+            Err(e) => Err(e),
+        },
+        OutboxAction::PostResults => post_poll_results(database, ctx, &entry.poll_id).await,
+        OutboxAction::SendReminder(notify) => send_reminder(database, ctx, &entry.poll_id, notify).await,
+    };
+
+    let outcome = match result {
+        Ok(()) => {
+            info!("Delivered outbox action {} ({}) for poll {}", entry.id, entry.action.kind(), entry.poll_id);
+            database.complete_outbox_action(entry.id).await
+        }
+        Err(e) if entry.attempts >= MAX_ATTEMPTS => {
+            error!(
+                "Outbox action {} ({}) for poll {} failed {} times, dead-lettering it: {}",
+                entry.id, entry.action.kind(), entry.poll_id, entry.attempts, e
+            );
+            database.fail_outbox_action(entry.id, &e.to_string(), None).await
+        }
+        Err(e) => {
+            let retry_at = Utc::now() + backoff(entry.attempts);
+            warn!(
+                "Outbox action {} ({}) for poll {} failed (attempt {}), retrying at {}: {}",
+                entry.id, entry.action.kind(), entry.poll_id, entry.attempts, retry_at, e
+            );
+            database.fail_outbox_action(entry.id, &e.to_string(), Some(retry_at)).await
+        }
+    };
+    // The lease runs out eventually, so the action is retried even if this fails
+    if let Err(e) = outcome {
+        error!("Failed to record the outcome of outbox action {}: {}", entry.id, e);
+    }
+}
+
+// Delay before the next try after `attempts` failed ones
+fn backoff(attempts: i32) -> Duration {
+    let exponent = attempts.clamp(1, 16) as u32 - 1;
+    Duration::seconds(BASE_BACKOFF_SECONDS.saturating_mul(1 << exponent).min(MAX_BACKOFF_SECONDS))
+}
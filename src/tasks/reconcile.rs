@@ -0,0 +1,76 @@
+use crate::commands::poll::{end_poll_logic, open_poll_logic};
+use crate::db::Database;
+use crate::models::{CloseReason, OutboxAction, PollStatus, SCHEDULER_ACTOR};
+use crate::tasks::outbox;
+use serenity::prelude::*;
+use chrono::{Duration, Utc};
+use log::{info, error};
+
+// Drafts are published within seconds of creation; older ones were never posted
+const STALE_DRAFT_MINUTES: i64 = 10;
+
+// One pass after startup to repair what happened while the bot was offline: close polls that
+// are past their end time (their results say they closed late), open scheduled polls that are
+// due, and queue a re-render of every other active poll message from the stored state. The
+// outbox forgets messages that were deleted. Drafts that never got published are removed.
+pub async fn reconcile_polls_on_startup(database: &Database, ctx: &Context) {
+    match database.delete_stale_drafts(Utc::now() - Duration::minutes(STALE_DRAFT_MINUTES)).await {
+        Ok(0) => {}
+        Ok(n) => info!("Startup reconciliation: deleted {} polls that were never published", n),
+        Err(e) => error!("Startup reconciliation: failed to delete unpublished drafts: {}", e),
+    }
+
+    let polls = match database.get_active_poll_times().await {
+        Ok(polls) => polls,
+        Err(e) => {
+            error!("Startup reconciliation: failed to load open polls: {}", e);
+            return;
+        }
+    };
+    info!("Startup reconciliation: checking {} open or pending polls", polls.len());
+
+    let now = Utc::now();
+    let (mut closed, mut opened, mut queued) = (0, 0, 0);
+    for (poll_id, status, starts_at, ends_at) in polls {
+        let is_scheduled = status == PollStatus::Scheduled;
+        if !is_scheduled && ends_at.is_some_and(|ends_at| ends_at <= now) {
+            match end_poll_logic(database, ctx, &poll_id, SCHEDULER_ACTOR, CloseReason::Expired).await {
+                Ok(true) => closed += 1,
+                Ok(false) => {} // Closed by the scheduler or another process in the meantime
+                Err(e) => error!("Startup reconciliation: failed to close overdue poll {}: {}", poll_id, e),
+            }
+            continue;
+        }
+        if is_scheduled && starts_at.is_none_or(|starts_at| starts_at <= now) {
+            // The scheduler closes it right away if its end time has passed too
+            match open_poll_logic(database, ctx, &poll_id).await {
+                Ok(()) => opened += 1,
+                Err(e) => error!("Startup reconciliation: failed to open scheduled poll {}: {}", poll_id, e),
+            }
+            continue;
+        }
+
+        let poll = match database.get_poll(&poll_id).await {
+            Ok(poll) => poll,
+            Err(e) => {
+                error!("Startup reconciliation: failed to load poll {}: {}", poll_id, e);
+                continue;
+            }
+        };
+        if poll.message_id.is_none() {
+            continue;
+        }
+        // Queued rather than edited here: the outbox delivers the edits one at a time with
+        // backoff, instead of a burst that runs into Discord's rate limits on large deployments
+        match database.enqueue_outbox_action(&poll_id, OutboxAction::EditPollMessage).await {
+            Ok(()) => queued += 1,
+            Err(e) => error!("Startup reconciliation: failed to queue a re-render of poll {}: {}", poll_id, e),
+        }
+    }
+    outbox::wake(ctx).await;
+
+    info!(
+        "Startup reconciliation: closed {} overdue polls, opened {} scheduled polls, queued {} poll messages for re-rendering",
+        closed, opened, queued
+    );
+}
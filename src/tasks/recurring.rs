@@ -0,0 +1,61 @@
+use crate::commands::poll::publish_poll;
+use crate::db::Database;
+use crate::tasks::leader::Leadership;
+use serenity::prelude::*;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use chrono::Utc;
+use log::{info, error};
+use tokio::time::interval;
+
+const CHECK_INTERVAL_SECONDS: u64 = 60;
+
+// Posts a new poll from every scheduled template whose time has come. If the bot was offline
+// through several occurrences only one poll is posted and the schedule moves to the next future one.
+pub async fn check_recurring_polls_task(database: Arc<Database>, ctx: Context, leadership: Arc<Leadership>) {
+    info!("Starting background task for recurring polls...");
+    let mut interval = interval(StdDuration::from_secs(CHECK_INTERVAL_SECONDS));
+
+    loop {
+        interval.tick().await;
+        if !leadership.is_leader().await {
+            continue;
+        }
+        let now = Utc::now();
+
+        let due_templates = match database.get_due_templates(now).await {
+            Ok(templates) => templates,
+            Err(e) => {
+                error!("Failed to query for due poll templates: {}", e);
+                continue;
+            }
+        };
+
+        for template in due_templates {
+            let (Some(recurrence), Some(scheduled_for)) = (&template.recurrence, template.next_run_at) else {
+                continue;
+            };
+            let next_run_at = recurrence.next_after(now);
+            match database.advance_template_schedule(&template.id, scheduled_for, next_run_at).await {
+                Ok(true) => {}
+                Ok(false) => continue, // Already handled
+                Err(e) => {
+                    error!("Failed to advance schedule for template {}: {}", template.id, e);
+                    continue;
+                }
+            }
+
+            let mut poll = template.instantiate();
+            match publish_poll(&database, &ctx, &mut poll).await {
+                Ok(()) => info!(
+                    "Posted recurring poll {} from template '{}' in guild {}",
+                    poll.id, template.name, template.guild_id
+                ),
+                Err(e) => error!(
+                    "Failed to post recurring poll from template '{}' in guild {}: {}",
+                    template.name, template.guild_id, e
+                ),
+            }
+        }
+    }
+}
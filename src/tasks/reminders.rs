@@ -0,0 +1,174 @@
+use crate::db::{Database, DueReminder};
+use crate::models::{Poll, ReminderNotify};
+use crate::tasks::leader::Leadership;
+use crate::tasks::outbox;
+use serenity::model::id::{ChannelId, GuildId, UserId};
+use serenity::model::user::User;
+use serenity::prelude::*;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use chrono::Utc;
+use log::{info, warn, error};
+use tokio::time::interval;
+
+const CHECK_INTERVAL_SECONDS: u64 = 60;
+// Upper bound on reminder DMs per poll and reminder, to stay well clear of Discord's rate limits
+const MAX_REMINDER_DMS: usize = 100;
+
+pub async fn check_poll_reminders_task(database: Arc<Database>, ctx: Context, leadership: Arc<Leadership>) {
+    info!("Starting background task for poll reminders...");
+    let mut interval = interval(StdDuration::from_secs(CHECK_INTERVAL_SECONDS));
+
+    loop {
+        interval.tick().await;
+        if !leadership.is_leader().await {
+            continue;
+        }
+
+        let due = match database.get_due_reminders(Utc::now()).await {
+            Ok(due) => due,
+            Err(e) => {
+                error!("Failed to query for due poll reminders: {}", e);
+                continue;
+            }
+        };
+
+        // Reminders missed while the bot was offline are folded into one per poll
+        let mut by_poll: Vec<(String, Vec<DueReminder>)> = Vec::new();
+        for reminder in due {
+            match by_poll.last_mut() {
+                Some((poll_id, reminders)) if *poll_id == reminder.poll_id => reminders.push(reminder),
+                _ => by_poll.push((reminder.poll_id.clone(), vec![reminder])),
+            }
+        }
+
+        let mut queued = false;
+        for (poll_id, reminders) in by_poll {
+            // The broadest audience asked for by any of the folded reminders
+            let notify = if reminders.iter().any(|r| r.notify == ReminderNotify::Dm) {
+                ReminderNotify::Dm
+            } else if reminders.iter().any(|r| r.notify == ReminderNotify::Role) {
+                ReminderNotify::Role
+            } else {
+                ReminderNotify::Channel
+            };
+
+            // Claiming queues the reminder in the outbox, which posts (and retries) it
+            let offsets: Vec<i64> = reminders.iter().map(|r| r.offset_minutes).collect();
+            match database.claim_reminders(&poll_id, &offsets, notify).await {
+                Ok(claimed) => queued |= claimed,
+                Err(e) => error!("Failed to claim reminders for poll {}: {}", poll_id, e),
+            }
+        }
+        if queued {
+            outbox::wake(&ctx).await;
+        }
+    }
+}
+
+// Post a reminder for a poll; used by the outbox worker
+pub async fn send_reminder(
+    database: &Database,
+    ctx: &Context,
+    poll_id: &str,
+    notify: ReminderNotify,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let poll = database.get_poll(poll_id).await?;
+    let ends_at = match poll.ends_at {
+        Some(ends_at) if poll.status.accepts_votes() => ends_at,
+        _ => return Ok(()), // Poll paused or closed, or its end time was removed, before delivery
+    };
+
+    let jump_link = poll.message_id.as_ref().map_or(String::new(), |message_id| {
+        format!(
+            "\n[Jump to the poll](https://discord.com/channels/{}/{}/{})",
+            poll.guild_id, poll.channel_id, message_id
+        )
+    });
+    let reminder = format!(
+        "⏰ Reminder: voting on **{}** closes <t:{}:R>.{}",
+        poll.question,
+        ends_at.timestamp(),
+        jump_link
+    );
+
+    // DMs need the member list. Without the Server Members intent, mention the voter role instead.
+    let mut recipients = Vec::new();
+    let mut mention_role = notify == ReminderNotify::Role;
+    if notify == ReminderNotify::Dm {
+        match members_who_have_not_voted(database, ctx, &poll).await {
+            Ok(members) => recipients = members,
+            Err(e) => {
+                warn!(
+                    "Could not list members to DM reminders for poll {}: {}. Enable the Server Members intent in the Discord developer portal; mentioning the voter role instead.",
+                    poll.id, e
+                );
+                mention_role = true;
+            }
+        }
+    }
+    let role_mention = match poll.allowed_roles.as_ref().and_then(|roles| roles.first()) {
+        Some(role_id) if mention_role => format!("<@&{}> ", role_id),
+        _ => String::new(),
+    };
+
+    let channel_id = poll.channel_id.parse::<ChannelId>()?;
+    channel_id
+        .send_message(&ctx.http, |m| m.content(format!("{}{}", role_mention, reminder)))
+        .await?;
+    info!("Posted reminder for poll {}", poll.id);
+
+    // Best effort: failing here would repost the channel reminder on retry
+    if !recipients.is_empty() {
+        let mut sent = 0;
+        for user in &recipients {
+            match user.direct_message(ctx, |m| m.content(&reminder)).await {
+                Ok(_) => sent += 1,
+                // Members with DMs closed are expected; keep going
+                Err(e) => warn!("Could not DM reminder for poll {} to {}: {}", poll.id, user.id, e),
+            }
+        }
+        info!("Sent {} of {} reminder DMs for poll {}", sent, recipients.len(), poll.id);
+    }
+    Ok(())
+}
+
+// Eligible members who haven't voted yet, up to MAX_REMINDER_DMS. Listing members needs the
+// Server Members privileged intent enabled for the bot.
+async fn members_who_have_not_voted(
+    database: &Database,
+    ctx: &Context,
+    poll: &Poll,
+) -> Result<Vec<User>, Box<dyn std::error::Error + Send + Sync>> {
+    let voters: HashSet<String> = database.get_poll_voter_ids(&poll.id).await?.into_iter().collect();
+    let guild_id = poll.guild_id.parse::<GuildId>()?;
+    let allowed_role = poll.allowed_roles.as_ref().and_then(|roles| roles.first());
+
+    let mut recipients = Vec::new();
+    let mut after: Option<UserId> = None;
+    loop {
+        let members = guild_id.members(&ctx.http, Some(1000), after).await?;
+        let Some(last) = members.last() else { break };
+        after = Some(last.user.id);
+
+        for member in members.iter() {
+            let eligible = !member.user.bot
+                && allowed_role.is_none_or(|role| member.roles.iter().any(|r| r.to_string() == *role))
+                && !voters.contains(&member.user.id.to_string());
+            if !eligible {
+                continue;
+            }
+            if recipients.len() >= MAX_REMINDER_DMS {
+                warn!("Reached the reminder DM limit for poll {}, not messaging the remaining members", poll.id);
+                return Ok(recipients);
+            }
+            recipients.push(member.user.clone());
+        }
+
+        if members.len() < 1000 {
+            break;
+        }
+    }
+    Ok(recipients)
+}
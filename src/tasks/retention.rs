@@ -0,0 +1,171 @@
+use crate::voting::calculate_poll_results;
+use crate::db::{Database, RetentionReport};
+use crate::models::RetentionPolicy;
+use crate::tasks::leader::Leadership;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration as StdDuration;
+use log::{info, warn, error};
+use tokio::time::interval;
+
+const RETENTION_INTERVAL_SECONDS: u64 = 6 * 60 * 60; // Run every 6 hours
+
+// Global policy, read from RETENTION_ANONYMIZE_AFTER_DAYS, RETENTION_PURGE_BALLOTS_AFTER_DAYS
+// and RETENTION_DELETE_AFTER_DAYS. Unset means data is kept unless a guild configures otherwise.
+pub fn global_policy_from_env() -> RetentionPolicy {
+    fn days(var: &str) -> Option<i32> {
+        let value = env::var(var).ok()?;
+        match value.trim().parse::<i32>() {
+            Ok(days) if days >= 0 => Some(days),
+            _ => {
+                warn!("Ignoring invalid value '{}' for {}", value, var);
+                None
+            }
+        }
+    }
+
+    RetentionPolicy {
+        anonymize_after_days: days("RETENTION_ANONYMIZE_AFTER_DAYS"),
+        purge_ballots_after_days: days("RETENTION_PURGE_BALLOTS_AFTER_DAYS"),
+        delete_after_days: days("RETENTION_DELETE_AFTER_DAYS"),
+    }
+}
+
+// RETENTION_DRY_RUN=true only logs what each pass would remove
+fn dry_run_from_env() -> bool {
+    env::var("RETENTION_DRY_RUN")
+        .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+pub async fn run_retention_task(database: Arc<Database>, leadership: Arc<Leadership>) {
+    let global = global_policy_from_env();
+    let dry_run = dry_run_from_env();
+    info!(
+        "Starting background retention task (global policy: {:?}, dry run: {})",
+        global, dry_run
+    );
+    let mut interval = interval(StdDuration::from_secs(RETENTION_INTERVAL_SECONDS));
+
+    loop {
+        interval.tick().await;
+        if !leadership.is_leader().await {
+            continue;
+        }
+
+        match database.find_retention_candidates(&global, None).await {
+            Ok(report) if report.is_empty() => {}
+            Ok(report) => {
+                if dry_run {
+                    info!("Retention dry run:\n{}", format_retention_log(&report));
+                } else {
+                    apply_retention(&database, &report).await;
+                }
+            }
+            Err(e) => {
+                error!("Failed to query retention candidates: {}", e);
+            }
+        }
+    }
+}
+
+// Carry out a retention report. Failures on one poll are logged and don't stop the rest.
+pub async fn apply_retention(database: &Database, report: &RetentionReport) {
+    for candidate in &report.delete {
+        match database.delete_poll(&candidate.poll_id).await {
+            Ok(_) => info!("Retention: deleted poll {} (guild {})", candidate.poll_id, candidate.guild_id),
+            Err(e) => error!("Retention: failed to delete poll {}: {}", candidate.poll_id, e),
+        }
+    }
+
+    for candidate in &report.purge_ballots {
+        // Older polls may predate results snapshots; store one before the ballots are gone
+        if let Err(e) = ensure_results_snapshot(database, &candidate.poll_id).await {
+            error!(
+                "Retention: skipping ballot purge for poll {}, could not store results snapshot: {}",
+                candidate.poll_id, e
+            );
+            continue;
+        }
+        match database.purge_poll_ballots(&candidate.poll_id).await {
+            Ok(rows) => info!("Retention: purged {} vote rows from poll {}", rows, candidate.poll_id),
+            Err(e) => error!("Retention: failed to purge ballots for poll {}: {}", candidate.poll_id, e),
+        }
+    }
+
+    for candidate in &report.anonymize {
+        match database.anonymize_poll_voters(&candidate.poll_id).await {
+            Ok(rows) => info!("Retention: anonymized {} vote rows in poll {}", rows, candidate.poll_id),
+            Err(e) => error!("Retention: failed to anonymize poll {}: {}", candidate.poll_id, e),
+        }
+    }
+}
+
+async fn ensure_results_snapshot(
+    database: &Database,
+    poll_id: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if database.get_results_snapshot(poll_id).await?.is_some() {
+        return Ok(());
+    }
+    let poll = database.get_poll(poll_id).await?;
+    let votes = database.get_poll_votes(poll_id).await?;
+    let results = calculate_poll_results(&poll, &votes);
+    database.save_results_snapshot(poll_id, &results).await
+}
+
+// Plain-text summary used for dry-run logs and the `/pollretention preview` command
+pub fn format_retention_report(report: &RetentionReport) -> String {
+    report_text(report, true)
+}
+
+// The same report as plain text for the log, without Discord markdown or timestamp tags
+fn format_retention_log(report: &RetentionReport) -> String {
+    report_text(report, false)
+}
+
+fn report_text(report: &RetentionReport, markdown: bool) -> String {
+    if report.is_empty() {
+        return "Nothing to remove.".to_string();
+    }
+
+    let mut text = String::new();
+    let sections = [
+        ("Delete poll", &report.delete),
+        ("Delete ballots, keep results", &report.purge_ballots),
+        ("Anonymize voter IDs", &report.anonymize),
+    ];
+    for (title, candidates) in sections {
+        if candidates.is_empty() {
+            continue;
+        }
+        if markdown {
+            text.push_str(&format!("**{}** ({} polls)\n", title, candidates.len()));
+        } else {
+            text.push_str(&format!("{} ({} polls):\n", title, candidates.len()));
+        }
+        for c in candidates {
+            let line = if markdown {
+                format!(
+                    "• `{}` {} (closed <t:{}:d>, {} ballots)\n",
+                    c.poll_id,
+                    c.question,
+                    c.closed_at.timestamp(),
+                    c.ballot_count
+                )
+            } else {
+                format!(
+                    "  - {} guild {} (closed {}, {} ballots): {}\n",
+                    c.poll_id,
+                    c.guild_id,
+                    c.closed_at.format("%Y-%m-%d"),
+                    c.ballot_count,
+                    c.question.replace(['\n', '\r'], " ")
+                )
+            };
+            text.push_str(&line);
+        }
+        text.push('\n');
+    }
+    text
+}
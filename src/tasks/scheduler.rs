@@ -0,0 +1,231 @@
+use crate::commands::poll::{end_poll_logic, open_poll_logic};
+use crate::db::Database;
+use crate::models::{CloseReason, Poll, PollStatus, SCHEDULER_ACTOR};
+use serenity::prelude::*;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration as StdDuration;
+use chrono::{DateTime, Utc};
+use log::{debug, info, warn, error};
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+// Safety net: reload every deadline from the database this often, picking up polls changed by
+// another process and anything a missed update left behind
+const RECONCILE_INTERVAL_SECONDS: u64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum TimerKind {
+    Open, // Pending poll reaches `starts_at`
+    End,  // Open poll reaches `ends_at`
+}
+
+#[derive(Default)]
+struct Timers {
+    queue: BTreeSet<(DateTime<Utc>, String, TimerKind)>,
+    by_poll: HashMap<(String, TimerKind), DateTime<Utc>>,
+    // Polls set while a reconcile query runs; their timers are newer than what it read
+    touched: Option<HashSet<String>>,
+}
+
+impl Timers {
+    fn set(&mut self, poll_id: &str, kind: TimerKind, at: Option<DateTime<Utc>>) {
+        if let Some(touched) = self.touched.as_mut() {
+            touched.insert(poll_id.to_string());
+        }
+        if let Some(previous) = self.by_poll.remove(&(poll_id.to_string(), kind)) {
+            self.queue.remove(&(previous, poll_id.to_string(), kind));
+        }
+        if let Some(at) = at {
+            self.queue.insert((at, poll_id.to_string(), kind));
+            self.by_poll.insert((poll_id.to_string(), kind), at);
+        }
+    }
+
+    fn next(&self) -> Option<DateTime<Utc>> {
+        self.queue.first().map(|(at, _, _)| *at)
+    }
+
+    fn pop_due(&mut self, now: DateTime<Utc>) -> Vec<(String, TimerKind)> {
+        let mut due = Vec::new();
+        while let Some((at, _, _)) = self.queue.first() {
+            if *at > now {
+                break;
+            }
+            let (_, poll_id, kind) = self.queue.pop_first().expect("queue is not empty");
+            self.by_poll.remove(&(poll_id.clone(), kind));
+            due.push((poll_id, kind));
+        }
+        due
+    }
+}
+
+// Opens and ends polls at their exact scheduled times. Command handlers keep it up to date
+// through `sync_poll` whenever a poll is created, edited, opened or ended.
+#[derive(Default)]
+pub struct PollScheduler {
+    timers: Mutex<Timers>,
+    wake: Notify,
+    running: AtomicBool,
+}
+
+pub struct PollSchedulerKey;
+
+impl TypeMapKey for PollSchedulerKey {
+    type Value = Arc<PollScheduler>;
+}
+
+// Update the timers for a poll after it changed; a no-op if the scheduler isn't installed
+pub async fn sync_poll(ctx: &Context, poll: &Poll) {
+    let scheduler = ctx.data.read().await.get::<PollSchedulerKey>().cloned();
+    if let Some(scheduler) = scheduler {
+        scheduler.set_poll_times(&poll.id, poll.status, poll.starts_at, poll.ends_at);
+    }
+}
+
+impl PollScheduler {
+    pub fn set_poll_times(
+        &self,
+        poll_id: &str,
+        status: PollStatus,
+        starts_at: Option<DateTime<Utc>>,
+        ends_at: Option<DateTime<Utc>>,
+    ) {
+        let (open_at, end_at) = match status {
+            // A scheduled poll's end timer is set when it opens
+            PollStatus::Scheduled => (Some(starts_at.unwrap_or_else(Utc::now)), None),
+            // Pausing doesn't stop the clock
+            PollStatus::Open | PollStatus::Paused => (None, ends_at),
+            PollStatus::Draft | PollStatus::Closed | PollStatus::Archived => (None, None),
+        };
+
+        let mut timers = self.timers.lock().expect("scheduler lock poisoned");
+        let previous_next = timers.next();
+        timers.set(poll_id, TimerKind::Open, open_at);
+        timers.set(poll_id, TimerKind::End, end_at);
+        let next = timers.next();
+        drop(timers);
+
+        // Only the runner's sleep deadline matters; wake it if that moved
+        if next != previous_next {
+            self.wake.notify_one();
+        }
+    }
+
+    // Load every deadline at startup, then fire timers as they come due. Calling it again
+    // (Discord can send `ready` more than once) does nothing.
+    pub async fn run(self: Arc<Self>, database: Arc<Database>, ctx: Context) {
+        if self.running.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        info!("Starting poll scheduler");
+
+        let mut next_reconcile = Instant::now();
+        loop {
+            if Instant::now() >= next_reconcile {
+                self.reconcile(&database).await;
+                next_reconcile = Instant::now() + StdDuration::from_secs(RECONCILE_INTERVAL_SECONDS);
+            }
+
+            let now = Utc::now();
+            let due = self.timers.lock().expect("scheduler lock poisoned").pop_due(now);
+            for (poll_id, kind) in due {
+                let db_clone = Arc::clone(&database);
+                let ctx_clone = ctx.clone();
+                let scheduler = Arc::clone(&self);
+                tokio::spawn(async move {
+                    scheduler.fire(&db_clone, &ctx_clone, &poll_id, kind).await;
+                });
+            }
+
+            let next = self.timers.lock().expect("scheduler lock poisoned").next();
+            let wake_at = match next.and_then(|at| (at - Utc::now()).to_std().ok()) {
+                Some(until_next) => (Instant::now() + until_next).min(next_reconcile),
+                None if next.is_some() => Instant::now(), // Already due
+                None => next_reconcile,
+            };
+            tokio::select! {
+                _ = tokio::time::sleep_until(wake_at) => {}
+                _ = self.wake.notified() => {}
+            }
+        }
+    }
+
+    // Replace the in-memory timers with what the database says, except for polls that were
+    // synced while the query ran: those keep the timers they were given
+    async fn reconcile(&self, database: &Database) {
+        self.timers.lock().expect("scheduler lock poisoned").touched = Some(HashSet::new());
+        let polls = database.get_scheduled_polls().await;
+
+        let mut current = self.timers.lock().expect("scheduler lock poisoned");
+        let touched = current.touched.take().unwrap_or_default();
+        let polls = match polls {
+            Ok(polls) => polls,
+            Err(e) => {
+                error!("Failed to load poll deadlines for the scheduler: {}", e);
+                return;
+            }
+        };
+        debug!("Scheduler reconciled {} scheduled, open or paused polls", polls.len());
+
+        let mut timers = Timers::default();
+        for (poll_id, status, starts_at, ends_at) in polls {
+            if touched.contains(&poll_id) {
+                continue;
+            }
+            if status == PollStatus::Scheduled {
+                timers.set(&poll_id, TimerKind::Open, Some(starts_at.unwrap_or_else(Utc::now)));
+            } else {
+                timers.set(&poll_id, TimerKind::End, ends_at);
+            }
+        }
+        for ((poll_id, kind), at) in &current.by_poll {
+            if touched.contains(poll_id) {
+                timers.set(poll_id, *kind, Some(*at));
+            }
+        }
+        *current = timers;
+    }
+
+    async fn fire(&self, database: &Database, ctx: &Context, poll_id: &str, kind: TimerKind) {
+        // The timer may be stale (poll edited or ended elsewhere), so check the stored poll first
+        let poll = match database.get_poll(poll_id).await {
+            Ok(poll) => poll,
+            Err(e) => {
+                warn!("Scheduled poll {} could not be loaded, dropping its timer: {}", poll_id, e);
+                return;
+            }
+        };
+        let now = Utc::now();
+
+        match kind {
+            TimerKind::Open if poll.status == PollStatus::Scheduled && poll.starts_at.is_none_or(|t| t <= now) => {
+                info!("Opening scheduled poll {}", poll_id);
+                if let Err(e) = open_poll_logic(database, ctx, poll_id).await {
+                    error!("Error opening scheduled poll {}: {}", poll_id, e);
+                }
+            }
+            TimerKind::End
+                if matches!(poll.status, PollStatus::Open | PollStatus::Paused) && poll.ends_at.is_some_and(|t| t <= now) =>
+            {
+                info!("Ending expired poll {}", poll_id);
+                if let Err(e) = end_poll_logic(
+                    database,
+                    ctx,
+                    poll_id,
+                    SCHEDULER_ACTOR,
+                    CloseReason::Expired,
+                )
+                .await
+                {
+                    error!("Error ending expired poll {}: {}", poll_id, e);
+                }
+            }
+            _ => {
+                debug!("Timer for poll {} is stale, rescheduling from stored state", poll_id);
+                self.set_poll_times(&poll.id, poll.status, poll.starts_at, poll.ends_at);
+            }
+        }
+    }
+}